@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{FieldElement, PartialWitnessGenerator, ProofSystemCompiler};
+use acvm_backend_barretenberg::test_circuits::{self, TestCircuit};
+use acvm_backend_barretenberg::Barretenberg;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn solve(bb: &Barretenberg, test_circuit: &TestCircuit) -> BTreeMap<Witness, FieldElement> {
+    let mut witness = test_circuit.initial_witness.clone();
+    bb.solve(
+        &mut witness,
+        &mut Blocks::default(),
+        test_circuit.circuit.opcodes.clone(),
+    )
+    .expect("test circuits should be solvable");
+    witness
+}
+
+fn bench_circuit(c: &mut Criterion, name: &str, parameter: usize, test_circuit: TestCircuit) {
+    let bb = Barretenberg::default();
+    let circuit = &test_circuit.circuit;
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+
+    group.bench_function(BenchmarkId::new("solve", parameter), |b| {
+        b.iter(|| solve(&bb, &test_circuit))
+    });
+
+    group.bench_function(BenchmarkId::new("preprocess", parameter), |b| {
+        b.iter(|| bb.preprocess(circuit).unwrap())
+    });
+
+    let (proving_key, verification_key) = bb.preprocess(circuit).unwrap();
+    let witness = solve(&bb, &test_circuit);
+    group.bench_function(BenchmarkId::new("prove", parameter), |b| {
+        b.iter(|| {
+            bb.prove_with_pk(circuit, witness.clone(), &proving_key)
+                .unwrap()
+        })
+    });
+
+    let proof = bb
+        .prove_with_pk(circuit, witness.clone(), &proving_key)
+        .unwrap();
+    let public_inputs: BTreeMap<Witness, FieldElement> = circuit
+        .public_inputs()
+        .indices()
+        .into_iter()
+        .map(|index| (Witness(index), witness[&Witness(index)]))
+        .collect();
+    group.bench_function(BenchmarkId::new("verify", parameter), |b| {
+        b.iter(|| {
+            assert!(bb
+                .verify_with_vk(&proof, public_inputs.clone(), circuit, &verification_key)
+                .unwrap())
+        })
+    });
+
+    group.finish();
+}
+
+fn pedersen(c: &mut Criterion) {
+    for num_hashes in [1, 16, 64] {
+        bench_circuit(
+            c,
+            "pedersen",
+            num_hashes,
+            test_circuits::pedersen_hashes(num_hashes),
+        );
+    }
+}
+
+fn sha256(c: &mut Criterion) {
+    for chain_length in [1, 4, 16] {
+        bench_circuit(
+            c,
+            "sha256_chain",
+            chain_length,
+            test_circuits::sha256_chain(chain_length),
+        );
+    }
+}
+
+fn merkle(c: &mut Criterion) {
+    bench_circuit(
+        c,
+        "merkle_membership",
+        32,
+        test_circuits::merkle_membership(32),
+    );
+}
+
+fn arithmetic(c: &mut Criterion) {
+    for log_num_gates in [10, 14, 16] {
+        bench_circuit(
+            c,
+            "arithmetic_gates",
+            1 << log_num_gates,
+            test_circuits::arithmetic_gates(log_num_gates),
+        );
+    }
+}
+
+criterion_group!(benches, pedersen, sha256, merkle, arithmetic);
+criterion_main!(benches);
@@ -8,19 +8,23 @@ use std::env;
 // }
 
 fn main() -> Result<(), String> {
+    // Without any `rerun-if` directives, Cargo reruns this script whenever any file in the package changes.
+    println!("cargo:rerun-if-changed=build.rs");
+
     let native_backend = env::var("CARGO_FEATURE_NATIVE").is_ok();
 
     if native_backend {
         Ok(())
     } else {
+        println!("cargo:rerun-if-env-changed=BARRETENBERG_BIN_DIR");
         match env::var("BARRETENBERG_BIN_DIR") {
             Ok(bindir) => {
-                println!("cargo:rustc-env=BARRETENBERG_BIN_DIR={bindir}");
+                use_bindir(&bindir);
                 Ok(())
             }
             Err(_) => {
                 if let Ok(bindir) = pkg_config::get_variable("barretenberg", "bindir") {
-                    println!("cargo:rustc-env=BARRETENBERG_BIN_DIR={bindir}");
+                    use_bindir(&bindir);
                     Ok(())
                 } else {
                     Err("Unable to locate barretenberg.wasm - Please set the BARRETENBERG_BIN_DIR env var to the directory where it exists".into())
@@ -29,3 +33,9 @@ fn main() -> Result<(), String> {
         }
     }
 }
+
+fn use_bindir(bindir: &str) {
+    println!("cargo:rustc-env=BARRETENBERG_BIN_DIR={bindir}");
+    // The wasm is embedded into the crate, so it must be rebuilt when a different wasm is installed.
+    println!("cargo:rerun-if-changed={bindir}/barretenberg.wasm");
+}
@@ -0,0 +1,8 @@
+#![no_main]
+
+use acvm_backend_barretenberg::{read_circuit_checked, CircuitLimits};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = read_circuit_checked(data, &CircuitLimits::default());
+});
@@ -0,0 +1,20 @@
+#![no_main]
+
+use acvm::ProofSystemCompiler;
+use acvm_backend_barretenberg::{read_circuit_checked, Barretenberg, CircuitLimits};
+use libfuzzer_sys::fuzz_target;
+
+// Keep circuits small so that the fuzzer spends its time exploring the serializer
+// rather than building large constraint systems.
+const LIMITS: CircuitLimits = CircuitLimits {
+    max_compressed_bytes: 64 * 1024,
+    max_decompressed_bytes: 1024 * 1024,
+    max_opcodes: 1024,
+    max_witness_index: 1 << 16,
+};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(circuit) = read_circuit_checked(data, &LIMITS) {
+        let _ = Barretenberg::default().get_exact_circuit_size(&circuit);
+    }
+});
@@ -0,0 +1,394 @@
+//! Encodes a program's inputs into the initial witness expected by
+//! [`PartialWitnessGenerator::solve`][acvm::PartialWitnessGenerator::solve], using the ABI which Noir emits
+//! alongside the circuit.
+//!
+//! This lets callers which don't use Noir's own tooling prove a program from inputs written in the same JSON or
+//! `Prover.toml` format accepted by `nargo`, the latter when the `toml` feature is enabled. Fields and integers
+//! may be given as numbers, decimal strings or `0x`-prefixed hex strings.
+
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::field::{field_from_be_bytes, hex_to_bytes};
+use crate::{BackendError, Error};
+
+/// The parts of a Noir ABI needed to lay out the initial witness.
+#[derive(Debug, Deserialize)]
+struct Abi {
+    parameters: Vec<AbiParameter>,
+    param_witnesses: BTreeMap<String, Vec<u32>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AbiParameter {
+    name: String,
+    #[serde(rename = "type")]
+    typ: AbiType,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum AbiType {
+    Field,
+    Boolean,
+    Integer {
+        sign: Sign,
+        width: u32,
+    },
+    Array {
+        length: usize,
+        #[serde(rename = "type")]
+        typ: Box<AbiType>,
+    },
+    String {
+        length: usize,
+    },
+    Struct {
+        fields: StructFields,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Sign {
+    Signed,
+    Unsigned,
+}
+
+/// Struct fields are listed in declaration order by newer compilers but keyed by name by older ones.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StructFields {
+    Ordered(Vec<(String, AbiType)>),
+    Named(BTreeMap<String, AbiType>),
+}
+
+/// Returns the initial witness for a program with the ABI `abi_json`, given its inputs as a JSON object keyed by
+/// parameter name.
+pub fn initial_witness_from_json(
+    abi_json: &str,
+    inputs_json: &str,
+) -> Result<BTreeMap<Witness, FieldElement>, BackendError> {
+    let inputs: BTreeMap<String, Value> = serde_json::from_str(inputs_json)
+        .map_err(|err| Error::AbiInput(format!("inputs are not a JSON object ({err})")))?;
+    initial_witness(abi_json, inputs)
+}
+
+/// Returns the initial witness for a program with the ABI `abi_json`, given its inputs in the TOML format of a
+/// `Prover.toml` file.
+#[cfg(feature = "toml")]
+pub fn initial_witness_from_toml(
+    abi_json: &str,
+    inputs_toml: &str,
+) -> Result<BTreeMap<Witness, FieldElement>, BackendError> {
+    let inputs: BTreeMap<String, Value> = toml::from_str(inputs_toml)
+        .map_err(|err| Error::AbiInput(format!("inputs are not valid TOML ({err})")))?;
+    initial_witness(abi_json, inputs)
+}
+
+fn initial_witness(
+    abi_json: &str,
+    mut inputs: BTreeMap<String, Value>,
+) -> Result<BTreeMap<Witness, FieldElement>, BackendError> {
+    let abi: Abi =
+        serde_json::from_str(abi_json).map_err(|err| Error::MalformedAbi(err.to_string()))?;
+
+    let mut initial_witness = BTreeMap::new();
+    for parameter in &abi.parameters {
+        let value = inputs
+            .remove(&parameter.name)
+            .ok_or_else(|| Error::AbiInput(format!("missing input {}", parameter.name)))?;
+        let mut fields = Vec::new();
+        encode_value(&value, &parameter.typ, &parameter.name, &mut fields)?;
+
+        let witnesses = abi.param_witnesses.get(&parameter.name).ok_or_else(|| {
+            Error::MalformedAbi(format!("no witnesses for parameter {}", parameter.name))
+        })?;
+        if witnesses.len() != fields.len() {
+            return Err(Error::MalformedAbi(format!(
+                "parameter {} has {} witnesses but its type holds {} values",
+                parameter.name,
+                witnesses.len(),
+                fields.len()
+            ))
+            .into());
+        }
+        initial_witness.extend(witnesses.iter().map(|index| Witness(*index)).zip(fields));
+    }
+
+    // Inputs files may include the program's expected return value, which isn't part of the initial witness.
+    inputs.remove("return");
+    if let Some(name) = inputs.keys().next() {
+        return Err(Error::AbiInput(format!("unexpected input {name}")).into());
+    }
+    Ok(initial_witness)
+}
+
+/// Appends the field elements encoding `value` as an `abi_type` to `fields`, in the order they're assigned to
+/// the parameter's witnesses. `path` names the value in errors.
+fn encode_value(
+    value: &Value,
+    abi_type: &AbiType,
+    path: &str,
+    fields: &mut Vec<FieldElement>,
+) -> Result<(), Error> {
+    let mismatch =
+        |expected: &str| Error::AbiInput(format!("{path} should be {expected} but was {value}"));
+
+    match abi_type {
+        AbiType::Field => {
+            fields.push(parse_field(value).ok_or_else(|| mismatch("a field element"))?)
+        }
+        AbiType::Boolean => {
+            let boolean = value.as_bool().ok_or_else(|| mismatch("a boolean"))?;
+            fields.push(FieldElement::from(boolean as u128));
+        }
+        AbiType::Integer {
+            sign: Sign::Unsigned,
+            width,
+        } => {
+            let field = parse_field(value)
+                .filter(|field| field.num_bits() <= *width)
+                .ok_or_else(|| mismatch(&format!("an unsigned {width} bit integer")))?;
+            fields.push(field);
+        }
+        AbiType::Integer {
+            sign: Sign::Signed,
+            width,
+        } => {
+            let integer = parse_signed(value)
+                .filter(|_| (1..=128).contains(width))
+                .filter(|integer| {
+                    // Every `i128` fits within 128 bits, and shifting by 127 would overflow.
+                    *width == 128 || {
+                        let bound = 1_i128 << (width - 1);
+                        (-bound..bound).contains(integer)
+                    }
+                })
+                .ok_or_else(|| mismatch(&format!("a signed {width} bit integer")))?;
+            // Negative values are encoded in two's complement, which for an `i128` is its bit pattern truncated
+            // to `width` bits.
+            let mask = u128::MAX >> (128 - width);
+            fields.push(FieldElement::from(integer as u128 & mask));
+        }
+        AbiType::Array { length, typ } => {
+            let elements = value
+                .as_array()
+                .filter(|elements| elements.len() == *length)
+                .ok_or_else(|| mismatch(&format!("an array of {length} elements")))?;
+            for (index, element) in elements.iter().enumerate() {
+                encode_value(element, typ, &format!("{path}[{index}]"), fields)?;
+            }
+        }
+        AbiType::String { length } => {
+            let string = value
+                .as_str()
+                .filter(|string| string.len() == *length)
+                .ok_or_else(|| mismatch(&format!("a string of {length} bytes")))?;
+            fields.extend(string.bytes().map(|byte| FieldElement::from(byte as u128)));
+        }
+        AbiType::Struct {
+            fields: struct_fields,
+        } => {
+            let members = value.as_object().ok_or_else(|| mismatch("a struct"))?;
+            let struct_fields: Vec<(&String, &AbiType)> = match struct_fields {
+                StructFields::Ordered(struct_fields) => struct_fields
+                    .iter()
+                    .map(|(name, typ)| (name, typ))
+                    .collect(),
+                StructFields::Named(struct_fields) => struct_fields.iter().collect(),
+            };
+            if members.len() != struct_fields.len() {
+                return Err(mismatch(&format!(
+                    "a struct with {} fields",
+                    struct_fields.len()
+                )));
+            }
+            for (name, typ) in struct_fields {
+                let member = members
+                    .get(name)
+                    .ok_or_else(|| Error::AbiInput(format!("missing input {path}.{name}")))?;
+                encode_value(member, typ, &format!("{path}.{name}"), fields)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses a field element from a non-negative integer, a decimal string or a `0x`-prefixed hex string.
+/// Negative values are accepted and wrap around the field modulus as they do in Noir.
+///
+/// Strings must hold at least one digit and, ignoring any sign, a value less than the field modulus, so that no
+/// two distinct inputs are silently reduced to the same field element.
+fn parse_field(value: &Value) -> Option<FieldElement> {
+    match value {
+        Value::Number(number) => {
+            if let Some(unsigned) = number.as_u64() {
+                Some(FieldElement::from(unsigned as u128))
+            } else {
+                number
+                    .as_i64()
+                    .map(|signed| -FieldElement::from(signed.unsigned_abs() as u128))
+            }
+        }
+        Value::String(string) => {
+            if let Some(hex) = string.strip_prefix("0x") {
+                return field_from_be_bytes(&hex_to_bytes(hex)?).ok();
+            }
+            let (negative, digits) = match string.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, string.as_str()),
+            };
+            let field = field_from_be_bytes(&decimal_to_bytes(digits)?).ok()?;
+            Some(if negative { -field } else { field })
+        }
+        _ => None,
+    }
+}
+
+/// Converts a string of decimal digits into big-endian bytes.
+///
+/// Returns `None` if `digits` is empty or contains anything other than decimal digits.
+fn decimal_to_bytes(digits: &str) -> Option<Vec<u8>> {
+    if digits.is_empty() {
+        return None;
+    }
+    let mut bytes: Vec<u8> = Vec::new();
+    for digit in digits.chars() {
+        // Multiply by ten and add the digit, least significant byte first.
+        let mut carry = digit.to_digit(10)?;
+        for byte in bytes.iter_mut().rev() {
+            let value = *byte as u32 * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        while carry > 0 {
+            bytes.insert(0, carry as u8);
+            carry >>= 8;
+        }
+    }
+    Some(bytes)
+}
+
+/// Parses an integer from a number or a decimal string.
+fn parse_signed(value: &Value) -> Option<i128> {
+    match value {
+        Value::Number(number) => number.as_i64().map(i128::from),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABI: &str = r#"{
+        "parameters": [
+            {"name": "x", "type": {"kind": "field"}, "visibility": "private"},
+            {"name": "bytes", "type": {"kind": "array", "length": 2, "type": {"kind": "integer", "sign": "unsigned", "width": 8}}, "visibility": "private"},
+            {"name": "point", "type": {"kind": "struct", "fields": [["y", {"kind": "integer", "sign": "signed", "width": 8}], ["valid", {"kind": "boolean"}]]}, "visibility": "public"}
+        ],
+        "param_witnesses": {"x": [1], "bytes": [2, 3], "point": [4, 5]},
+        "return_type": null,
+        "return_witnesses": []
+    }"#;
+
+    fn expected_witness() -> BTreeMap<Witness, FieldElement> {
+        BTreeMap::from([
+            (Witness(1), FieldElement::from(255_u128)),
+            (Witness(2), FieldElement::from(1_u128)),
+            (Witness(3), FieldElement::from(200_u128)),
+            (Witness(4), FieldElement::from(0xfe_u128)),
+            (Witness(5), FieldElement::one()),
+        ])
+    }
+
+    #[test]
+    fn encodes_json_inputs() -> Result<(), BackendError> {
+        let json = r#"{"x": "0xff", "bytes": [1, "200"], "point": {"y": -2, "valid": true}}"#;
+
+        assert_eq!(initial_witness_from_json(ABI, json)?, expected_witness());
+        Ok(())
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn encodes_toml_inputs() -> Result<(), BackendError> {
+        let toml = r#"
+            x = "255"
+            bytes = [1, 200]
+            return = "0x00"
+
+            [point]
+            y = "-2"
+            valid = true
+        "#;
+
+        assert_eq!(initial_witness_from_toml(ABI, toml)?, expected_witness());
+        Ok(())
+    }
+
+    #[test]
+    fn parses_canonical_field_elements() {
+        let modulus_minus_one =
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616";
+        assert_eq!(
+            parse_field(&Value::from(modulus_minus_one)),
+            Some(-FieldElement::one())
+        );
+        assert_eq!(
+            parse_field(&Value::from("0x0001")),
+            Some(FieldElement::one())
+        );
+        assert_eq!(
+            parse_field(&Value::from("-300")),
+            Some(-FieldElement::from(300_u128))
+        );
+        assert_eq!(parse_field(&Value::from("-")), None);
+    }
+
+    #[test]
+    fn encodes_128_bit_signed_integers() -> Result<(), Error> {
+        let typ = AbiType::Integer {
+            sign: Sign::Signed,
+            width: 128,
+        };
+        let mut fields = Vec::new();
+        encode_value(&Value::from("-1"), &typ, "x", &mut fields)?;
+        encode_value(&Value::from(i128::MAX.to_string()), &typ, "x", &mut fields)?;
+
+        assert_eq!(
+            fields,
+            vec![
+                FieldElement::from(u128::MAX),
+                FieldElement::from(i128::MAX as u128)
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_inputs_which_do_not_match_the_abi() {
+        for inputs in [
+            r#"{"x": 1, "bytes": [1, 256], "point": {"y": 0, "valid": true}}"#,
+            r#"{"x": 1, "bytes": [1], "point": {"y": 0, "valid": true}}"#,
+            r#"{"x": 1, "bytes": [1, 2], "point": {"y": 128, "valid": true}}"#,
+            r#"{"x": 1, "bytes": [1, 2], "point": {"y": 0}}"#,
+            r#"{"x": 1, "bytes": [1, 2]}"#,
+            r#"{"x": 1, "bytes": [1, 2], "point": {"y": 0, "valid": true}, "z": 3}"#,
+            r#"{"x": "0xzz", "bytes": [1, 2], "point": {"y": 0, "valid": true}}"#,
+            r#"{"x": "0x", "bytes": [1, 2], "point": {"y": 0, "valid": true}}"#,
+            r#"{"x": "", "bytes": [1, 2], "point": {"y": 0, "valid": true}}"#,
+            // One more than the field modulus, which must not be reduced to 1.
+            r#"{"x": 1, "bytes": ["0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000002", 2], "point": {"y": 0, "valid": true}}"#,
+            r#"{"x": "21888242871839275222246405745257275088548364400416034343698204186575808495617", "bytes": [1, 2], "point": {"y": 0, "valid": true}}"#,
+        ] {
+            assert!(initial_witness_from_json(ABI, inputs).is_err(), "{inputs}");
+        }
+    }
+}
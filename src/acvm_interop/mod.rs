@@ -4,4 +4,13 @@ mod proof_system;
 mod pwg;
 mod smart_contract;
 
+pub(crate) use proof_system::black_box_function_supported;
+pub(crate) use pwg::referenced_witnesses;
+#[cfg(any(feature = "native", feature = "wasm"))]
+pub use pwg::HttpOracleResolver;
+pub use pwg::{
+    ConstraintViolation, OpcodeTiming, OracleResolver, OracleSolveError, SolveProfile, SolveTrace,
+    SolverSnapshot, TraceEntry, TraceOutcome, TracedSolveError, WitnessSolver,
+};
+
 impl acvm::Backend for Barretenberg {}
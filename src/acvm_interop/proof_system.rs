@@ -3,9 +3,10 @@ use acvm::FieldElement;
 use acvm::{Language, ProofSystemCompiler};
 use std::collections::BTreeMap;
 
-use crate::barretenberg_structures::Assignments;
 use crate::composer::Composer;
-use crate::{BackendError, Barretenberg};
+use crate::proof::strip_public_inputs;
+use crate::secret::zeroize_field_elements;
+use crate::{extract_public_inputs, BackendError, Barretenberg, Proof, ProvingKey, WitnessVector};
 
 impl ProofSystemCompiler for Barretenberg {
     type Error = BackendError;
@@ -17,31 +18,16 @@ impl ProofSystemCompiler for Barretenberg {
     fn get_exact_circuit_size(&self, circuit: &Circuit) -> Result<u32, Self::Error> {
         Ok(Composer::get_exact_circuit_size(
             self,
-            &circuit.try_into()?,
+            &self.constraint_system(circuit)?,
         )?)
     }
 
     fn black_box_function_supported(&self, opcode: &BlackBoxFunc) -> bool {
-        match opcode {
-            BlackBoxFunc::AND
-            | BlackBoxFunc::XOR
-            | BlackBoxFunc::RANGE
-            | BlackBoxFunc::SHA256
-            | BlackBoxFunc::Blake2s
-            | BlackBoxFunc::Keccak256
-            | BlackBoxFunc::ComputeMerkleRoot
-            | BlackBoxFunc::SchnorrVerify
-            | BlackBoxFunc::Pedersen
-            | BlackBoxFunc::HashToField128Security
-            | BlackBoxFunc::EcdsaSecp256k1
-            | BlackBoxFunc::FixedBaseScalarMul => true,
-
-            BlackBoxFunc::AES => false,
-        }
+        black_box_function_supported(opcode)
     }
 
     fn preprocess(&self, circuit: &Circuit) -> Result<(Vec<u8>, Vec<u8>), Self::Error> {
-        let constraint_system = &circuit.try_into()?;
+        let constraint_system = &self.constraint_system(circuit)?;
 
         let proving_key = self.compute_proving_key(constraint_system)?;
         let verification_key = self.compute_verification_key(constraint_system, &proving_key)?;
@@ -52,12 +38,15 @@ impl ProofSystemCompiler for Barretenberg {
     fn prove_with_pk(
         &self,
         circuit: &Circuit,
-        witness_values: BTreeMap<Witness, FieldElement>,
+        mut witness_values: BTreeMap<Witness, FieldElement>,
         proving_key: &[u8],
     ) -> Result<Vec<u8>, Self::Error> {
-        let assignments = flatten_witness_map(circuit, witness_values);
+        // Witnesses are looked up by index for every slot of the flattened assignment, which is far cheaper
+        // against a dense vector than a `BTreeMap`.
+        let witness_vector = WitnessVector::from_witness_map(circuit, &witness_values);
+        zeroize_field_elements(witness_values.values_mut());
 
-        Ok(self.create_proof_with_pk(&circuit.try_into()?, assignments, proving_key)?)
+        self.prove_witness_vector(circuit, witness_vector, proving_key)
     }
 
     fn verify_with_vk(
@@ -71,9 +60,17 @@ impl ProofSystemCompiler for Barretenberg {
         // Witness values should be ordered by their index but we skip over any indices without an assignment.
         let flattened_public_inputs: Vec<FieldElement> = public_inputs.into_values().collect();
 
+        // Proofs from other Barretenberg tooling keep their public inputs at the head of the proof.
+        // We accept these as well as proofs produced by `prove_with_pk`, which have them removed.
+        let num_public_inputs = circuit.public_inputs().indices().len();
+        let Some(proof) = strip_public_inputs(proof, num_public_inputs, &flattened_public_inputs)?
+        else {
+            return Ok(false);
+        };
+
         Ok(Composer::verify_with_vk(
             self,
-            &circuit.try_into()?,
+            &self.constraint_system(circuit)?,
             proof,
             flattened_public_inputs.into(),
             verification_key,
@@ -81,23 +78,112 @@ impl ProofSystemCompiler for Barretenberg {
     }
 }
 
-/// Flatten a witness map into a vector of witness assignments.
-fn flatten_witness_map(
-    circuit: &Circuit,
-    witness_values: BTreeMap<Witness, FieldElement>,
-) -> Assignments {
-    let num_witnesses = circuit.num_vars();
-
-    // Note: The witnesses are sorted via their witness index
-    // witness_values may not have all the witness indexes, e.g for unused witness which are not solved by the solver
-    let witness_assignments: Vec<FieldElement> = (1..num_witnesses)
-        .map(|witness_index| {
-            // Get the value if it exists. If i does not, then we fill it with the zero value
-            witness_values
-                .get(&Witness(witness_index))
-                .map_or(FieldElement::zero(), |field| *field)
-        })
-        .collect();
-
-    Assignments::from(witness_assignments)
+impl Barretenberg {
+    /// Creates a proof in the same manner as [`ProofSystemCompiler::prove_with_pk`], also returning the values of
+    /// the circuit's public inputs taken from `witness_values`.
+    ///
+    /// The public inputs are ordered by witness index and can be passed unchanged to [`Barretenberg::verify`].
+    /// Fails if any public input is missing from `witness_values`.
+    pub fn prove_with_public_inputs(
+        &self,
+        circuit: &Circuit,
+        witness_values: BTreeMap<Witness, FieldElement>,
+        proving_key: &ProvingKey,
+    ) -> Result<(Proof, BTreeMap<Witness, FieldElement>), BackendError> {
+        let public_inputs = extract_public_inputs(circuit, &witness_values)?;
+        let proof = self.prove_with_pk(circuit, witness_values, proving_key.as_ref())?;
+        Ok((Proof(proof), public_inputs))
+    }
+
+    /// Creates a proof in the same manner as [`ProofSystemCompiler::prove_with_pk`] from a [`WitnessVector`],
+    /// avoiding the cost of building a `BTreeMap` for circuits with very many witnesses.
+    pub fn prove_with_witness_vector(
+        &self,
+        circuit: &Circuit,
+        witness_values: WitnessVector,
+        proving_key: &ProvingKey,
+    ) -> Result<Proof, BackendError> {
+        Ok(Proof(self.prove_witness_vector(
+            circuit,
+            witness_values,
+            proving_key.as_ref(),
+        )?))
+    }
+
+    fn prove_witness_vector(
+        &self,
+        circuit: &Circuit,
+        mut witness_values: WitnessVector,
+        proving_key: &[u8],
+    ) -> Result<Vec<u8>, BackendError> {
+        // Witnesses which weren't solved, e.g. those which are unused, are passed to barretenberg as zero.
+        let assignments = witness_values.to_assignments(circuit.num_vars());
+        witness_values.zeroize();
+
+        Ok(self.create_proof_with_pk(
+            &self.constraint_system(circuit)?,
+            assignments,
+            proving_key,
+        )?)
+    }
+}
+
+pub(crate) fn black_box_function_supported(opcode: &BlackBoxFunc) -> bool {
+    match opcode {
+        BlackBoxFunc::AND
+        | BlackBoxFunc::XOR
+        | BlackBoxFunc::RANGE
+        | BlackBoxFunc::SHA256
+        | BlackBoxFunc::Blake2s
+        | BlackBoxFunc::Keccak256
+        | BlackBoxFunc::ComputeMerkleRoot
+        | BlackBoxFunc::SchnorrVerify
+        | BlackBoxFunc::Pedersen
+        | BlackBoxFunc::HashToField128Security
+        | BlackBoxFunc::EcdsaSecp256k1
+        | BlackBoxFunc::FixedBaseScalarMul => true,
+
+        BlackBoxFunc::AES => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::PublicInputs;
+    use acvm::pwg::block::Blocks;
+    use acvm::PartialWitnessGenerator;
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    #[test]
+    fn returns_public_inputs_used_for_proof() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let mut test_circuit = arithmetic_gates(2);
+        let output = Witness(test_circuit.circuit.current_witness_index);
+        test_circuit.circuit.return_values = PublicInputs(BTreeSet::from([output]));
+        let circuit = test_circuit.circuit;
+
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .unwrap();
+        let (proving_key, verification_key) = bb.preprocess_keys(&circuit)?;
+
+        let (proof, public_inputs) =
+            bb.prove_with_public_inputs(&circuit, witness.clone(), &proving_key)?;
+
+        assert_eq!(public_inputs, BTreeMap::from([(output, witness[&output])]));
+        assert!(bb.verify(&proof, public_inputs, &circuit, &verification_key)?);
+
+        witness.remove(&output);
+        assert!(bb
+            .prove_with_public_inputs(&circuit, witness, &proving_key)
+            .is_err());
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+use acvm::acir::native_types::Witness;
 use acvm::acir::BlackBoxFunc;
-use acvm::acir::{circuit::opcodes::BlackBoxFuncCall, native_types::Witness};
 use acvm::pwg::{hash, logic, range, signature, witness_to_value};
 use acvm::{FieldElement, OpcodeResolution};
 use acvm::{OpcodeResolutionError, PartialWitnessGenerator};
@@ -7,13 +8,31 @@ use acvm::{OpcodeResolutionError, PartialWitnessGenerator};
 use std::collections::BTreeMap;
 
 use crate::pedersen::Pedersen;
+use crate::primitives::blake2s_to_field;
 use crate::scalar_mul::ScalarMul;
 use crate::schnorr::SchnorrSig;
-use crate::Barretenberg;
-
-use blake2::{Blake2s, Digest};
+use crate::secret::zeroize_field_elements;
+use crate::{telemetry, Barretenberg, SecretScalar};
 
+mod check;
 mod merkle;
+mod oracle;
+#[cfg(feature = "parallel-pwg")]
+mod parallel;
+mod profile;
+mod rounds;
+mod solver;
+mod streaming;
+mod trace;
+
+pub use check::ConstraintViolation;
+#[cfg(any(feature = "native", feature = "wasm"))]
+pub use oracle::HttpOracleResolver;
+pub use oracle::{OracleResolver, OracleSolveError};
+pub use profile::{OpcodeTiming, SolveProfile};
+pub use solver::{SolverSnapshot, WitnessSolver};
+pub(crate) use trace::referenced_witnesses;
+pub use trace::{SolveTrace, TraceEntry, TraceOutcome, TracedSolveError};
 
 impl PartialWitnessGenerator for Barretenberg {
     fn solve_black_box_function_call(
@@ -21,16 +40,32 @@ impl PartialWitnessGenerator for Barretenberg {
         initial_witness: &mut BTreeMap<Witness, FieldElement>,
         func_call: &BlackBoxFuncCall,
     ) -> Result<OpcodeResolution, OpcodeResolutionError> {
+        telemetry::black_box_call(func_call.name);
+        // Range constraints report over-width inputs as an unsatisfied constraint themselves.
+        if func_call.name != BlackBoxFunc::RANGE {
+            check_input_widths(initial_witness, func_call)?;
+        }
+        if let Some((_, solve)) = self
+            .options
+            .black_box_overrides
+            .iter()
+            .find(|(func, _)| *func == func_call.name)
+        {
+            return solve(initial_witness, func_call);
+        }
+
         match func_call.name {
-            BlackBoxFunc::SHA256 => hash::sha256(initial_witness, func_call),
-            BlackBoxFunc::Blake2s => hash::blake2s(initial_witness, func_call),
-            BlackBoxFunc::Keccak256 => hash::keccak256(initial_witness, func_call),
-            BlackBoxFunc::EcdsaSecp256k1 => {
-                signature::ecdsa::secp256k1_prehashed(initial_witness, func_call)
-            }
+            BlackBoxFunc::SHA256 => solve_delegated(initial_witness, func_call, hash::sha256),
+            BlackBoxFunc::Blake2s => solve_delegated(initial_witness, func_call, hash::blake2s),
+            BlackBoxFunc::Keccak256 => solve_delegated(initial_witness, func_call, hash::keccak256),
+            BlackBoxFunc::EcdsaSecp256k1 => solve_delegated(
+                initial_witness,
+                func_call,
+                signature::ecdsa::secp256k1_prehashed,
+            ),
 
             BlackBoxFunc::AND | BlackBoxFunc::XOR => {
-                logic::solve_logic_opcode(initial_witness, func_call)
+                solve_delegated(initial_witness, func_call, logic::solve_logic_opcode)
             }
             BlackBoxFunc::RANGE => range::solve_range_opcode(initial_witness, func_call),
             BlackBoxFunc::AES => Err(OpcodeResolutionError::UnsupportedBlackBoxFunc(
@@ -59,7 +94,10 @@ impl PartialWitnessGenerator for Barretenberg {
                     OpcodeResolutionError::BlackBoxFunctionFailed(func_call.name, err.to_string())
                 })?;
 
-                initial_witness.insert(func_call.outputs[0], computed_merkle_root);
+                insert_outputs(
+                    initial_witness,
+                    &[(func_call.outputs[0], computed_merkle_root)],
+                )?;
                 Ok(OpcodeResolution::Solved)
             }
             BlackBoxFunc::SchnorrVerify => {
@@ -137,8 +175,10 @@ impl PartialWitnessGenerator for Barretenberg {
                     message.push(msg_i);
                 }
 
+                // A malformed public key or signature fails to solve, whereas a well formed but invalid
+                // signature solves with a result of zero.
                 let valid_signature = self
-                    .verify_signature(pub_key, sig_s, sig_e, &message)
+                    .verify_signature(&pub_key, &sig_s, &sig_e, &message)
                     .map_err(|err| {
                         OpcodeResolutionError::BlackBoxFunctionFailed(
                             func_call.name,
@@ -155,7 +195,7 @@ impl PartialWitnessGenerator for Barretenberg {
                     FieldElement::zero()
                 };
 
-                initial_witness.insert(func_call.outputs[0], result);
+                insert_outputs(initial_witness, &[(func_call.outputs[0], result)])?;
                 Ok(OpcodeResolution::Solved)
             }
             BlackBoxFunc::Pedersen => {
@@ -169,12 +209,14 @@ impl PartialWitnessGenerator for Barretenberg {
                 let (res_x, res_y) = self.encrypt(scalars).map_err(|err| {
                     OpcodeResolutionError::BlackBoxFunctionFailed(func_call.name, err.to_string())
                 })?;
-                initial_witness.insert(func_call.outputs[0], res_x);
-                initial_witness.insert(func_call.outputs[1], res_y);
+                insert_outputs(
+                    initial_witness,
+                    &[(func_call.outputs[0], res_x), (func_call.outputs[1], res_y)],
+                )?;
                 Ok(OpcodeResolution::Solved)
             }
             BlackBoxFunc::HashToField128Security => {
-                let mut hasher = <Blake2s as blake2::Digest>::new();
+                let mut message = Vec::new();
 
                 // 0. For each input in the vector of inputs, check if we have their witness assignments (Can do this outside of match, since they all have inputs)
                 for input_index in func_call.inputs.iter() {
@@ -185,27 +227,247 @@ impl PartialWitnessGenerator for Barretenberg {
 
                     let bytes = assignment.fetch_nearest_bytes(num_bits as usize);
 
-                    hasher.update(bytes);
+                    message.extend(bytes);
                 }
-                let result = hasher.finalize();
 
-                let reduced_res = FieldElement::from_be_bytes_reduce(&result);
+                let reduced_res = blake2s_to_field(&message);
                 assert_eq!(func_call.outputs.len(), 1);
 
-                initial_witness.insert(func_call.outputs[0], reduced_res);
+                insert_outputs(initial_witness, &[(func_call.outputs[0], reduced_res)])?;
                 Ok(OpcodeResolution::Solved)
             }
             BlackBoxFunc::FixedBaseScalarMul => {
-                let scalar = witness_to_value(initial_witness, func_call.inputs[0].witness)?;
+                let scalar = SecretScalar::from_field(witness_to_value(
+                    initial_witness,
+                    func_call.inputs[0].witness,
+                )?);
 
-                let (pub_x, pub_y) = self.fixed_base(scalar).map_err(|err| {
+                let (pub_x, pub_y) = self.fixed_base(&scalar).map_err(|err| {
                     OpcodeResolutionError::BlackBoxFunctionFailed(func_call.name, err.to_string())
                 })?;
 
-                initial_witness.insert(func_call.outputs[0], pub_x);
-                initial_witness.insert(func_call.outputs[1], pub_y);
+                insert_outputs(
+                    initial_witness,
+                    &[(func_call.outputs[0], pub_x), (func_call.outputs[1], pub_y)],
+                )?;
                 Ok(OpcodeResolution::Solved)
             }
         }
     }
 }
+
+/// Checks that the value of each assigned input to `func_call` fits within the input's declared bit size.
+///
+/// The solvers truncate inputs to their declared size, so an over-width input would otherwise produce outputs which
+/// only fail to satisfy the circuit once a proof is attempted.
+fn check_input_widths(
+    initial_witness: &BTreeMap<Witness, FieldElement>,
+    func_call: &BlackBoxFuncCall,
+) -> Result<(), OpcodeResolutionError> {
+    for FunctionInput { witness, num_bits } in &func_call.inputs {
+        // Unassigned inputs are left for the solver to report as unsolvable.
+        let Some(value) = initial_witness.get(witness) else {
+            continue;
+        };
+        if value.num_bits() > *num_bits {
+            return Err(OpcodeResolutionError::BlackBoxFunctionFailed(
+                func_call.name,
+                format!(
+                    "input _{} is 0x{} which exceeds its declared size of {num_bits} bits",
+                    witness.witness_index(),
+                    value.to_hex()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Assigns each output value to its witness, failing without assigning any of them if a witness has already been
+/// assigned a different value.
+fn insert_outputs(
+    initial_witness: &mut BTreeMap<Witness, FieldElement>,
+    outputs: &[(Witness, FieldElement)],
+) -> Result<(), OpcodeResolutionError> {
+    for (index, (witness, value)) in outputs.iter().enumerate() {
+        let conflicts = initial_witness
+            .get(witness)
+            .into_iter()
+            .chain(
+                outputs[..index]
+                    .iter()
+                    .filter(|(other, _)| other == witness)
+                    .map(|(_, value)| value),
+            )
+            .any(|existing| existing != value);
+        if conflicts {
+            return Err(OpcodeResolutionError::UnsatisfiedConstrain);
+        }
+    }
+    initial_witness.extend(outputs.iter().copied());
+    Ok(())
+}
+
+/// Solves `func_call` with one of acvm's black box solvers.
+///
+/// These overwrite existing assignments to the call's outputs, or write some outputs before finding that another
+/// conflicts, so they're run against a copy of the call's inputs and their outputs are then assigned through
+/// [`insert_outputs`].
+fn solve_delegated(
+    initial_witness: &mut BTreeMap<Witness, FieldElement>,
+    func_call: &BlackBoxFuncCall,
+    solve: impl FnOnce(
+        &mut BTreeMap<Witness, FieldElement>,
+        &BlackBoxFuncCall,
+    ) -> Result<OpcodeResolution, OpcodeResolutionError>,
+) -> Result<OpcodeResolution, OpcodeResolutionError> {
+    let mut call_witness = call_inputs(initial_witness, func_call);
+    let result = solve(&mut call_witness, func_call).and_then(|resolution| {
+        insert_outputs(initial_witness, &call_outputs(&call_witness, func_call))?;
+        Ok(resolution)
+    });
+    zeroize_field_elements(call_witness.values_mut());
+    result
+}
+
+/// Returns the subset of `witness_assignments` which is read by `func_call`.
+fn call_inputs(
+    witness_assignments: &BTreeMap<Witness, FieldElement>,
+    func_call: &BlackBoxFuncCall,
+) -> BTreeMap<Witness, FieldElement> {
+    func_call
+        .inputs
+        .iter()
+        .filter_map(|input| {
+            let value = witness_assignments.get(&input.witness)?;
+            Some((input.witness, *value))
+        })
+        .collect()
+}
+
+/// Returns the assignments to the outputs of `func_call` made in `call_witness`.
+fn call_outputs(
+    call_witness: &BTreeMap<Witness, FieldElement>,
+    func_call: &BlackBoxFuncCall,
+) -> Vec<(Witness, FieldElement)> {
+    func_call
+        .outputs
+        .iter()
+        .filter_map(|witness| Some((*witness, *call_witness.get(witness)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BackendOptions;
+
+    fn and(num_bits: u32) -> BlackBoxFuncCall {
+        BlackBoxFuncCall {
+            name: BlackBoxFunc::AND,
+            inputs: vec![
+                FunctionInput {
+                    witness: Witness(1),
+                    num_bits,
+                },
+                FunctionInput {
+                    witness: Witness(2),
+                    num_bits,
+                },
+            ],
+            outputs: vec![Witness(3)],
+        }
+    }
+
+    #[test]
+    fn rejects_over_width_inputs() {
+        let bb = Barretenberg::new();
+        let mut witness = BTreeMap::from([
+            (Witness(1), FieldElement::from(0x1ff_u128)),
+            (Witness(2), FieldElement::from(0xff_u128)),
+        ]);
+
+        assert!(matches!(
+            bb.solve_black_box_function_call(&mut witness.clone(), &and(8)),
+            Err(OpcodeResolutionError::BlackBoxFunctionFailed(
+                BlackBoxFunc::AND,
+                _
+            ))
+        ));
+        assert_eq!(
+            bb.solve_black_box_function_call(&mut witness, &and(9)),
+            Ok(OpcodeResolution::Solved)
+        );
+        assert_eq!(witness[&Witness(3)], FieldElement::from(0xff_u128));
+    }
+
+    #[test]
+    fn uses_registered_overrides() {
+        let options = BackendOptions::default().with_black_box_override(
+            BlackBoxFunc::AND,
+            |witness: &mut BTreeMap<Witness, FieldElement>, func_call: &BlackBoxFuncCall| {
+                witness.insert(func_call.outputs[0], FieldElement::from(42_u128));
+                Ok(OpcodeResolution::Solved)
+            },
+        );
+        let bb = Barretenberg::with_options(options).unwrap();
+        let mut witness = BTreeMap::from([
+            (Witness(1), FieldElement::from(3_u128)),
+            (Witness(2), FieldElement::from(5_u128)),
+        ]);
+
+        assert_eq!(
+            bb.solve_black_box_function_call(&mut witness, &and(8)),
+            Ok(OpcodeResolution::Solved)
+        );
+        assert_eq!(witness[&Witness(3)], FieldElement::from(42_u128));
+    }
+
+    #[test]
+    fn rejects_conflicting_outputs() {
+        let bb = Barretenberg::new();
+        let fixed_base = BlackBoxFuncCall {
+            name: BlackBoxFunc::FixedBaseScalarMul,
+            inputs: vec![FunctionInput {
+                witness: Witness(1),
+                num_bits: FieldElement::max_num_bits(),
+            }],
+            outputs: vec![Witness(2), Witness(3)],
+        };
+        let mut witness = BTreeMap::from([
+            (Witness(1), FieldElement::one()),
+            (Witness(3), FieldElement::zero()),
+        ]);
+
+        assert!(matches!(
+            bb.solve_black_box_function_call(&mut witness, &fixed_base),
+            Err(OpcodeResolutionError::UnsatisfiedConstrain)
+        ));
+        // Neither output is assigned when only the second conflicts.
+        assert_eq!(witness.get(&Witness(2)), None);
+        assert_eq!(witness[&Witness(3)], FieldElement::zero());
+    }
+
+    #[test]
+    fn rejects_conflicting_outputs_of_delegated_functions() {
+        let bb = Barretenberg::new();
+        let mut witness = BTreeMap::from([
+            (Witness(1), FieldElement::from(3_u128)),
+            (Witness(2), FieldElement::from(5_u128)),
+            (Witness(3), FieldElement::from(7_u128)),
+        ]);
+
+        assert!(matches!(
+            bb.solve_black_box_function_call(&mut witness, &and(8)),
+            Err(OpcodeResolutionError::UnsatisfiedConstrain)
+        ));
+        assert_eq!(witness[&Witness(3)], FieldElement::from(7_u128));
+
+        // Agreeing with an existing assignment is fine.
+        witness.insert(Witness(3), FieldElement::from(1_u128));
+        assert_eq!(
+            bb.solve_black_box_function_call(&mut witness, &and(8)),
+            Ok(OpcodeResolution::Solved)
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Circuit, Opcode};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::acir::BlackBoxFunc;
+use acvm::{FieldElement, OpcodeResolution, PartialWitnessGenerator};
+
+use std::collections::BTreeMap;
+
+use super::trace::{assigned, referenced_witnesses};
+use crate::Barretenberg;
+
+/// An opcode whose constraint is not satisfied by a witness, as reported by [`Barretenberg::check_witness`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    /// The position of the opcode within the circuit.
+    pub opcode_index: usize,
+    /// The black box function called by the opcode, if any.
+    pub black_box_func: Option<BlackBoxFunc>,
+    /// The assigned witnesses referenced by the opcode.
+    pub witness_values: Vec<(Witness, FieldElement)>,
+    /// A description of why the constraint is not satisfied.
+    pub reason: String,
+}
+
+impl Barretenberg {
+    /// Evaluates every arithmetic opcode and black box function call in `circuit` against `witness_values`,
+    /// returning those which are not satisfied in the order in which they appear in the circuit.
+    ///
+    /// Black box function calls are checked by recomputing their outputs from their inputs with this backend,
+    /// including any of its [`black_box_overrides`][crate::BackendOptions::black_box_overrides], so this reports
+    /// which constraint causes a proof to fail without needing to construct the proof.
+    /// Directives, oracles and memory opcodes are not checked.
+    pub fn check_witness(
+        &self,
+        circuit: &Circuit,
+        witness_values: &BTreeMap<Witness, FieldElement>,
+    ) -> Vec<ConstraintViolation> {
+        circuit
+            .opcodes
+            .iter()
+            .enumerate()
+            .filter_map(|(opcode_index, opcode)| {
+                let (black_box_func, result) = match opcode {
+                    Opcode::Arithmetic(expr) => (None, check_expression(expr, witness_values)),
+                    Opcode::BlackBoxFuncCall(func_call) => (
+                        Some(func_call.name),
+                        check_black_box_func_call(self, func_call, witness_values),
+                    ),
+                    // Directives and oracles only guide the solver, and memory opcodes aren't passed to barretenberg.
+                    _ => return None,
+                };
+
+                let reason = result.err()?;
+                let witness_values = referenced_witnesses(opcode)
+                    .map(|witnesses| assigned(witness_values, &witnesses))
+                    .unwrap_or_default();
+                Some(ConstraintViolation {
+                    opcode_index,
+                    black_box_func,
+                    witness_values,
+                    reason,
+                })
+            })
+            .collect()
+    }
+}
+
+fn check_expression(
+    expr: &Expression,
+    witness_values: &BTreeMap<Witness, FieldElement>,
+) -> Result<(), String> {
+    let value = |witness: Witness| witness_value(witness_values, witness);
+
+    let mut result = expr.q_c;
+    for (coefficient, lhs, rhs) in &expr.mul_terms {
+        result = result + *coefficient * value(*lhs)? * value(*rhs)?;
+    }
+    for (coefficient, witness) in &expr.linear_combinations {
+        result = result + *coefficient * value(*witness)?;
+    }
+
+    if result.is_zero() {
+        Ok(())
+    } else {
+        Err(format!(
+            "expression evaluates to 0x{} rather than zero",
+            result.to_hex()
+        ))
+    }
+}
+
+fn check_black_box_func_call(
+    bb: &Barretenberg,
+    func_call: &BlackBoxFuncCall,
+    witness_values: &BTreeMap<Witness, FieldElement>,
+) -> Result<(), String> {
+    // Only the inputs are provided to the solver so that it recomputes the outputs.
+    let mut solved_witness = BTreeMap::new();
+    for input in &func_call.inputs {
+        let value = witness_value(witness_values, input.witness)?;
+        solved_witness.insert(input.witness, value);
+    }
+
+    match bb.solve_black_box_function_call(&mut solved_witness, func_call) {
+        Ok(OpcodeResolution::Solved) => (),
+        Ok(_) => return Err("black box function could not be evaluated".to_owned()),
+        Err(err) => return Err(err.to_string()),
+    }
+
+    for output in &func_call.outputs {
+        let actual = witness_value(witness_values, *output)?;
+        let expected = solved_witness.get(output).copied().ok_or_else(|| {
+            format!(
+                "black box function did not assign output _{}",
+                output.witness_index()
+            )
+        })?;
+        if actual != expected {
+            return Err(format!(
+                "output _{} is 0x{} but the inputs give 0x{}",
+                output.witness_index(),
+                actual.to_hex(),
+                expected.to_hex()
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn witness_value(
+    witness_values: &BTreeMap<Witness, FieldElement>,
+    witness: Witness,
+) -> Result<FieldElement, String> {
+    witness_values
+        .get(&witness)
+        .copied()
+        .ok_or_else(|| format!("witness _{} is unassigned", witness.witness_index()))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::FunctionInput;
+    use acvm::acir::circuit::PublicInputs;
+
+    use super::*;
+    use crate::BackendOptions;
+
+    /// Constrains `_3 = _1 & _2` and `_4 = _3 + 1`.
+    fn circuit() -> Circuit {
+        let and = Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::AND,
+            inputs: vec![
+                FunctionInput {
+                    witness: Witness(1),
+                    num_bits: 8,
+                },
+                FunctionInput {
+                    witness: Witness(2),
+                    num_bits: 8,
+                },
+            ],
+            outputs: vec![Witness(3)],
+        });
+        let increment = Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![
+                (FieldElement::one(), Witness(3)),
+                (-FieldElement::one(), Witness(4)),
+            ],
+            q_c: FieldElement::one(),
+        });
+        Circuit {
+            current_witness_index: 4,
+            opcodes: vec![and, increment],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        }
+    }
+
+    fn witness(values: &[u128]) -> BTreeMap<Witness, FieldElement> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (Witness(i as u32 + 1), FieldElement::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_satisfying_witness() {
+        assert_eq!(
+            Barretenberg::new().check_witness(&circuit(), &witness(&[6, 3, 2, 3])),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn reports_unsatisfied_constraints() {
+        let violations = Barretenberg::new().check_witness(&circuit(), &witness(&[6, 3, 7, 3]));
+
+        let summary: Vec<_> = violations
+            .iter()
+            .map(|violation| (violation.opcode_index, violation.black_box_func))
+            .collect();
+        assert_eq!(summary, vec![(0, Some(BlackBoxFunc::AND)), (1, None)]);
+        assert_eq!(
+            violations[1].witness_values,
+            vec![
+                (Witness(3), FieldElement::from(7_u128)),
+                (Witness(4), FieldElement::from(3_u128))
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_black_box_overrides() {
+        // The AND override always outputs 42, whilst the XOR override leaves its output unassigned.
+        let options = BackendOptions::default()
+            .with_black_box_override(
+                BlackBoxFunc::AND,
+                |witness: &mut BTreeMap<Witness, FieldElement>, func_call: &BlackBoxFuncCall| {
+                    witness.insert(func_call.outputs[0], FieldElement::from(42_u128));
+                    Ok(OpcodeResolution::Solved)
+                },
+            )
+            .with_black_box_override(BlackBoxFunc::XOR, |_, _| Ok(OpcodeResolution::Solved));
+        let bb = Barretenberg::with_options(options).unwrap();
+
+        assert_eq!(
+            bb.check_witness(&circuit(), &witness(&[6, 3, 42, 43])),
+            vec![]
+        );
+
+        let mut circuit = circuit();
+        let Opcode::BlackBoxFuncCall(func_call) = &mut circuit.opcodes[0] else {
+            unreachable!()
+        };
+        func_call.name = BlackBoxFunc::XOR;
+        let violations = bb.check_witness(&circuit, &witness(&[6, 3, 5, 6]));
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].reason,
+            "black box function did not assign output _3"
+        );
+    }
+
+    #[test]
+    fn reports_unassigned_witnesses() {
+        let violations = Barretenberg::new().check_witness(&circuit(), &witness(&[6, 3, 2]));
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].opcode_index, 1);
+        assert_eq!(violations[0].reason, "witness _4 is unassigned");
+    }
+}
@@ -33,13 +33,12 @@ pub(super) fn compute_merkle_root(
 
 #[cfg(test)]
 mod tests {
-    use crate::merkle::{MerkleTree, MessageHasher};
-    use crate::Error;
-    use crate::{pedersen::Pedersen, Barretenberg};
+    use crate::primitives::blake2s_to_field;
+    use crate::{pedersen::Pedersen, BackendError, Barretenberg, InMemoryMerkleTree};
     use acvm::FieldElement;
 
     #[test]
-    fn test_check_membership() -> Result<(), Error> {
+    fn test_check_membership() -> Result<(), BackendError> {
         struct Test<'a> {
             // Index of the leaf in the MerkleTree
             index: &'a str,
@@ -92,26 +91,22 @@ mod tests {
             },
         ];
 
-        use tempfile::tempdir;
-        let temp_dir = tempdir().unwrap();
-        let mut msg_hasher: blake2::Blake2s = MessageHasher::new();
-
-        let mut tree: MerkleTree<blake2::Blake2s, Barretenberg> = MerkleTree::new(3, &temp_dir);
+        let mut tree = InMemoryMerkleTree::new(3)?;
 
         for test_vector in tests {
             let index = FieldElement::try_from_str(test_vector.index).unwrap();
-            let index_as_usize: usize = test_vector.index.parse().unwrap();
+            let index_as_u64: u64 = test_vector.index.parse().unwrap();
             let mut index_bits = index.bits();
             index_bits.reverse();
 
-            let leaf = msg_hasher.hash(&test_vector.message);
+            let leaf = blake2s_to_field(&test_vector.message);
 
             let mut root = tree.root();
             if test_vector.should_update_tree {
-                root = tree.update_message(index_as_usize, &test_vector.message)?;
+                root = tree.update_message(index_as_u64, &test_vector.message)?;
             }
 
-            let hash_path = tree.get_hash_path(index_as_usize);
+            let hash_path = tree.get_hash_path(index_as_u64)?;
             let mut hash_path_ref = Vec::new();
             for (i, path_pair) in hash_path.into_iter().enumerate() {
                 let path_bit = index_bits[i];
@@ -141,11 +136,8 @@ mod tests {
 
     // This test uses `update_leaf` directly rather than `update_message`
     #[test]
-    fn simple_shield() -> Result<(), Error> {
-        use tempfile::tempdir;
-        let temp_dir = tempdir().unwrap();
-
-        let mut tree: MerkleTree<blake2::Blake2s, Barretenberg> = MerkleTree::new(3, &temp_dir);
+    fn simple_shield() -> Result<(), BackendError> {
+        let mut tree = InMemoryMerkleTree::new(3)?;
 
         let barretenberg = Barretenberg::new();
         let pubkey_x = FieldElement::from_hex(
@@ -161,13 +153,13 @@ mod tests {
         let leaf = note_commitment_x;
 
         let index = FieldElement::try_from_str("0").unwrap();
-        let index_as_usize: usize = 0_usize;
+        let index_as_u64: u64 = 0;
         let mut index_bits = index.bits();
         index_bits.reverse();
 
-        let root = tree.update_leaf(index_as_usize, leaf)?;
+        let root = tree.update_leaf(index_as_u64, leaf)?;
 
-        let hash_path = tree.get_hash_path(index_as_usize);
+        let hash_path = tree.get_hash_path(index_as_u64)?;
         let mut hash_path_ref = Vec::new();
         for (i, path_pair) in hash_path.into_iter().enumerate() {
             let path_bit = index_bits[i];
@@ -0,0 +1,391 @@
+use acvm::acir::native_types::Witness;
+use acvm::{FieldElement, OpcodeResolutionError, PartialWitnessGeneratorStatus};
+
+use std::collections::BTreeMap;
+
+use super::WitnessSolver;
+#[cfg(any(feature = "native", feature = "wasm"))]
+use crate::field::{field_from_be_bytes, hex_to_bytes};
+#[cfg(any(feature = "native", feature = "wasm"))]
+use crate::{BackendError, Error};
+
+/// Provides the outputs of oracle calls made by a circuit, such as merkle paths or notes held by an indexer.
+pub trait OracleResolver {
+    /// Returns the output values of a call to the oracle `name` with the given input values.
+    fn resolve(&self, name: &str, inputs: &[FieldElement]) -> Result<Vec<FieldElement>, String>;
+}
+
+impl<F> OracleResolver for F
+where
+    F: Fn(&str, &[FieldElement]) -> Result<Vec<FieldElement>, String>,
+{
+    fn resolve(&self, name: &str, inputs: &[FieldElement]) -> Result<Vec<FieldElement>, String> {
+        self(name, inputs)
+    }
+}
+
+/// An error encountered by [`WitnessSolver::solve_with_resolver`].
+#[derive(Debug, thiserror::Error)]
+pub enum OracleSolveError {
+    #[error(transparent)]
+    Solve(#[from] OpcodeResolutionError),
+
+    #[error("Oracle \"{name}\" could not be resolved: {reason}")]
+    Resolve { name: String, reason: String },
+}
+
+impl WitnessSolver<'_> {
+    /// Solves the circuit to completion, fetching the outputs of each oracle call from `resolver` as it's reached.
+    pub fn solve_with_resolver(
+        mut self,
+        resolver: &impl OracleResolver,
+    ) -> Result<BTreeMap<Witness, FieldElement>, OracleSolveError> {
+        while let PartialWitnessGeneratorStatus::RequiresOracleData { .. } = self.solve()? {
+            for oracle_data in self.required_oracle_data_mut() {
+                let output_values = resolver
+                    .resolve(&oracle_data.name, &oracle_data.input_values)
+                    .map_err(|reason| OracleSolveError::Resolve {
+                        name: oracle_data.name.clone(),
+                        reason,
+                    })?;
+                if output_values.len() != oracle_data.outputs.len() {
+                    return Err(OracleSolveError::Resolve {
+                        name: oracle_data.name.clone(),
+                        reason: format!(
+                            "expected {} output values but received {}",
+                            oracle_data.outputs.len(),
+                            output_values.len()
+                        ),
+                    });
+                }
+                oracle_data.output_values = output_values;
+            }
+        }
+        Ok(self.into_witness())
+    }
+}
+
+/// Resolves oracle calls by making JSON-RPC 2.0 requests to an HTTP endpoint.
+///
+/// Each call is sent as a request whose method is the oracle's name and whose params are the input values as
+/// `0x`-prefixed hex strings. The result must be an array of the output values, encoded in the same way.
+///
+/// Requests are made on a runtime owned by the resolver, so it can be used both from synchronous code and from
+/// within another async runtime, e.g. by a proving server.
+#[cfg(any(feature = "native", feature = "wasm"))]
+#[derive(Debug, Clone)]
+pub struct HttpOracleResolver {
+    url: String,
+    client: reqwest::Client,
+    next_id: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    runtime: tokio::runtime::Handle,
+    _runtime: std::sync::Arc<OracleRuntime>,
+}
+
+/// Shuts down the resolver's runtime once every clone of the resolver has been dropped.
+#[cfg(any(feature = "native", feature = "wasm"))]
+#[derive(Debug)]
+struct OracleRuntime(Option<tokio::runtime::Runtime>);
+
+#[cfg(any(feature = "native", feature = "wasm"))]
+impl Drop for OracleRuntime {
+    fn drop(&mut self) {
+        // Dropping a runtime waits for its tasks, which panics if the resolver is dropped inside another runtime.
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+#[cfg(any(feature = "native", feature = "wasm"))]
+impl HttpOracleResolver {
+    /// How long to wait for the endpoint to respond to each call before giving up.
+    pub const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+    pub fn new(url: impl Into<String>) -> Result<HttpOracleResolver, BackendError> {
+        HttpOracleResolver::with_timeout(url, HttpOracleResolver::DEFAULT_TIMEOUT)
+    }
+
+    /// Creates a resolver which fails any call to which the endpoint hasn't responded within `timeout`.
+    pub fn with_timeout(
+        url: impl Into<String>,
+        timeout: std::time::Duration,
+    ) -> Result<HttpOracleResolver, BackendError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|err| {
+                Error::OracleResolver(format!("could not create HTTP client ({err})"))
+            })?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("oracle-resolver")
+            .enable_all()
+            .build()
+            .map_err(|err| Error::OracleResolver(format!("could not start runtime ({err})")))?;
+
+        Ok(HttpOracleResolver {
+            url: url.into(),
+            client,
+            next_id: Default::default(),
+            runtime: runtime.handle().clone(),
+            _runtime: std::sync::Arc::new(OracleRuntime(Some(runtime))),
+        })
+    }
+}
+
+#[cfg(any(feature = "native", feature = "wasm"))]
+async fn call(
+    client: reqwest::Client,
+    url: String,
+    request: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let response = client
+        .post(&url)
+        .header("Content-Type", "application/json")
+        .body(request.to_string())
+        .send()
+        .await
+        .map_err(|err| format!("Failed to POST to '{url}' ({err})"))?;
+    let body = response
+        .bytes()
+        .await
+        .map_err(|err| format!("Failed to read response from '{url}' ({err})"))?;
+    serde_json::from_slice(&body).map_err(|err| format!("Malformed JSON-RPC response ({err})"))
+}
+
+#[cfg(any(feature = "native", feature = "wasm"))]
+impl OracleResolver for HttpOracleResolver {
+    fn resolve(&self, name: &str, inputs: &[FieldElement]) -> Result<Vec<FieldElement>, String> {
+        let id = self
+            .next_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let params: Vec<String> = inputs
+            .iter()
+            .map(|input| format!("0x{}", input.to_hex()))
+            .collect();
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": name,
+            "params": params,
+        });
+
+        // The call runs on the resolver's runtime and its result is awaited with a plain channel, as blocking on a
+        // runtime from inside another one panics.
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let call = call(self.client.clone(), self.url.clone(), request);
+        self.runtime.spawn(async move {
+            // The receiver is only gone if the resolving thread has panicked.
+            let _ = sender.send(call.await);
+        });
+        let response = receiver
+            .recv()
+            .map_err(|_| "HTTP oracle runtime has shut down".to_owned())??;
+
+        if response.get("id").and_then(serde_json::Value::as_u64) != Some(id) {
+            return Err(format!("JSON-RPC response is not for request {id}"));
+        }
+        if let Some(error) = response.get("error") {
+            return Err(format!("JSON-RPC error: {error}"));
+        }
+        let result = response
+            .get("result")
+            .and_then(serde_json::Value::as_array)
+            .ok_or("JSON-RPC response has no result array")?;
+        result
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .and_then(|hex| hex.strip_prefix("0x"))
+                    .and_then(hex_to_bytes)
+                    .and_then(|bytes| field_from_be_bytes(&bytes).ok())
+                    .ok_or_else(|| format!("{value} is not a hex encoded field element"))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::OracleData;
+    use acvm::acir::circuit::Opcode;
+    use acvm::acir::native_types::Expression;
+
+    use super::*;
+    use crate::Barretenberg;
+
+    /// `_2` is provided by the oracle "double", which is called with `_1`.
+    fn opcodes() -> Vec<Opcode> {
+        vec![Opcode::Oracle(OracleData {
+            name: "double".to_owned(),
+            inputs: vec![Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(FieldElement::one(), Witness(1))],
+                q_c: FieldElement::zero(),
+            }],
+            input_values: vec![],
+            outputs: vec![Witness(2)],
+            output_values: vec![],
+        })]
+    }
+
+    #[test]
+    fn solves_using_resolver() {
+        let bb = Barretenberg::new();
+        let double = |name: &str, inputs: &[FieldElement]| {
+            assert_eq!(name, "double");
+            Ok::<_, String>(vec![inputs[0] + inputs[0]])
+        };
+
+        let solver = WitnessSolver::new(
+            &bb,
+            BTreeMap::from([(Witness(1), FieldElement::from(3_u128))]),
+            opcodes(),
+        );
+        let witness = solver.solve_with_resolver(&double).unwrap();
+
+        assert_eq!(witness[&Witness(2)], FieldElement::from(6_u128));
+    }
+
+    #[test]
+    fn rejects_wrong_number_of_outputs() {
+        let bb = Barretenberg::new();
+        let no_outputs = |_: &str, _: &[FieldElement]| Ok::<_, String>(Vec::new());
+
+        let solver = WitnessSolver::new(
+            &bb,
+            BTreeMap::from([(Witness(1), FieldElement::from(3_u128))]),
+            opcodes(),
+        );
+
+        assert!(matches!(
+            solver.solve_with_resolver(&no_outputs),
+            Err(OracleSolveError::Resolve { .. })
+        ));
+    }
+
+    #[cfg(any(feature = "native", feature = "wasm"))]
+    mod http {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+        use std::time::Duration;
+
+        use super::*;
+
+        /// Starts a JSON-RPC server which answers each request with the response returned by `respond`, or never
+        /// answers if it returns `None`. Returns the server's URL.
+        fn serve(
+            respond: impl Fn(serde_json::Value) -> Option<serde_json::Value> + Send + 'static,
+        ) -> String {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(&mut stream);
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        reader.read_line(&mut line).unwrap();
+                        let line = line.trim_end().to_ascii_lowercase();
+                        if line.is_empty() {
+                            break;
+                        }
+                        if let Some(length) = line.strip_prefix("content-length:") {
+                            content_length = length.trim().parse().unwrap();
+                        }
+                    }
+                    let mut body = vec![0; content_length];
+                    reader.read_exact(&mut body).unwrap();
+
+                    match respond(serde_json::from_slice(&body).unwrap()) {
+                        Some(response) => {
+                            let response = response.to_string();
+                            write!(
+                                stream,
+                                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                                response.len()
+                            )
+                            .unwrap();
+                        }
+                        None => std::thread::sleep(Duration::from_secs(5)),
+                    }
+                }
+            });
+            url
+        }
+
+        /// Answers calls to "double" with twice their input.
+        fn double(request: serde_json::Value) -> Option<serde_json::Value> {
+            assert_eq!(request["method"], "double");
+            let input = FieldElement::from_hex(request["params"][0].as_str().unwrap()).unwrap();
+            Some(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": [format!("0x{}", (input + input).to_hex())],
+            }))
+        }
+
+        #[test]
+        fn resolves_calls_over_http() {
+            let resolver = HttpOracleResolver::new(serve(double)).unwrap();
+
+            for _ in 0..2 {
+                assert_eq!(
+                    resolver.resolve("double", &[FieldElement::from(3_u128)]),
+                    Ok(vec![FieldElement::from(6_u128)])
+                );
+            }
+        }
+
+        #[test]
+        fn resolves_calls_inside_another_runtime() {
+            let url = serve(double);
+
+            let outputs = tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap()
+                .block_on(async move {
+                    // The resolver is also dropped inside the runtime.
+                    let resolver = HttpOracleResolver::new(url).unwrap();
+                    resolver.resolve("double", &[FieldElement::from(3_u128)])
+                });
+
+            assert_eq!(outputs, Ok(vec![FieldElement::from(6_u128)]));
+        }
+
+        #[test]
+        fn rejects_invalid_responses() {
+            let wrong_id = serve(|request| {
+                Some(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"].as_u64().unwrap() + 1,
+                    "result": ["0x01"],
+                }))
+            });
+            let non_canonical = serve(|request| {
+                Some(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": [format!("0x{}", "f".repeat(64))],
+                }))
+            });
+
+            for url in [wrong_id, non_canonical] {
+                let resolver = HttpOracleResolver::new(url).unwrap();
+                assert!(resolver.resolve("double", &[FieldElement::one()]).is_err());
+            }
+        }
+
+        #[test]
+        fn times_out_unresponsive_endpoints() {
+            let resolver =
+                HttpOracleResolver::with_timeout(serve(|_| None), Duration::from_millis(100))
+                    .unwrap();
+
+            assert!(resolver.resolve("double", &[FieldElement::one()]).is_err());
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{
+    FieldElement, OpcodeResolution, OpcodeResolutionError, PartialWitnessGenerator,
+    PartialWitnessGeneratorStatus,
+};
+use rayon::prelude::*;
+
+use std::collections::BTreeMap;
+
+use super::rounds::SolveHooks;
+use super::{call_inputs, call_outputs, insert_outputs};
+use crate::secret::zeroize_field_elements;
+use crate::Barretenberg;
+
+impl Barretenberg {
+    /// Solves `opcodes` in the same manner as [`PartialWitnessGenerator::solve`], dispatching black box
+    /// function calls whose inputs are all known onto the rayon thread pool.
+    ///
+    /// The results of each batch of black box function calls are merged into `initial_witness` in opcode order,
+    /// so the solved witness is identical to that produced by the serial solver.
+    pub fn solve_parallel(
+        &self,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        blocks: &mut Blocks,
+        opcodes: Vec<Opcode>,
+    ) -> Result<PartialWitnessGeneratorStatus, OpcodeResolutionError> {
+        let stalled = self.solve_in_rounds(
+            initial_witness,
+            blocks,
+            opcodes.into_iter().enumerate().collect(),
+            &mut ParallelBlackBoxCalls,
+        )?;
+        self.finish_rounds(initial_witness, blocks, stalled)
+    }
+}
+
+/// Solves the black box function calls whose inputs are all known at the start of each round in parallel, leaving
+/// the remaining opcodes to the serial solver.
+struct ParallelBlackBoxCalls;
+
+impl SolveHooks for ParallelBlackBoxCalls {
+    fn start_round(
+        &mut self,
+        bb: &Barretenberg,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        opcodes: Vec<(usize, Opcode)>,
+        stalled: &mut Vec<(usize, Opcode)>,
+    ) -> Result<Vec<(usize, Opcode)>, OpcodeResolutionError> {
+        let (black_box_calls, other_opcodes): (Vec<_>, Vec<_>) = opcodes
+            .into_iter()
+            .partition(|(_, opcode)| matches!(opcode, Opcode::BlackBoxFuncCall(_)));
+
+        // Black box function calls only depend upon their inputs so every call whose inputs have been
+        // assigned is independent of the others and can be solved concurrently. Those which aren't ready are
+        // retained for the next round as they may depend upon the outputs of a later opcode.
+        let (ready_calls, blocked_calls): (Vec<_>, Vec<_>) =
+            black_box_calls
+                .into_iter()
+                .partition(|(_, opcode)| match opcode {
+                    Opcode::BlackBoxFuncCall(func_call) => {
+                        has_all_inputs(initial_witness, func_call)
+                    }
+                    _ => unreachable!("only black box function calls are solved in parallel"),
+                });
+        stalled.extend(blocked_calls);
+
+        let resolutions: Vec<_> = ready_calls
+            .par_iter()
+            .map(|(_, opcode)| match opcode {
+                Opcode::BlackBoxFuncCall(func_call) => {
+                    let mut call_witness = call_inputs(initial_witness, func_call);
+                    bb.solve_black_box_function_call(&mut call_witness, func_call)
+                        .map(|resolution| (resolution, call_witness))
+                }
+                _ => unreachable!("only black box function calls are solved in parallel"),
+            })
+            .collect();
+
+        for (opcode, resolution) in ready_calls.into_iter().zip(resolutions) {
+            let (resolution, mut call_witness) = resolution?;
+            let Opcode::BlackBoxFuncCall(func_call) = &opcode.1 else {
+                unreachable!("only black box function calls are solved in parallel");
+            };
+            // Outputs may already have been assigned, e.g. by an earlier opcode, so they're checked in the same
+            // manner as by the serial solver rather than overwritten.
+            let merged = insert_outputs(initial_witness, &call_outputs(&call_witness, func_call));
+            zeroize_field_elements(call_witness.values_mut());
+            merged?;
+            if !matches!(resolution, OpcodeResolution::Solved) {
+                stalled.push(opcode);
+            }
+        }
+
+        Ok(other_opcodes)
+    }
+}
+
+fn has_all_inputs(
+    witness_assignments: &BTreeMap<Witness, FieldElement>,
+    func_call: &BlackBoxFuncCall,
+) -> bool {
+    func_call
+        .inputs
+        .iter()
+        .all(|input| witness_assignments.contains_key(&input.witness))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::FunctionInput;
+    use acvm::acir::native_types::Expression;
+    use acvm::acir::BlackBoxFunc;
+
+    use super::*;
+
+    fn pedersen(input: Witness, outputs: [Witness; 2]) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::Pedersen,
+            inputs: vec![FunctionInput {
+                witness: input,
+                num_bits: FieldElement::max_num_bits(),
+            }],
+            outputs: outputs.to_vec(),
+        })
+    }
+
+    #[test]
+    fn parallel_solve_matches_serial_solve() -> Result<(), OpcodeResolutionError> {
+        let bb = Barretenberg::new();
+
+        // Witness(2) is computed by an arithmetic opcode so the second hash can only be solved in a later round,
+        // whilst the final opcode depends upon the outputs of both hashes.
+        let opcodes = vec![
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![
+                    (FieldElement::one(), Witness(3)),
+                    (FieldElement::one(), Witness(5)),
+                    (-FieldElement::one(), Witness(7)),
+                ],
+                q_c: FieldElement::zero(),
+            }),
+            pedersen(Witness(1), [Witness(3), Witness(4)]),
+            pedersen(Witness(2), [Witness(5), Witness(6)]),
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![
+                    (FieldElement::one(), Witness(1)),
+                    (-FieldElement::one(), Witness(2)),
+                ],
+                q_c: FieldElement::one(),
+            }),
+        ];
+        let initial_witness = BTreeMap::from([(Witness(1), FieldElement::from(5_i128))]);
+
+        let mut serial_witness = initial_witness.clone();
+        let serial_status =
+            bb.solve(&mut serial_witness, &mut Blocks::default(), opcodes.clone())?;
+
+        let mut parallel_witness = initial_witness;
+        let parallel_status =
+            bb.solve_parallel(&mut parallel_witness, &mut Blocks::default(), opcodes)?;
+
+        assert_eq!(serial_status, PartialWitnessGeneratorStatus::Solved);
+        assert_eq!(parallel_status, PartialWitnessGeneratorStatus::Solved);
+        assert_eq!(parallel_witness, serial_witness);
+        Ok(())
+    }
+
+    #[test]
+    fn parallel_solve_rejects_conflicting_outputs() {
+        let bb = Barretenberg::new();
+        let opcodes = vec![pedersen(Witness(1), [Witness(2), Witness(3)])];
+        let mut witness = BTreeMap::from([
+            (Witness(1), FieldElement::from(5_i128)),
+            (Witness(2), FieldElement::zero()),
+        ]);
+
+        let result = bb.solve_parallel(&mut witness, &mut Blocks::default(), opcodes);
+
+        assert!(matches!(
+            result,
+            Err(OpcodeResolutionError::UnsatisfiedConstrain)
+        ));
+        assert_eq!(witness[&Witness(2)], FieldElement::zero());
+    }
+
+    #[test]
+    fn parallel_solve_reports_unsolvable_opcodes() {
+        let bb = Barretenberg::new();
+        let opcodes = vec![pedersen(Witness(1), [Witness(2), Witness(3)])];
+
+        let result = bb.solve_parallel(&mut BTreeMap::new(), &mut Blocks::default(), opcodes);
+
+        assert!(matches!(
+            result,
+            Err(OpcodeResolutionError::OpcodeNotSolvable(_))
+        ));
+    }
+}
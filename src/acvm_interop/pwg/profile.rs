@@ -0,0 +1,135 @@
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{FieldElement, OpcodeResolutionError, PartialWitnessGeneratorStatus};
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use super::rounds::SolveHooks;
+use super::TraceOutcome;
+use crate::Barretenberg;
+
+/// The time spent solving opcodes of a single kind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OpcodeTiming {
+    /// The number of attempts to solve an opcode of this kind, including those which stalled.
+    pub count: usize,
+    pub total: Duration,
+}
+
+/// The cumulative time spent on each kind of opcode by [`Barretenberg::solve_with_profile`].
+///
+/// Black box function calls are keyed by the function's name, e.g. `sha256` or `pedersen`, and other opcodes by
+/// `arithmetic`, `directive`, `oracle` or `memory`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveProfile {
+    pub timings: BTreeMap<String, OpcodeTiming>,
+}
+
+impl SolveProfile {
+    /// The total time spent solving opcodes.
+    pub fn total(&self) -> Duration {
+        self.timings.values().map(|timing| timing.total).sum()
+    }
+
+    fn record(&mut self, opcode: &Opcode, elapsed: Duration) {
+        let timing = self.timings.entry(opcode_kind(opcode)).or_default();
+        timing.count += 1;
+        timing.total += elapsed;
+    }
+}
+
+impl Barretenberg {
+    /// Solves `opcodes` in the same manner as [`PartialWitnessGenerator::solve`] whilst timing each opcode.
+    ///
+    /// Opcodes are solved one at a time so this is slower than [`PartialWitnessGenerator::solve`], but the
+    /// proportion of time spent on each kind of opcode is representative.
+    ///
+    /// [`PartialWitnessGenerator::solve`]: acvm::PartialWitnessGenerator::solve
+    pub fn solve_with_profile(
+        &self,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        blocks: &mut Blocks,
+        opcodes: Vec<Opcode>,
+    ) -> Result<(PartialWitnessGeneratorStatus, SolveProfile), OpcodeResolutionError> {
+        let mut profiler = Profiler {
+            profile: SolveProfile::default(),
+            started: Instant::now(),
+        };
+        let stalled = self.solve_in_rounds(
+            initial_witness,
+            blocks,
+            opcodes.into_iter().enumerate().collect(),
+            &mut profiler,
+        )?;
+        let status = self.finish_rounds(initial_witness, blocks, stalled)?;
+        Ok((status, profiler.profile))
+    }
+}
+
+struct Profiler {
+    profile: SolveProfile,
+    /// When the opcode currently being solved was started.
+    started: Instant,
+}
+
+impl SolveHooks for Profiler {
+    fn before_opcode(
+        &mut self,
+        _opcode_index: usize,
+        _opcode: &Opcode,
+        _initial_witness: &BTreeMap<Witness, FieldElement>,
+    ) {
+        self.started = Instant::now();
+    }
+
+    fn after_opcode(
+        &mut self,
+        _opcode_index: usize,
+        opcode: &Opcode,
+        _initial_witness: &BTreeMap<Witness, FieldElement>,
+        _outcome: TraceOutcome,
+    ) {
+        self.profile.record(opcode, self.started.elapsed());
+    }
+}
+
+fn opcode_kind(opcode: &Opcode) -> String {
+    match opcode {
+        Opcode::Arithmetic(_) => "arithmetic".to_owned(),
+        Opcode::BlackBoxFuncCall(func_call) => func_call.name.to_string(),
+        Opcode::Directive(_) => "directive".to_owned(),
+        Opcode::Oracle(_) => "oracle".to_owned(),
+        Opcode::Block(_) | Opcode::RAM(_) | Opcode::ROM(_) => "memory".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::BlackBoxFunc;
+
+    use super::*;
+    use crate::test_circuits::pedersen_hashes;
+
+    #[test]
+    fn profiles_each_kind_of_opcode() {
+        let bb = Barretenberg::new();
+        let test_circuit = pedersen_hashes(3);
+        let mut witness = test_circuit.initial_witness;
+
+        let (status, profile) = bb
+            .solve_with_profile(
+                &mut witness,
+                &mut Blocks::default(),
+                test_circuit.circuit.opcodes,
+            )
+            .unwrap();
+
+        assert_eq!(status, PartialWitnessGeneratorStatus::Solved);
+        let pedersen = &profile.timings[&BlackBoxFunc::Pedersen.to_string()];
+        assert_eq!(pedersen.count, 3);
+        assert_eq!(profile.timings.len(), 1);
+        assert_eq!(profile.total(), pedersen.total);
+    }
+}
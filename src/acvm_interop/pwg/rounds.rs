@@ -0,0 +1,152 @@
+use acvm::acir::circuit::opcodes::OracleData;
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{
+    FieldElement, OpcodeResolutionError, PartialWitnessGenerator, PartialWitnessGeneratorStatus,
+};
+
+use std::collections::BTreeMap;
+
+use super::TraceOutcome;
+use crate::Barretenberg;
+
+/// Hooks into [`Barretenberg::solve_in_rounds`], e.g. to time or trace each opcode.
+///
+/// Opcodes are identified by their position in the circuit.
+pub(super) trait SolveHooks {
+    /// Called at the start of each round with the opcodes which remain unsolved, returning those which should be
+    /// solved one at a time.
+    ///
+    /// Opcodes which this solves itself, e.g. in parallel, should be dropped whilst those which it tried and
+    /// failed to solve, or which shouldn't be attempted until the next round, should be pushed onto `stalled`.
+    fn start_round(
+        &mut self,
+        _bb: &Barretenberg,
+        _initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        opcodes: Vec<(usize, Opcode)>,
+        _stalled: &mut Vec<(usize, Opcode)>,
+    ) -> Result<Vec<(usize, Opcode)>, OpcodeResolutionError> {
+        Ok(opcodes)
+    }
+
+    /// Called immediately before an attempt to solve an opcode.
+    fn before_opcode(
+        &mut self,
+        _opcode_index: usize,
+        _opcode: &Opcode,
+        _initial_witness: &BTreeMap<Witness, FieldElement>,
+    ) {
+    }
+
+    /// Called immediately after an attempt to solve an opcode.
+    fn after_opcode(
+        &mut self,
+        _opcode_index: usize,
+        _opcode: &Opcode,
+        _initial_witness: &BTreeMap<Witness, FieldElement>,
+        _outcome: TraceOutcome,
+    ) {
+    }
+}
+
+impl SolveHooks for () {}
+
+/// The opcodes which [`Barretenberg::solve_in_rounds`] couldn't solve.
+#[derive(Debug, Default)]
+pub(super) struct Stalled {
+    pub(super) opcodes: Vec<(usize, Opcode)>,
+    /// The oracle calls whose outputs are needed before solving can continue.
+    pub(super) required_oracle_data: Vec<OracleData>,
+}
+
+impl Barretenberg {
+    /// Attempts to solve each of `opcodes` in turn, then repeats for those which stalled until every opcode is
+    /// solved, oracle data is required or a round makes no progress.
+    ///
+    /// This is the loop shared by the solvers which need to observe or take over individual opcodes, which
+    /// [`PartialWitnessGenerator::solve`] doesn't allow.
+    pub(super) fn solve_in_rounds(
+        &self,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        blocks: &mut Blocks,
+        mut opcodes: Vec<(usize, Opcode)>,
+        hooks: &mut impl SolveHooks,
+    ) -> Result<Stalled, OpcodeResolutionError> {
+        loop {
+            let num_opcodes = opcodes.len();
+            let num_assignments = initial_witness.len();
+
+            let mut stalled = Stalled::default();
+            let opcodes =
+                hooks.start_round(self, initial_witness, opcodes, &mut stalled.opcodes)?;
+            for (opcode_index, opcode) in opcodes {
+                hooks.before_opcode(opcode_index, &opcode, initial_witness);
+                let result = self.solve(initial_witness, blocks, vec![opcode.clone()]);
+                let outcome = match &result {
+                    Ok(PartialWitnessGeneratorStatus::Solved) => TraceOutcome::Solved,
+                    Ok(PartialWitnessGeneratorStatus::RequiresOracleData { .. })
+                    | Err(OpcodeResolutionError::OpcodeNotSolvable(_)) => TraceOutcome::Stalled,
+                    Err(_) => TraceOutcome::Failed,
+                };
+                hooks.after_opcode(opcode_index, &opcode, initial_witness, outcome);
+
+                match result {
+                    Ok(PartialWitnessGeneratorStatus::Solved) => (),
+                    Ok(PartialWitnessGeneratorStatus::RequiresOracleData {
+                        required_oracle_data,
+                        unsolved_opcodes,
+                    }) => {
+                        stalled.required_oracle_data.extend(required_oracle_data);
+                        stalled.opcodes.extend(
+                            unsolved_opcodes
+                                .into_iter()
+                                .map(|opcode| (opcode_index, opcode)),
+                        );
+                    }
+                    Err(OpcodeResolutionError::OpcodeNotSolvable(_)) => {
+                        stalled.opcodes.push((opcode_index, opcode));
+                    }
+                    Err(error) => return Err(error),
+                }
+            }
+
+            let made_progress =
+                stalled.opcodes.len() != num_opcodes || initial_witness.len() != num_assignments;
+            if stalled.opcodes.is_empty()
+                || !stalled.required_oracle_data.is_empty()
+                || !made_progress
+            {
+                return Ok(stalled);
+            }
+            opcodes = stalled.opcodes;
+        }
+    }
+
+    /// Returns the status which [`PartialWitnessGenerator::solve`] would have returned for the opcodes left by
+    /// [`Barretenberg::solve_in_rounds`].
+    pub(super) fn finish_rounds(
+        &self,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        blocks: &mut Blocks,
+        stalled: Stalled,
+    ) -> Result<PartialWitnessGeneratorStatus, OpcodeResolutionError> {
+        let opcodes: Vec<_> = stalled
+            .opcodes
+            .into_iter()
+            .map(|(_, opcode)| opcode)
+            .collect();
+
+        if !stalled.required_oracle_data.is_empty() {
+            Ok(PartialWitnessGeneratorStatus::RequiresOracleData {
+                required_oracle_data: stalled.required_oracle_data,
+                unsolved_opcodes: opcodes,
+            })
+        } else if opcodes.is_empty() {
+            Ok(PartialWitnessGeneratorStatus::Solved)
+        } else {
+            // No progress can be made so we defer to the serial solver to report the failure.
+            self.solve(initial_witness, blocks, opcodes)
+        }
+    }
+}
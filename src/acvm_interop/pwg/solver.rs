@@ -0,0 +1,179 @@
+use acvm::acir::circuit::opcodes::OracleData;
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{
+    FieldElement, OpcodeResolutionError, PartialWitnessGenerator, PartialWitnessGeneratorStatus,
+};
+
+use std::collections::BTreeMap;
+
+use crate::{Barretenberg, WitnessVector};
+
+/// Solves a circuit incrementally, pausing whenever oracle data is required.
+///
+/// The solver's state can be captured with [`WitnessSolver::snapshot`] before providing oracle data
+/// which may turn out to be wrong (e.g. a guessed merkle path), and rolled back with [`WitnessSolver::restore`]
+/// rather than solving the circuit again from the start.
+pub struct WitnessSolver<'a> {
+    backend: &'a Barretenberg,
+    witness: BTreeMap<Witness, FieldElement>,
+    blocks: Blocks,
+    unsolved_opcodes: Vec<Opcode>,
+    required_oracle_data: Vec<OracleData>,
+}
+
+/// The state of a [`WitnessSolver`] at the point at which [`WitnessSolver::snapshot`] was called.
+#[derive(Debug, Clone)]
+pub struct SolverSnapshot {
+    /// Snapshots may be held for a long time, so the witness is stored densely.
+    witness: WitnessVector,
+    unsolved_opcodes: Vec<Opcode>,
+    required_oracle_data: Vec<OracleData>,
+}
+
+impl<'a> WitnessSolver<'a> {
+    pub fn new(
+        backend: &'a Barretenberg,
+        initial_witness: BTreeMap<Witness, FieldElement>,
+        opcodes: Vec<Opcode>,
+    ) -> Self {
+        WitnessSolver {
+            backend,
+            witness: initial_witness,
+            blocks: Blocks::default(),
+            unsolved_opcodes: opcodes,
+            required_oracle_data: Vec::new(),
+        }
+    }
+
+    /// Solves as many of the remaining opcodes as possible.
+    ///
+    /// If this returns [`PartialWitnessGeneratorStatus::RequiresOracleData`] then the output values of
+    /// [`WitnessSolver::required_oracle_data_mut`] should be filled in before calling this again.
+    /// After an error the witness may be partially updated, so the solver should be restored from a snapshot
+    /// before it is used again.
+    pub fn solve(&mut self) -> Result<PartialWitnessGeneratorStatus, OpcodeResolutionError> {
+        let opcodes: Vec<Opcode> = self
+            .required_oracle_data
+            .iter()
+            .cloned()
+            .map(Opcode::Oracle)
+            .chain(self.unsolved_opcodes.iter().cloned())
+            .collect();
+
+        let status = self
+            .backend
+            .solve(&mut self.witness, &mut self.blocks, opcodes)?;
+
+        match &status {
+            PartialWitnessGeneratorStatus::Solved => {
+                self.unsolved_opcodes.clear();
+                self.required_oracle_data.clear();
+            }
+            PartialWitnessGeneratorStatus::RequiresOracleData {
+                required_oracle_data,
+                unsolved_opcodes,
+            } => {
+                self.unsolved_opcodes = unsolved_opcodes.clone();
+                self.required_oracle_data = required_oracle_data.clone();
+            }
+        }
+        Ok(status)
+    }
+
+    /// The oracle calls which must be resolved before solving can continue.
+    pub fn required_oracle_data_mut(&mut self) -> &mut [OracleData] {
+        &mut self.required_oracle_data
+    }
+
+    /// The witness assignments made so far.
+    pub fn witness(&self) -> &BTreeMap<Witness, FieldElement> {
+        &self.witness
+    }
+
+    pub fn into_witness(self) -> BTreeMap<Witness, FieldElement> {
+        self.witness
+    }
+
+    /// Captures the current state of the solver so that it can later be returned to with [`WitnessSolver::restore`].
+    pub fn snapshot(&self) -> SolverSnapshot {
+        SolverSnapshot {
+            witness: WitnessVector::from(&self.witness),
+            unsolved_opcodes: self.unsolved_opcodes.clone(),
+            required_oracle_data: self.required_oracle_data.clone(),
+        }
+    }
+
+    /// Returns the solver to the state captured by `snapshot`.
+    pub fn restore(&mut self, snapshot: SolverSnapshot) {
+        self.witness = snapshot.witness.into();
+        self.unsolved_opcodes = snapshot.unsolved_opcodes;
+        self.required_oracle_data = snapshot.required_oracle_data;
+        // The state of a memory block is derived from the witness by replaying its trace, so it's rebuilt
+        // from the restored witness the next time the block's opcode is solved.
+        self.blocks = Blocks::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::native_types::Expression;
+
+    use super::*;
+
+    /// `_2` is provided by an oracle which is called with `_1`, and must equal 5.
+    fn opcodes() -> Vec<Opcode> {
+        let five = FieldElement::from(5_u128);
+        vec![
+            Opcode::Oracle(OracleData {
+                name: "guess".to_owned(),
+                inputs: vec![Expression {
+                    mul_terms: vec![],
+                    linear_combinations: vec![(FieldElement::one(), Witness(1))],
+                    q_c: FieldElement::zero(),
+                }],
+                input_values: vec![],
+                outputs: vec![Witness(2)],
+                output_values: vec![],
+            }),
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(FieldElement::one(), Witness(2))],
+                q_c: -five,
+            }),
+        ]
+    }
+
+    #[test]
+    fn restores_after_wrong_oracle_data() {
+        let bb = Barretenberg::new();
+        let mut solver = WitnessSolver::new(
+            &bb,
+            BTreeMap::from([(Witness(1), FieldElement::one())]),
+            opcodes(),
+        );
+
+        let status = solver.solve().unwrap();
+        assert!(matches!(
+            status,
+            PartialWitnessGeneratorStatus::RequiresOracleData { .. }
+        ));
+        let snapshot = solver.snapshot();
+
+        solver.required_oracle_data_mut()[0].output_values = vec![FieldElement::from(4_u128)];
+        assert!(solver.solve().is_err());
+
+        solver.restore(snapshot);
+        assert_eq!(solver.witness().len(), 1);
+        solver.required_oracle_data_mut()[0].output_values = vec![FieldElement::from(5_u128)];
+        assert_eq!(
+            solver.solve().unwrap(),
+            PartialWitnessGeneratorStatus::Solved
+        );
+        assert_eq!(
+            solver.into_witness()[&Witness(2)],
+            FieldElement::from(5_u128)
+        );
+    }
+}
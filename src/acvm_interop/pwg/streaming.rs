@@ -0,0 +1,109 @@
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{FieldElement, OpcodeResolutionError, PartialWitnessGeneratorStatus};
+
+use std::collections::BTreeMap;
+
+use super::rounds::Stalled;
+use crate::Barretenberg;
+
+impl Barretenberg {
+    /// Solves opcodes in the same manner as [`PartialWitnessGenerator::solve`], pulling them from `opcodes`
+    /// `chunk_size` at a time rather than requiring the whole circuit to be held in memory.
+    ///
+    /// Opcodes are dropped once solved, so only those which depend upon a witness assigned later in the circuit
+    /// are kept between chunks. If oracle data is required then solving stops and the remaining opcodes are
+    /// collected into the returned `unsolved_opcodes`.
+    ///
+    /// [`PartialWitnessGenerator::solve`]: acvm::PartialWitnessGenerator::solve
+    pub fn solve_streaming(
+        &self,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        blocks: &mut Blocks,
+        opcodes: impl IntoIterator<Item = Opcode>,
+        chunk_size: usize,
+    ) -> Result<PartialWitnessGeneratorStatus, OpcodeResolutionError> {
+        let mut opcodes = opcodes.into_iter().enumerate();
+        let mut stalled = Stalled::default();
+        loop {
+            let num_pending = stalled.opcodes.len();
+            stalled
+                .opcodes
+                .extend(opcodes.by_ref().take(chunk_size.max(1)));
+            if stalled.opcodes.len() == num_pending {
+                break;
+            }
+
+            stalled = self.solve_in_rounds(initial_witness, blocks, stalled.opcodes, &mut ())?;
+            if !stalled.required_oracle_data.is_empty() {
+                stalled.opcodes.extend(opcodes);
+                break;
+            }
+        }
+
+        // Once every opcode has been seen those remaining can't be solved, which the serial solver reports.
+        self.finish_rounds(initial_witness, blocks, stalled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acvm::acir::native_types::Expression;
+    use acvm::acir::BlackBoxFunc;
+    use acvm::PartialWitnessGenerator;
+
+    use super::*;
+
+    /// `_3 = _2 + 1` appears before the hash which assigns `_2`.
+    fn opcodes() -> Vec<Opcode> {
+        vec![
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![
+                    (FieldElement::one(), Witness(2)),
+                    (-FieldElement::one(), Witness(3)),
+                ],
+                q_c: FieldElement::one(),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                name: BlackBoxFunc::Pedersen,
+                inputs: vec![FunctionInput {
+                    witness: Witness(1),
+                    num_bits: FieldElement::max_num_bits(),
+                }],
+                outputs: vec![Witness(2), Witness(4)],
+            }),
+        ]
+    }
+
+    #[test]
+    fn streaming_solve_matches_serial_solve() -> Result<(), OpcodeResolutionError> {
+        let bb = Barretenberg::new();
+        let initial_witness = BTreeMap::from([(Witness(1), FieldElement::from(5_u128))]);
+
+        let mut serial_witness = initial_witness.clone();
+        bb.solve(&mut serial_witness, &mut Blocks::default(), opcodes())?;
+
+        let mut streamed_witness = initial_witness;
+        let status =
+            bb.solve_streaming(&mut streamed_witness, &mut Blocks::default(), opcodes(), 1)?;
+
+        assert_eq!(status, PartialWitnessGeneratorStatus::Solved);
+        assert_eq!(streamed_witness, serial_witness);
+        Ok(())
+    }
+
+    #[test]
+    fn streaming_solve_reports_unsolvable_opcodes() {
+        let bb = Barretenberg::new();
+
+        let result = bb.solve_streaming(&mut BTreeMap::new(), &mut Blocks::default(), opcodes(), 8);
+
+        assert!(matches!(
+            result,
+            Err(OpcodeResolutionError::OpcodeNotSolvable(_))
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+use acvm::acir::circuit::Opcode;
+use acvm::acir::native_types::Witness;
+use acvm::acir::BlackBoxFunc;
+use acvm::pwg::block::Blocks;
+use acvm::{FieldElement, OpcodeResolutionError, PartialWitnessGeneratorStatus};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::rounds::SolveHooks;
+use crate::Barretenberg;
+
+/// The result of a single attempt to solve an opcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceOutcome {
+    /// All of the opcode's outputs were assigned.
+    Solved,
+    /// The opcode could not be solved yet, it will be attempted again once more witnesses have been assigned.
+    Stalled,
+    /// Solving the opcode resulted in an error.
+    Failed,
+}
+
+/// A record of a single attempt to solve an opcode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// The position of the opcode within the circuit.
+    pub opcode_index: usize,
+    /// The black box function called by the opcode, if any.
+    pub black_box_func: Option<BlackBoxFunc>,
+    /// The assigned witnesses referenced by the opcode before it was solved.
+    pub inputs: Vec<(Witness, FieldElement)>,
+    /// The witnesses which were assigned by solving the opcode.
+    pub outputs: Vec<(Witness, FieldElement)>,
+    pub outcome: TraceOutcome,
+}
+
+/// The sequence of opcode resolutions performed by [`Barretenberg::solve_with_trace`], in the order they occurred.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveTrace {
+    pub entries: Vec<TraceEntry>,
+}
+
+/// An error encountered whilst solving a circuit along with the trace of the solve up to that point.
+#[derive(Debug, thiserror::Error)]
+#[error("{error}")]
+pub struct TracedSolveError {
+    #[source]
+    pub error: OpcodeResolutionError,
+    pub trace: SolveTrace,
+}
+
+impl Barretenberg {
+    /// Solves `opcodes` in the same manner as [`PartialWitnessGenerator::solve`] whilst recording a [`SolveTrace`].
+    ///
+    /// This is significantly slower than [`PartialWitnessGenerator::solve`] so should only be used to debug
+    /// circuits which fail to solve.
+    ///
+    /// [`PartialWitnessGenerator::solve`]: acvm::PartialWitnessGenerator::solve
+    pub fn solve_with_trace(
+        &self,
+        initial_witness: &mut BTreeMap<Witness, FieldElement>,
+        blocks: &mut Blocks,
+        opcodes: Vec<Opcode>,
+    ) -> Result<(PartialWitnessGeneratorStatus, SolveTrace), TracedSolveError> {
+        let mut tracer = Tracer::default();
+        let result = self
+            .solve_in_rounds(
+                initial_witness,
+                blocks,
+                opcodes.into_iter().enumerate().collect(),
+                &mut tracer,
+            )
+            .and_then(|stalled| self.finish_rounds(initial_witness, blocks, stalled));
+        match result {
+            Ok(status) => Ok((status, tracer.trace)),
+            Err(error) => Err(TracedSolveError {
+                error,
+                trace: tracer.trace,
+            }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tracer {
+    trace: SolveTrace,
+    /// The witnesses referenced by the opcode currently being solved, see [`referenced_witnesses`].
+    referenced_witnesses: Option<Vec<Witness>>,
+    /// The values of `referenced_witnesses` before the opcode was solved.
+    inputs: Vec<(Witness, FieldElement)>,
+    /// Every assigned witness before the opcode was solved, used if `referenced_witnesses` is `None`.
+    assigned_before: BTreeSet<Witness>,
+}
+
+impl SolveHooks for Tracer {
+    fn before_opcode(
+        &mut self,
+        _opcode_index: usize,
+        opcode: &Opcode,
+        initial_witness: &BTreeMap<Witness, FieldElement>,
+    ) {
+        self.referenced_witnesses = referenced_witnesses(opcode);
+        self.inputs = self
+            .referenced_witnesses
+            .as_ref()
+            .map(|witnesses| assigned(initial_witness, witnesses))
+            .unwrap_or_default();
+        self.assigned_before = match self.referenced_witnesses {
+            Some(_) => BTreeSet::new(),
+            None => initial_witness.keys().copied().collect(),
+        };
+    }
+
+    fn after_opcode(
+        &mut self,
+        opcode_index: usize,
+        opcode: &Opcode,
+        initial_witness: &BTreeMap<Witness, FieldElement>,
+        outcome: TraceOutcome,
+    ) {
+        let inputs = std::mem::take(&mut self.inputs);
+        let outputs = match self.referenced_witnesses.take() {
+            Some(witnesses) => assigned(initial_witness, &witnesses)
+                .into_iter()
+                .filter(|(witness, _)| !inputs.iter().any(|(input, _)| input == witness))
+                .collect(),
+            None => initial_witness
+                .iter()
+                .filter(|(witness, _)| !self.assigned_before.contains(witness))
+                .map(|(witness, value)| (*witness, *value))
+                .collect(),
+        };
+        self.trace.entries.push(TraceEntry {
+            opcode_index,
+            black_box_func: match opcode {
+                Opcode::BlackBoxFuncCall(func_call) => Some(func_call.name),
+                _ => None,
+            },
+            inputs,
+            outputs,
+            outcome,
+        });
+    }
+}
+
+/// Returns the witnesses which `opcode` may read or write.
+///
+/// Returns `None` for opcodes where these can't be determined cheaply, in which case the witness map is diffed instead.
+pub(crate) fn referenced_witnesses(opcode: &Opcode) -> Option<Vec<Witness>> {
+    match opcode {
+        Opcode::Arithmetic(expr) => Some(
+            expr.mul_terms
+                .iter()
+                .flat_map(|(_, lhs, rhs)| [*lhs, *rhs])
+                .chain(expr.linear_combinations.iter().map(|(_, witness)| *witness))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        ),
+        Opcode::BlackBoxFuncCall(func_call) => Some(
+            func_call
+                .inputs
+                .iter()
+                .map(|input| input.witness)
+                .chain(func_call.outputs.iter().copied())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+pub(super) fn assigned(
+    witness_assignments: &BTreeMap<Witness, FieldElement>,
+    witnesses: &[Witness],
+) -> Vec<(Witness, FieldElement)> {
+    witnesses
+        .iter()
+        .filter_map(|witness| {
+            witness_assignments
+                .get(witness)
+                .map(|value| (*witness, *value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acvm::acir::native_types::Expression;
+
+    use super::*;
+
+    #[test]
+    fn records_opcode_resolutions() {
+        let bb = Barretenberg::new();
+        let opcodes = vec![
+            // Witness(3) = Witness(2) + 1, which stalls until Witness(2) is assigned below.
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![
+                    (FieldElement::one(), Witness(2)),
+                    (-FieldElement::one(), Witness(3)),
+                ],
+                q_c: FieldElement::one(),
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                name: BlackBoxFunc::HashToField128Security,
+                inputs: vec![FunctionInput {
+                    witness: Witness(1),
+                    num_bits: 8,
+                }],
+                outputs: vec![Witness(2)],
+            }),
+        ];
+        let mut witness = BTreeMap::from([(Witness(1), FieldElement::from(7_u128))]);
+
+        let (status, trace) = bb
+            .solve_with_trace(&mut witness, &mut Blocks::default(), opcodes)
+            .unwrap();
+
+        assert_eq!(status, PartialWitnessGeneratorStatus::Solved);
+        let summary: Vec<_> = trace
+            .entries
+            .iter()
+            .map(|entry| (entry.opcode_index, entry.outcome))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (0, TraceOutcome::Stalled),
+                (1, TraceOutcome::Solved),
+                (0, TraceOutcome::Solved)
+            ]
+        );
+        assert_eq!(
+            trace.entries[1].black_box_func,
+            Some(BlackBoxFunc::HashToField128Security)
+        );
+        assert_eq!(
+            trace.entries[1].inputs,
+            vec![(Witness(1), FieldElement::from(7_u128))]
+        );
+        assert_eq!(
+            trace.entries[1].outputs,
+            vec![(Witness(2), witness[&Witness(2)])]
+        );
+        assert_eq!(
+            trace.entries[2].outputs,
+            vec![(Witness(3), witness[&Witness(3)])]
+        );
+    }
+
+    #[test]
+    fn returns_trace_on_failure() {
+        let bb = Barretenberg::new();
+        // Witness(1) = 2 is unsatisfiable as Witness(1) is already assigned to 1.
+        let opcodes = vec![Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(FieldElement::one(), Witness(1))],
+            q_c: -(FieldElement::one() + FieldElement::one()),
+        })];
+        let mut witness = BTreeMap::from([(Witness(1), FieldElement::one())]);
+
+        let err = bb
+            .solve_with_trace(&mut witness, &mut Blocks::default(), opcodes)
+            .unwrap_err();
+
+        assert!(matches!(
+            err.error,
+            OpcodeResolutionError::UnsatisfiedConstrain
+        ));
+        assert_eq!(err.trace.entries.len(), 1);
+        assert_eq!(err.trace.entries[0].outcome, TraceOutcome::Failed);
+        assert_eq!(
+            err.trace.entries[0].inputs,
+            vec![(Witness(1), FieldElement::one())]
+        );
+    }
+}
@@ -1,11 +1,17 @@
-use acvm::SmartContract;
+use acvm::{FieldElement, SmartContract};
 
-use crate::crs::G2;
-use crate::{BackendError, Barretenberg};
+use crate::field::field_from_be_bytes;
+use crate::{BackendError, Barretenberg, Error, Proof, VerificationKey};
 
 /// Embed the Solidity verifier file
 const ULTRA_VERIFIER_CONTRACT: &str = include_str!("contract.sol");
 
+/// The function selector for `verify(bytes,bytes32[])` on the Solidity verifier.
+const VERIFY_SELECTOR: [u8; 4] = [0xea, 0x50, 0xd0, 0xe4];
+
+/// The number of bytes in a single word of ABI encoded calldata.
+const WORD_BYTES: usize = 32;
+
 #[cfg(feature = "native")]
 impl SmartContract for Barretenberg {
     type Error = BackendError;
@@ -13,7 +19,7 @@ impl SmartContract for Barretenberg {
     fn eth_contract_from_vk(&self, verification_key: &[u8]) -> Result<String, Self::Error> {
         use std::slice;
 
-        let g2 = G2::new();
+        let g2 = self.g2();
 
         let mut contract_ptr: *mut u8 = std::ptr::null_mut();
         let p_contract_ptr = &mut contract_ptr as *mut *mut u8;
@@ -41,7 +47,9 @@ impl SmartContract for Barretenberg {
     type Error = BackendError;
 
     fn eth_contract_from_vk(&self, verification_key: &[u8]) -> Result<String, Self::Error> {
-        let g2 = G2::new();
+        use crate::composer::WasmBufferHandle;
+
+        let g2 = self.g2();
 
         let g2_ptr = self.allocate(&g2.data)?;
         let vk_ptr = self.allocate(verification_key)?;
@@ -50,16 +58,27 @@ impl SmartContract for Barretenberg {
         // `contract_ptr_ptr` is a pointer to a pointer which holds the smart contract string.
         let contract_ptr_ptr: usize = 0;
 
+        // This doesn't unwrap the result because we need to free even if there is a failure
         let contract_size = self.call_multiple(
             "acir_proofs_get_solidity_verifier",
             vec![&g2_ptr, &vk_ptr, &contract_ptr_ptr.into()],
-        )?;
+        );
 
-        // We then need to read the pointer at `contract_ptr_ptr` to get the smart contract's location
-        // and then slice memory again at `contract_ptr_ptr` to get the smart contract string.
-        let contract_ptr = self.get_pointer(contract_ptr_ptr);
+        // A failed call poisons the backend, so its error is returned rather than that from `free`.
+        let g2_freed = self.free(g2_ptr);
+        let vk_freed = self.free(vk_ptr);
+        let contract_size = contract_size?;
+        g2_freed?;
+        vk_freed?;
 
-        let sc_as_bytes = self.read_memory_variable_length(contract_ptr, contract_size.try_into()?);
+        // We then need to read the pointer at `contract_ptr_ptr` to get the smart contract's location
+        // and then slice memory again at `contract_ptr` to get the smart contract string.
+        let sc_as_bytes = WasmBufferHandle {
+            barretenberg: self,
+            ptr: self.get_pointer(contract_ptr_ptr),
+            len: contract_size.try_into()?,
+        }
+        .to_vec();
 
         let verification_key_library: String = sc_as_bytes.iter().map(|b| *b as char).collect();
         Ok(format!(
@@ -68,6 +87,150 @@ impl SmartContract for Barretenberg {
     }
 }
 
+impl Barretenberg {
+    /// Verifies a proof which has been ABI encoded as calldata for the Solidity verifier's `verify` function.
+    ///
+    /// The proof and public inputs are read out of `calldata` following the same layout rules as the
+    /// verifier contract, so this can be used to check a transaction before submitting it on-chain. As with the
+    /// contract, only the verification key is needed. Public inputs which aren't canonical field elements are
+    /// rejected rather than reduced, since the contract would reject them too.
+    pub fn verify_evm_calldata(
+        &self,
+        calldata: &[u8],
+        verification_key: &VerificationKey,
+    ) -> Result<bool, BackendError> {
+        let (proof, public_inputs) = decode_verify_calldata(calldata)?;
+
+        self.verify_with_vk_standalone(&Proof(proof), public_inputs, verification_key)
+    }
+}
+
+/// Splits calldata for `verify(bytes,bytes32[])` into the proof and its public inputs.
+fn decode_verify_calldata(calldata: &[u8]) -> Result<(Vec<u8>, Vec<FieldElement>), Error> {
+    let (selector, args) = if calldata.len() >= VERIFY_SELECTOR.len() {
+        calldata.split_at(VERIFY_SELECTOR.len())
+    } else {
+        return Err(Error::MalformedCalldata(
+            "calldata is too short to contain a function selector".into(),
+        ));
+    };
+    if selector != VERIFY_SELECTOR {
+        return Err(Error::MalformedCalldata(format!(
+            "expected function selector 0x{} but found 0x{}",
+            to_hex(&VERIFY_SELECTOR),
+            to_hex(selector)
+        )));
+    }
+
+    // The head of the calldata holds the offsets of the two dynamic arguments.
+    let proof_offset = read_word_as_usize(args, 0)?;
+    let public_inputs_offset = read_word_as_usize(args, WORD_BYTES)?;
+
+    // `bytes` is encoded as its length followed by the (padded) data.
+    let proof_len = read_word_as_usize(args, proof_offset)?;
+    let proof_start = proof_offset + WORD_BYTES;
+    let proof = proof_start
+        .checked_add(proof_len)
+        .and_then(|proof_end| args.get(proof_start..proof_end))
+        .ok_or_else(|| {
+            Error::MalformedCalldata(format!(
+                "proof of {proof_len} bytes overruns calldata of {} bytes",
+                args.len()
+            ))
+        })?;
+
+    // `bytes32[]` is encoded as its length followed by each element.
+    let num_public_inputs = read_word_as_usize(args, public_inputs_offset)?;
+    let mut public_inputs = Vec::new();
+    let mut public_input_offset = public_inputs_offset;
+    for _ in 0..num_public_inputs {
+        public_input_offset += WORD_BYTES;
+        let public_input = read_word(args, public_input_offset)?;
+        public_inputs.push(field_from_be_bytes(public_input)?);
+    }
+
+    Ok((proof.to_vec(), public_inputs))
+}
+
+/// Reads the 32 byte word starting at `offset`.
+fn read_word(args: &[u8], offset: usize) -> Result<&[u8], Error> {
+    offset
+        .checked_add(WORD_BYTES)
+        .and_then(|end| args.get(offset..end))
+        .ok_or_else(|| {
+            Error::MalformedCalldata(format!(
+                "expected a word at offset {offset} but calldata is only {} bytes",
+                args.len()
+            ))
+        })
+}
+
+/// Reads the 32 byte word starting at `offset` as an offset or length.
+fn read_word_as_usize(args: &[u8], offset: usize) -> Result<usize, Error> {
+    let word = read_word(args, offset)?;
+    let (high_bytes, low_bytes) = word.split_at(WORD_BYTES - std::mem::size_of::<u32>());
+    if high_bytes.iter().any(|byte| *byte != 0) {
+        return Err(Error::MalformedCalldata(format!(
+            "value at offset {offset} is too large to be an offset or length"
+        )));
+    }
+
+    let value = u32::from_be_bytes(low_bytes.try_into().expect("slice should be 4 bytes"));
+    // Every offset or length must still land inside of the calldata so this cannot overflow later additions.
+    if value as usize > args.len() {
+        return Err(Error::MalformedCalldata(format!(
+            "value {value} at offset {offset} points outside of calldata"
+        )));
+    }
+    Ok(value as usize)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[test]
+fn decodes_verify_calldata() -> Result<(), Error> {
+    let proof = vec![7u8; 40];
+    let public_inputs = vec![FieldElement::one(), FieldElement::from(2_i128)];
+
+    // selector | proof offset | public inputs offset | proof length | proof (padded) | num public inputs | public inputs
+    let mut calldata = VERIFY_SELECTOR.to_vec();
+    let word = |value: usize| {
+        let mut word = [0u8; WORD_BYTES];
+        word[WORD_BYTES - 8..].copy_from_slice(&(value as u64).to_be_bytes());
+        word
+    };
+    let padded_proof_len = 64;
+    calldata.extend(word(2 * WORD_BYTES));
+    calldata.extend(word(3 * WORD_BYTES + padded_proof_len));
+    calldata.extend(word(proof.len()));
+    calldata.extend(&proof);
+    calldata.extend(vec![0u8; padded_proof_len - proof.len()]);
+    calldata.extend(word(public_inputs.len()));
+    for public_input in &public_inputs {
+        calldata.extend(public_input.to_be_bytes());
+    }
+
+    let (decoded_proof, decoded_public_inputs) = decode_verify_calldata(&calldata)?;
+    assert_eq!(decoded_proof, proof);
+    assert_eq!(decoded_public_inputs, public_inputs);
+
+    // Truncated calldata must be rejected rather than panicking
+    assert!(decode_verify_calldata(&calldata[..calldata.len() - 1]).is_err());
+    assert!(decode_verify_calldata(&calldata[1..]).is_err());
+
+    // Public inputs of at least the field modulus must be rejected rather than reduced
+    let last_word = calldata.len() - WORD_BYTES;
+    calldata[last_word..].fill(0xff);
+    assert!(matches!(
+        decode_verify_calldata(&calldata),
+        Err(Error::NonCanonicalFieldElement)
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn test_smart_contract() -> Result<(), BackendError> {
     use crate::barretenberg_structures::{Constraint, ConstraintSystem};
@@ -0,0 +1,150 @@
+//! Static checks of whether a circuit can be handled by this backend, without needing to preprocess it.
+
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::circuit::{Circuit, Opcode};
+use acvm::acir::BlackBoxFunc;
+
+use crate::acvm_interop::black_box_function_supported;
+
+/// An opcode which this backend can't solve or prove.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedOpcode {
+    pub opcode_index: usize,
+    pub opcode: Opcode,
+    pub reason: String,
+}
+
+/// Returns every opcode in `circuit` which would prevent it from being solved or soundly proven by this backend.
+///
+/// Conversion to barretenberg's constraint system stops at the first unsupported opcode, whereas this reports all
+/// of them at once so that they can be fixed together.
+pub fn unsupported_opcodes(circuit: &Circuit) -> Vec<UnsupportedOpcode> {
+    circuit
+        .opcodes
+        .iter()
+        .enumerate()
+        .filter_map(|(opcode_index, opcode)| {
+            let reason = unsupported_reason(opcode)?;
+            Some(UnsupportedOpcode {
+                opcode_index,
+                opcode: opcode.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+fn unsupported_reason(opcode: &Opcode) -> Option<String> {
+    match opcode {
+        Opcode::Arithmetic(_) | Opcode::Directive(_) | Opcode::Oracle(_) => None,
+        Opcode::BlackBoxFuncCall(gadget_call) => black_box_reason(gadget_call),
+        Opcode::Block(_) | Opcode::RAM(_) | Opcode::ROM(_) => Some(
+            "memory opcodes can be solved but aren't yet passed to barretenberg, so a proof wouldn't enforce them"
+                .to_owned(),
+        ),
+    }
+}
+
+fn black_box_reason(gadget_call: &BlackBoxFuncCall) -> Option<String> {
+    let name = gadget_call.name;
+    if !black_box_function_supported(&name) {
+        return Some(format!("the {name} black box function is not supported"));
+    }
+
+    let (num_inputs, num_outputs) = (gadget_call.inputs.len(), gadget_call.outputs.len());
+    let expected = match name {
+        BlackBoxFunc::RANGE => {
+            (num_inputs != 1 || num_outputs != 0).then_some("1 input and no outputs")
+        }
+        BlackBoxFunc::AND | BlackBoxFunc::XOR => {
+            if num_inputs != 2 || num_outputs != 1 {
+                Some("2 inputs and 1 output")
+            } else if gadget_call.inputs[0].num_bits != gadget_call.inputs[1].num_bits {
+                Some("both inputs to have the same number of bits")
+            } else {
+                None
+            }
+        }
+        BlackBoxFunc::SHA256 | BlackBoxFunc::Blake2s | BlackBoxFunc::Keccak256 => {
+            (num_outputs != 32).then_some("32 outputs")
+        }
+        BlackBoxFunc::HashToField128Security => (num_outputs != 1).then_some("1 output"),
+        BlackBoxFunc::FixedBaseScalarMul => {
+            (num_inputs != 1 || num_outputs != 2).then_some("1 input and 2 outputs")
+        }
+        BlackBoxFunc::ComputeMerkleRoot => {
+            (num_inputs < 3).then_some("a leaf, an index and at least one hash path element")
+        }
+        BlackBoxFunc::SchnorrVerify
+        | BlackBoxFunc::Pedersen
+        | BlackBoxFunc::EcdsaSecp256k1
+        | BlackBoxFunc::AES => None,
+    }?;
+
+    Some(format!(
+        "{name} expects {expected} but was called with {num_inputs} inputs and {num_outputs} outputs"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::{BlockId, FunctionInput, MemoryBlock};
+    use acvm::acir::native_types::Witness;
+
+    use super::*;
+    use crate::test_circuits::{
+        arithmetic_gates, merkle_membership, pedersen_hashes, sha256_chain,
+    };
+
+    fn black_box(name: BlackBoxFunc, num_inputs: u32, num_outputs: u32) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name,
+            inputs: (1..=num_inputs)
+                .map(|index| FunctionInput {
+                    witness: Witness(index),
+                    num_bits: 8,
+                })
+                .collect(),
+            outputs: (1..=num_outputs)
+                .map(|index| Witness(100 + index))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn supported_circuits_have_no_unsupported_opcodes() {
+        for test_circuit in [
+            pedersen_hashes(2),
+            sha256_chain(2),
+            merkle_membership(3),
+            arithmetic_gates(2),
+        ] {
+            assert_eq!(unsupported_opcodes(&test_circuit.circuit), vec![]);
+        }
+    }
+
+    #[test]
+    fn reports_every_unsupported_opcode() {
+        let mut circuit = arithmetic_gates(1).circuit;
+        circuit.opcodes.extend([
+            black_box(BlackBoxFunc::AES, 2, 2),
+            black_box(BlackBoxFunc::RANGE, 1, 0),
+            black_box(BlackBoxFunc::SHA256, 4, 31),
+            Opcode::Block(MemoryBlock {
+                id: BlockId::default(),
+                len: 1,
+                trace: Vec::new(),
+            }),
+        ]);
+
+        let unsupported = unsupported_opcodes(&circuit);
+
+        let indices: Vec<_> = unsupported
+            .iter()
+            .map(|opcode| opcode.opcode_index)
+            .collect();
+        assert_eq!(indices, vec![2, 4, 5]);
+        assert_eq!(unsupported[0].opcode, circuit.opcodes[2]);
+        assert!(unsupported[1].reason.contains("32 outputs"));
+    }
+}
@@ -0,0 +1,332 @@
+//! Typed wrappers around the byte strings passed through [`ProofSystemCompiler`][acvm::ProofSystemCompiler].
+//!
+//! The trait takes proving keys, verification keys and proofs as plain `&[u8]`, so nothing stops a proving key
+//! being passed where a verification key is expected. [`ProvingKey`], [`VerificationKey`] and [`Proof`] can only
+//! be used in their intended place, and are stored with a [header][crate::framing] identifying what they contain
+//! so that a file holding the wrong kind of artifact is rejected when it's loaded rather than causing barretenberg
+//! to fail.
+//!
+//! Each type can be converted to and from the raw bytes used by the trait: `ProvingKey::from_bytes` and
+//! `AsRef<[u8]>`, `VerificationKey::from_bytes` and `to_bytes`, and the `Vec<u8>` held by a `Proof`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::{FieldElement, ProofSystemCompiler};
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::framing::{self, Framing};
+use crate::{BackendError, Barretenberg, Error, Proof, VerificationKey};
+
+/// A proving key produced by [`Barretenberg::preprocess_keys`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProvingKey(pub(crate) Vec<u8>);
+
+trait Artifact: Sized {
+    const FRAMING: Framing;
+
+    fn to_raw_bytes(&self) -> Vec<u8>;
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, BackendError>;
+
+    fn encode(&self) -> Vec<u8> {
+        Self::FRAMING.frame(&self.to_raw_bytes())
+    }
+
+    fn decode(framed: &[u8]) -> Result<Self, BackendError> {
+        let bytes = Self::FRAMING
+            .unframe(framed)
+            .map_err(Error::MalformedArtifact)?;
+        Self::from_raw_bytes(bytes)
+    }
+}
+
+impl Artifact for ProvingKey {
+    const FRAMING: Framing = framing::PROVING_KEY;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, BackendError> {
+        Ok(ProvingKey(bytes.to_vec()))
+    }
+}
+
+impl Artifact for VerificationKey {
+    const FRAMING: Framing = framing::VERIFICATION_KEY;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, BackendError> {
+        VerificationKey::from_bytes(bytes)
+    }
+}
+
+impl Artifact for Proof {
+    const FRAMING: Framing = framing::PROOF;
+
+    fn to_raw_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_raw_bytes(bytes: &[u8]) -> Result<Self, BackendError> {
+        Ok(Proof(bytes.to_vec()))
+    }
+}
+
+impl ProvingKey {
+    /// Wraps the raw bytes of a proving key, as returned by [`ProofSystemCompiler::preprocess`].
+    pub fn from_bytes(bytes: Vec<u8>) -> ProvingKey {
+        ProvingKey(bytes)
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    /// Encodes the key with a `BBPK` header for storage.
+    pub fn to_versioned_bytes(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// Decodes a key written by [`to_versioned_bytes`][ProvingKey::to_versioned_bytes].
+    pub fn from_versioned_bytes(versioned: &[u8]) -> Result<ProvingKey, BackendError> {
+        Artifact::decode(versioned)
+    }
+}
+
+impl VerificationKey {
+    /// Encodes the key with a `BBVK` header for storage.
+    pub fn to_versioned_bytes(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// Decodes a key written by [`to_versioned_bytes`][VerificationKey::to_versioned_bytes].
+    pub fn from_versioned_bytes(versioned: &[u8]) -> Result<VerificationKey, BackendError> {
+        Artifact::decode(versioned)
+    }
+}
+
+impl Proof {
+    /// Encodes the proof with a `BBPF` header for storage.
+    pub fn to_versioned_bytes(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// Decodes a proof written by [`to_versioned_bytes`][Proof::to_versioned_bytes].
+    pub fn from_versioned_bytes(versioned: &[u8]) -> Result<Proof, BackendError> {
+        Artifact::decode(versioned)
+    }
+}
+
+impl AsRef<[u8]> for ProvingKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Proof {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ProvingKey {
+    // Proving keys are hundreds of megabytes for large circuits, which isn't useful to print.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ProvingKey({} bytes)", self.0.len())
+    }
+}
+
+// Artifacts are serialized as their versioned bytes, so that a deserialized value always has the expected type.
+
+impl Serialize for ProvingKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_versioned_bytes())
+    }
+}
+
+impl Serialize for VerificationKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_versioned_bytes())
+    }
+}
+
+impl Serialize for Proof {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.to_versioned_bytes())
+    }
+}
+
+impl<'de> Deserialize<'de> for ProvingKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_artifact(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for VerificationKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_artifact(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Proof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_artifact(deserializer)
+    }
+}
+
+fn deserialize_artifact<'de, A: Artifact, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<A, D::Error> {
+    let versioned = deserializer.deserialize_bytes(BytesVisitor)?;
+    A::decode(&versioned).map_err(de::Error::custom)
+}
+
+/// Accepts byte strings from formats which support them and sequences of bytes from those which don't (e.g. JSON).
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(bytes.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, bytes: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(bytes)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Vec<u8>, S::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+impl Barretenberg {
+    /// Typed equivalent of [`ProofSystemCompiler::preprocess`].
+    pub fn preprocess_keys(
+        &self,
+        circuit: &Circuit,
+    ) -> Result<(ProvingKey, VerificationKey), BackendError> {
+        let (proving_key, verification_key) = self.preprocess(circuit)?;
+        Ok((
+            ProvingKey(proving_key),
+            VerificationKey::from_bytes(&verification_key)?,
+        ))
+    }
+
+    /// Typed equivalent of [`ProofSystemCompiler::prove_with_pk`].
+    ///
+    /// The proof is returned with the values of the circuit's public inputs prepended, as barretenberg's own
+    /// tooling does, so fails if any public input is missing from `witness_values`.
+    pub fn prove(
+        &self,
+        circuit: &Circuit,
+        witness_values: BTreeMap<Witness, FieldElement>,
+        proving_key: &ProvingKey,
+    ) -> Result<Proof, BackendError> {
+        let (proof, public_inputs) =
+            self.prove_with_public_inputs(circuit, witness_values, proving_key)?;
+        let mut proof_with_public_inputs: Vec<u8> = public_inputs
+            .values()
+            .flat_map(FieldElement::to_be_bytes)
+            .collect();
+        proof_with_public_inputs.extend_from_slice(&proof.0);
+        Ok(Proof(proof_with_public_inputs))
+    }
+
+    /// Typed equivalent of [`ProofSystemCompiler::verify_with_vk`].
+    ///
+    /// The public inputs held by `proof` are only checked against `public_inputs`, never trusted in their place.
+    pub fn verify(
+        &self,
+        proof: &Proof,
+        public_inputs: BTreeMap<Witness, FieldElement>,
+        circuit: &Circuit,
+        verification_key: &VerificationKey,
+    ) -> Result<bool, BackendError> {
+        self.verify_with_vk(
+            &proof.0,
+            public_inputs,
+            circuit,
+            &verification_key.to_bytes(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::pwg::block::Blocks;
+    use acvm::PartialWitnessGenerator;
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    #[test]
+    fn versioned_bytes_round_trip() -> Result<(), BackendError> {
+        let proving_key = ProvingKey::from_bytes(vec![1, 2, 3]);
+
+        let versioned = proving_key.to_versioned_bytes();
+
+        assert_eq!(versioned[..4], *b"BBPK");
+        assert_eq!(ProvingKey::from_versioned_bytes(&versioned)?, proving_key);
+        // A proving key can't be loaded as a verification key or proof.
+        assert!(VerificationKey::from_versioned_bytes(&versioned).is_err());
+        assert!(Proof::from_versioned_bytes(&versioned).is_err());
+
+        let mut wrong_version = versioned;
+        wrong_version[7] = 2;
+        assert!(ProvingKey::from_versioned_bytes(&wrong_version).is_err());
+        assert!(ProvingKey::from_versioned_bytes(b"BBPK").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let proof = Proof(vec![4, 5, 6]);
+
+        let json = serde_json::to_string(&proof).unwrap();
+        assert_eq!(serde_json::from_str::<Proof>(&json).unwrap(), proof);
+        assert!(serde_json::from_str::<VerificationKey>(&json).is_err());
+
+        let msgpack = rmp_serde::to_vec(&proof).unwrap();
+        assert_eq!(rmp_serde::from_slice::<Proof>(&msgpack).unwrap(), proof);
+    }
+
+    #[test]
+    fn typed_prove_and_verify() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let test_circuit = arithmetic_gates(2);
+        let circuit = test_circuit.circuit;
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .unwrap();
+
+        let (proving_key, verification_key) = bb.preprocess_keys(&circuit)?;
+        let proof = bb.prove(&circuit, witness, &proving_key)?;
+
+        assert!(bb.verify(&proof, BTreeMap::new(), &circuit, &verification_key)?);
+        assert_eq!(
+            VerificationKey::from_versioned_bytes(&verification_key.to_versioned_bytes())?,
+            verification_key
+        );
+        Ok(())
+    }
+}
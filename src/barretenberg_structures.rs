@@ -1,9 +1,12 @@
 use acvm::acir::circuit::{Circuit, Opcode};
-use acvm::acir::native_types::Expression;
+use acvm::acir::native_types::{Expression, Witness};
 use acvm::acir::BlackBoxFunc;
 use acvm::FieldElement;
+use std::collections::BTreeMap;
 
-use crate::Error;
+use crate::constraint_map::{ConstraintKind, ConstraintMap};
+use crate::framing;
+use crate::{Barretenberg, Error, WitnessVector};
 
 #[derive(Debug, Default, Clone)]
 pub(crate) struct Assignments(Vec<FieldElement>);
@@ -14,11 +17,15 @@ impl Assignments {
     pub(crate) fn new() -> Assignments {
         Assignments::default()
     }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Assignments {
+        Assignments(Vec::with_capacity(capacity))
+    }
 }
 
 impl Assignments {
     pub(crate) fn to_bytes(&self) -> Vec<u8> {
-        let mut buffer = Vec::new();
+        let mut buffer = Vec::with_capacity(4 + self.0.len() * crate::FIELD_BYTES);
 
         let witness_len = self.0.len() as u32;
         buffer.extend_from_slice(&witness_len.to_be_bytes());
@@ -30,9 +37,31 @@ impl Assignments {
         buffer
     }
 
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.0.len()
+    }
+
     pub(crate) fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    /// Appends the value of each witness in `witnesses`, in order, filling in zero for any witness for which
+    /// `witness_value` returns `None` such as those left unsolved because they're unused.
+    pub(crate) fn extend_from_witness_range(
+        &mut self,
+        witnesses: std::ops::Range<u32>,
+        witness_value: impl Fn(Witness) -> Option<FieldElement>,
+    ) {
+        self.0.extend(witnesses.map(|witness_index| {
+            witness_value(Witness(witness_index)).unwrap_or(FieldElement::zero())
+        }));
+    }
+
+    /// Overwrites all assignments with zero so that secret witness values don't linger in memory.
+    pub(crate) fn zeroize(&mut self) {
+        crate::secret::zeroize_field_elements(self.0.iter_mut());
+    }
 }
 
 impl IntoIterator for Assignments {
@@ -414,6 +443,11 @@ impl LogicConstraint {
     }
 }
 
+/// Returns `true` if `bytes` begin with the header written by [`ConstraintSystem::to_versioned_bytes`].
+pub(crate) fn is_versioned_constraint_system(bytes: &[u8]) -> bool {
+    framing::CONSTRAINT_SYSTEM.is_framed(bytes)
+}
+
 #[derive(Clone, Hash, Debug, Default)]
 pub(crate) struct ConstraintSystem {
     var_num: u32,
@@ -645,14 +679,268 @@ impl ConstraintSystem {
     }
 }
 
+/// Reads the big-endian encoding produced by the `to_bytes` methods above.
+struct ConstraintSystemReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl ConstraintSystemReader<'_> {
+    fn read_bytes<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], Error> {
+        let bytes = self
+            .bytes
+            .get(self.offset..self.offset + SIZE)
+            .ok_or_else(|| {
+                Error::MalformedConstraintSystem(format!(
+                    "unexpected end of constraint system at byte {}",
+                    self.offset
+                ))
+            })?;
+        self.offset += SIZE;
+        Ok(bytes.try_into().expect("slice has the requested length"))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, Error> {
+        Ok(i32::from_be_bytes(self.read_bytes()?))
+    }
+
+    fn read_field(&mut self) -> Result<FieldElement, Error> {
+        Ok(FieldElement::from_be_bytes_reduce(
+            &self.read_bytes::<32>()?,
+        ))
+    }
+
+    fn read_vec<T>(
+        &mut self,
+        mut read_element: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        let len = self.read_u32()?;
+        // Every element takes at least 4 bytes, so a length which couldn't fit in the remaining bytes is rejected
+        // before allocating for it.
+        if len as usize > (self.bytes.len() - self.offset) / 4 {
+            return Err(Error::MalformedConstraintSystem(format!(
+                "length {len} at byte {} exceeds the size of the constraint system",
+                self.offset - 4
+            )));
+        }
+        (0..len).map(|_| read_element(self)).collect()
+    }
+
+    fn read_witnesses<const SIZE: usize>(&mut self) -> Result<[i32; SIZE], Error> {
+        let witnesses = self.read_vec(Self::read_i32)?;
+        let len = witnesses.len();
+        witnesses.try_into().map_err(|_| {
+            Error::MalformedConstraintSystem(format!("expected {SIZE} witnesses but found {len}"))
+        })
+    }
+
+    fn read_byte_inputs(&mut self) -> Result<Vec<(i32, i32)>, Error> {
+        self.read_vec(|reader| Ok((reader.read_i32()?, reader.read_i32()?)))
+    }
+}
+
+impl ConstraintSystem {
+    /// Parses a constraint system from the format produced by [`ConstraintSystem::to_bytes`], which is the format
+    /// barretenberg's `acir_format` expects.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<ConstraintSystem, Error> {
+        let reader = &mut ConstraintSystemReader { bytes, offset: 0 };
+
+        let var_num = reader.read_u32()?;
+        let public_inputs = reader.read_vec(ConstraintSystemReader::read_u32)?;
+
+        let logic_constraints = reader.read_vec(|reader| {
+            Ok(LogicConstraint {
+                a: reader.read_i32()?,
+                b: reader.read_i32()?,
+                result: reader.read_i32()?,
+                num_bits: reader.read_i32()?,
+                is_xor_gate: reader.read_i32()? != 0,
+            })
+        })?;
+        let range_constraints = reader.read_vec(|reader| {
+            Ok(RangeConstraint {
+                a: reader.read_i32()?,
+                num_bits: reader.read_i32()?,
+            })
+        })?;
+        let sha256_constraints = reader.read_vec(|reader| {
+            Ok(Sha256Constraint {
+                inputs: reader.read_byte_inputs()?,
+                result: reader.read_witnesses()?,
+            })
+        })?;
+        let compute_merkle_root_constraints = reader.read_vec(|reader| {
+            let hash_path = reader.read_vec(ConstraintSystemReader::read_i32)?;
+            let leaf = reader.read_i32()?;
+            let result = reader.read_i32()?;
+            let index = reader.read_i32()?;
+            Ok(ComputeMerkleRootConstraint {
+                hash_path,
+                leaf,
+                index,
+                result,
+            })
+        })?;
+        let schnorr_constraints = reader.read_vec(|reader| {
+            Ok(SchnorrConstraint {
+                message: reader.read_vec(ConstraintSystemReader::read_i32)?,
+                signature: reader.read_witnesses()?,
+                public_key_x: reader.read_i32()?,
+                public_key_y: reader.read_i32()?,
+                result: reader.read_i32()?,
+            })
+        })?;
+        let ecdsa_secp256k1_constraints = reader.read_vec(|reader| {
+            Ok(EcdsaConstraint {
+                hashed_message: reader.read_vec(ConstraintSystemReader::read_i32)?,
+                signature: reader.read_witnesses()?,
+                public_key_x: reader.read_witnesses()?,
+                public_key_y: reader.read_witnesses()?,
+                result: reader.read_i32()?,
+            })
+        })?;
+        let blake2s_constraints = reader.read_vec(|reader| {
+            Ok(Blake2sConstraint {
+                inputs: reader.read_byte_inputs()?,
+                result: reader.read_witnesses()?,
+            })
+        })?;
+        let keccak_constraints = reader.read_vec(|reader| {
+            Ok(Keccak256Constraint {
+                inputs: reader.read_byte_inputs()?,
+                result: reader.read_witnesses()?,
+            })
+        })?;
+        let pedersen_constraints = reader.read_vec(|reader| {
+            Ok(PedersenConstraint {
+                inputs: reader.read_vec(ConstraintSystemReader::read_i32)?,
+                result_x: reader.read_i32()?,
+                result_y: reader.read_i32()?,
+            })
+        })?;
+        let hash_to_field_constraints = reader.read_vec(|reader| {
+            Ok(HashToFieldConstraint {
+                inputs: reader.read_byte_inputs()?,
+                result: reader.read_i32()?,
+            })
+        })?;
+        let fixed_base_scalar_mul_constraints = reader.read_vec(|reader| {
+            Ok(FixedBaseScalarMulConstraint {
+                scalar: reader.read_i32()?,
+                pubkey_x: reader.read_i32()?,
+                pubkey_y: reader.read_i32()?,
+            })
+        })?;
+        let constraints = reader.read_vec(|reader| {
+            Ok(Constraint {
+                a: reader.read_i32()?,
+                b: reader.read_i32()?,
+                c: reader.read_i32()?,
+                qm: reader.read_field()?,
+                ql: reader.read_field()?,
+                qr: reader.read_field()?,
+                qo: reader.read_field()?,
+                qc: reader.read_field()?,
+            })
+        })?;
+
+        if reader.offset != bytes.len() {
+            return Err(Error::MalformedConstraintSystem(format!(
+                "unexpected data after the end of the constraint system at byte {}",
+                reader.offset
+            )));
+        }
+
+        Ok(ConstraintSystem {
+            var_num,
+            public_inputs,
+            logic_constraints,
+            range_constraints,
+            sha256_constraints,
+            compute_merkle_root_constraints,
+            schnorr_constraints,
+            ecdsa_secp256k1_constraints,
+            blake2s_constraints,
+            keccak_constraints,
+            pedersen_constraints,
+            hash_to_field_constraints,
+            fixed_base_scalar_mul_constraints,
+            constraints,
+        })
+    }
+
+    pub(crate) fn num_variables(&self) -> u32 {
+        self.var_num
+    }
+
+    /// Serializes the constraint system with a header recording the version of the layout produced by
+    /// [`ConstraintSystem::to_bytes`], so that bytes written by a build with a different layout are rejected by
+    /// [`ConstraintSystem::from_versioned_bytes`] rather than being misread.
+    pub(crate) fn to_versioned_bytes(&self) -> Vec<u8> {
+        framing::CONSTRAINT_SYSTEM.frame(&self.to_bytes())
+    }
+
+    /// Parses a constraint system written by [`ConstraintSystem::to_versioned_bytes`].
+    pub(crate) fn from_versioned_bytes(versioned: &[u8]) -> Result<ConstraintSystem, Error> {
+        let bytes = framing::CONSTRAINT_SYSTEM
+            .unframe(versioned)
+            .map_err(Error::MalformedConstraintSystem)?;
+        ConstraintSystem::from_bytes(bytes)
+    }
+
+    /// Creates a constraint system without any constraints whose variables are all public inputs.
+    ///
+    /// barretenberg's verifier only takes the public inputs from the constraint system and reads everything else
+    /// from the verification key, so this stands in for the original constraint system when verifying.
+    pub(crate) fn public_inputs_only(num_public_inputs: u32) -> ConstraintSystem {
+        ConstraintSystem {
+            var_num: num_public_inputs + 1,
+            public_inputs: (1..=num_public_inputs).collect(),
+            ..ConstraintSystem::default()
+        }
+    }
+}
+
 impl TryFrom<&Circuit> for ConstraintSystem {
     type Error = Error;
 
     /// Converts an `IR` into the `StandardFormat` constraint system
     fn try_from(circuit: &Circuit) -> Result<Self, Self::Error> {
+        ConstraintSystem::from_circuit_with_map(circuit, false)
+            .map(|(constraint_system, _)| constraint_system)
+    }
+}
+
+impl Barretenberg {
+    /// Converts `circuit` into barretenberg's constraint system, applying the optimizations enabled in this
+    /// backend's [options][crate::BackendOptions].
+    ///
+    /// Keys and proofs must be created with the same options, as the optimizations change the constraint system.
+    pub(crate) fn constraint_system(&self, circuit: &Circuit) -> Result<ConstraintSystem, Error> {
+        ConstraintSystem::from_circuit_with_map(circuit, self.options.aggregate_range_constraints)
+            .map(|(constraint_system, _)| constraint_system)
+    }
+}
+
+impl ConstraintSystem {
+    /// Converts an `IR` into the `StandardFormat` constraint system, along with a map from each
+    /// resulting constraint back to the opcode it was created from.
+    ///
+    /// Range constraints are emitted in opcode order unless `aggregate_ranges` is set, in which case they're
+    /// [aggregated][aggregate_range_constraints].
+    pub(crate) fn from_circuit_with_map(
+        circuit: &Circuit,
+        aggregate_ranges: bool,
+    ) -> Result<(ConstraintSystem, ConstraintMap), Error> {
+        let mut constraint_map = ConstraintMap::default();
+
         // Create constraint system
         let mut constraints: Vec<Constraint> = Vec::new();
-        let mut range_constraints: Vec<RangeConstraint> = Vec::new();
+        let mut range_constraints: Vec<(RangeConstraint, usize)> = Vec::new();
         let mut logic_constraints: Vec<LogicConstraint> = Vec::new();
         let mut sha256_constraints: Vec<Sha256Constraint> = Vec::new();
         let mut blake2s_constraints: Vec<Blake2sConstraint> = Vec::new();
@@ -664,7 +952,7 @@ impl TryFrom<&Circuit> for ConstraintSystem {
         let mut fixed_base_scalar_mul_constraints: Vec<FixedBaseScalarMulConstraint> = Vec::new();
         let mut hash_to_field_constraints: Vec<HashToFieldConstraint> = Vec::new();
 
-        for gate in circuit.opcodes.iter() {
+        for (opcode_index, gate) in circuit.opcodes.iter().enumerate() {
             match gate {
                 Opcode::Arithmetic(expression) => {
                     let constraint = serialize_arithmetic_gates(expression);
@@ -684,7 +972,7 @@ impl TryFrom<&Circuit> for ConstraintSystem {
                                 a: witness.witness_index() as i32,
                                 num_bits: num_bits as i32,
                             };
-                            range_constraints.push(range_constraint);
+                            range_constraints.push((range_constraint, opcode_index));
                         }
                         BlackBoxFunc::AND | BlackBoxFunc::XOR => {
                             assert_eq!(gadget_call.inputs.len(), 2);
@@ -1048,10 +1336,25 @@ impl TryFrom<&Circuit> for ConstraintSystem {
                     // TODO: implement serialization to match BB's interface
                 }
             }
+
+            // Range constraints are recorded once they have been aggregated, as that can change their order.
+            match ConstraintKind::of_opcode(gate) {
+                Some(ConstraintKind::Range) | None => (),
+                Some(kind) => constraint_map.push(kind, opcode_index),
+            }
+        }
+
+        if aggregate_ranges {
+            range_constraints = aggregate_range_constraints(range_constraints);
+        }
+        let (range_constraints, range_opcode_indices): (Vec<_>, Vec<_>) =
+            range_constraints.into_iter().unzip();
+        for opcode_index in range_opcode_indices {
+            constraint_map.push(ConstraintKind::Range, opcode_index);
         }
 
         // Create constraint system
-        Ok(ConstraintSystem {
+        let constraint_system = ConstraintSystem {
             var_num: circuit.current_witness_index + 1, // number of witnesses is the witness index + 1;
             public_inputs: circuit.public_inputs().indices(),
             logic_constraints,
@@ -1066,10 +1369,72 @@ impl TryFrom<&Circuit> for ConstraintSystem {
             hash_to_field_constraints,
             constraints,
             fixed_base_scalar_mul_constraints,
-        })
+        };
+        Ok((constraint_system, constraint_map))
+    }
+}
+
+impl ConstraintSystem {
+    /// Returns the kind and index of the first arithmetic or range constraint which `witness_values` does not satisfy.
+    ///
+    /// Witnesses missing from `witness_values` are taken to be zero, as they are when the witness is passed to barretenberg.
+    /// Constraints created by black box functions are evaluated within barretenberg and so are not checked here.
+    pub(crate) fn first_unsatisfied_constraint(
+        &self,
+        witness_values: &WitnessVector,
+    ) -> Option<(ConstraintKind, usize)> {
+        let value = |witness_index: i32| {
+            witness_values
+                .get(Witness(witness_index as u32))
+                .unwrap_or_else(FieldElement::zero)
+        };
+
+        let unsatisfied_gate = self.constraints.iter().position(|gate| {
+            let (a, b, c) = (value(gate.a), value(gate.b), value(gate.c));
+            !(gate.qm * a * b + gate.ql * a + gate.qr * b + gate.qo * c + gate.qc).is_zero()
+        });
+        if let Some(constraint_index) = unsatisfied_gate {
+            return Some((ConstraintKind::Arithmetic, constraint_index));
+        }
+
+        self.range_constraints
+            .iter()
+            .position(|range| value(range.a).num_bits() > range.num_bits as u32)
+            .map(|constraint_index| (ConstraintKind::Range, constraint_index))
     }
 }
 
+/// Removes redundant range constraints and groups the remainder by bit size.
+///
+/// A witness which is constrained to several ranges only needs to be constrained to the smallest of them.
+/// Barretenberg builds a single set of range lookups for each distinct bit size, so emitting constraints of
+/// the same size together lets it share these lookups rather than building them for each constraint.
+///
+/// Each constraint is paired with the index of the opcode which created it. Where several opcodes constrain
+/// the same witness to the smallest range, the first of them is kept.
+fn aggregate_range_constraints(
+    range_constraints: Vec<(RangeConstraint, usize)>,
+) -> Vec<(RangeConstraint, usize)> {
+    let mut smallest_ranges: BTreeMap<i32, (i32, usize)> = BTreeMap::new();
+    for (RangeConstraint { a, num_bits }, opcode_index) in range_constraints {
+        smallest_ranges
+            .entry(a)
+            .and_modify(|smallest| {
+                if num_bits < smallest.0 {
+                    *smallest = (num_bits, opcode_index);
+                }
+            })
+            .or_insert((num_bits, opcode_index));
+    }
+
+    let mut aggregated: Vec<(RangeConstraint, usize)> = smallest_ranges
+        .into_iter()
+        .map(|(a, (num_bits, opcode_index))| (RangeConstraint { a, num_bits }, opcode_index))
+        .collect();
+    aggregated.sort_by_key(|(constraint, _)| (constraint.num_bits, constraint.a));
+    aggregated
+}
+
 #[allow(non_snake_case)]
 fn serialize_arithmetic_gates(gate: &Expression) -> Constraint {
     let mut cs = Constraint::default();
@@ -1098,9 +1463,37 @@ fn serialize_arithmetic_gates(gate: &Expression) -> Constraint {
 
 #[cfg(test)]
 mod tests {
-    use crate::barretenberg_structures::serialize_arithmetic_gates;
+    use crate::barretenberg_structures::{
+        aggregate_range_constraints, serialize_arithmetic_gates, Assignments, ConstraintSystem,
+        RangeConstraint,
+    };
+    use crate::test_circuits::{merkle_membership, pedersen_hashes, sha256_chain};
     use acvm::acir::native_types::{Expression, Witness};
     use acvm::FieldElement;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn extends_assignments_from_witness_range() {
+        let witness_values = BTreeMap::from([
+            (Witness(1), FieldElement::from(10_u128)),
+            (Witness(3), FieldElement::from(30_u128)),
+            (Witness(5), FieldElement::from(50_u128)),
+        ]);
+
+        let mut assignments = Assignments::with_capacity(3);
+        assignments
+            .extend_from_witness_range(1..4, |witness| witness_values.get(&witness).copied());
+
+        assert_eq!(assignments.len(), 3);
+        assert_eq!(
+            assignments.into_iter().collect::<Vec<_>>(),
+            vec![
+                FieldElement::from(10_u128),
+                FieldElement::zero(),
+                FieldElement::from(30_u128)
+            ]
+        );
+    }
 
     #[test]
     fn serialize_expression() {
@@ -1153,4 +1546,115 @@ mod tests {
         assert_eq!(constrain.b, 2);
         assert_eq!(constrain.c, 8);
     }
+
+    #[test]
+    fn aggregates_range_constraints() {
+        let aggregated = aggregate_range_constraints(
+            [(3, 8), (4, 16), (1, 32), (2, 8), (5, 4), (1, 8), (2, 8)]
+                .into_iter()
+                .enumerate()
+                .map(|(opcode_index, (a, num_bits))| {
+                    (RangeConstraint { a, num_bits }, opcode_index)
+                })
+                .collect(),
+        );
+
+        let aggregated: Vec<_> = aggregated
+            .into_iter()
+            .map(|(constraint, opcode_index)| (constraint.a, constraint.num_bits, opcode_index))
+            .collect();
+        assert_eq!(
+            aggregated,
+            vec![(5, 4, 4), (1, 8, 5), (2, 8, 3), (3, 8, 0), (4, 16, 1)]
+        );
+    }
+
+    #[test]
+    fn aggregated_range_constraints_accept_the_same_witnesses() {
+        use std::collections::BTreeMap;
+
+        use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+        use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+        use acvm::acir::BlackBoxFunc;
+        use acvm::ProofSystemCompiler;
+
+        use crate::{BackendOptions, Barretenberg};
+
+        let range = |witness, num_bits| {
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                name: BlackBoxFunc::RANGE,
+                inputs: vec![FunctionInput {
+                    witness: Witness(witness),
+                    num_bits,
+                }],
+                outputs: vec![],
+            })
+        };
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![range(1, 32), range(2, 4), range(1, 8), range(2, 4)],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        };
+
+        let unaggregated = Barretenberg::new();
+        let aggregated = Barretenberg::with_options(BackendOptions {
+            aggregate_range_constraints: true,
+            ..BackendOptions::default()
+        })
+        .unwrap();
+        assert_eq!(
+            unaggregated
+                .constraint_system(&circuit)
+                .unwrap()
+                .range_constraints
+                .len(),
+            4
+        );
+        assert_eq!(
+            aggregated
+                .constraint_system(&circuit)
+                .unwrap()
+                .range_constraints
+                .len(),
+            2
+        );
+
+        let accepts = |bb: &Barretenberg, x: u128, y: u128| {
+            let (proving_key, verification_key) = bb.preprocess(&circuit).unwrap();
+            let witness = BTreeMap::from([
+                (Witness(1), FieldElement::from(x)),
+                (Witness(2), FieldElement::from(y)),
+            ]);
+            bb.prove_with_pk(&circuit, witness, &proving_key)
+                .and_then(|proof| {
+                    bb.verify_with_vk(&proof, BTreeMap::new(), &circuit, &verification_key)
+                })
+                .unwrap_or(false)
+        };
+        for (x, y, expected) in [
+            (0, 0, true),
+            (255, 15, true),
+            (256, 15, false),
+            (255, 16, false),
+            (1 << 32, 0, false),
+        ] {
+            assert_eq!(accepts(&unaggregated, x, y), expected, "x = {x}, y = {y}");
+            assert_eq!(accepts(&aggregated, x, y), expected, "x = {x}, y = {y}");
+        }
+    }
+
+    #[test]
+    fn constraint_system_bytes_round_trip() {
+        for test_circuit in [pedersen_hashes(2), sha256_chain(2), merkle_membership(3)] {
+            let constraint_system = ConstraintSystem::try_from(&test_circuit.circuit).unwrap();
+            let bytes = constraint_system.to_bytes();
+
+            let parsed = ConstraintSystem::from_bytes(&bytes).unwrap();
+
+            assert_eq!(parsed.to_bytes(), bytes);
+            assert!(ConstraintSystem::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+            assert!(ConstraintSystem::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        }
+    }
 }
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use acvm::acir::circuit::Circuit;
+
+use crate::fingerprint::circuit_fingerprint;
+use crate::framing::{self, HEADER_BYTES};
+use crate::{BackendError, Error, ProvingKey, VerificationKey};
+
+/// The keys generated for a circuit, packaged together with the fingerprint of the circuit they belong to.
+///
+/// Bundles are written as `.bbkg` files, which are laid out as:
+/// - the magic bytes `BBKG` and a big-endian `u32` format version
+/// - the 32 byte [circuit fingerprint][crate::circuit_fingerprint]
+/// - the proving key and verification key, each prefixed by its length as a big-endian `u32`
+/// - the number of metadata entries, followed by each key and value as length-prefixed utf8
+/// - a flag byte indicating whether a verifier contract follows, then the length-prefixed contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    pub circuit_fingerprint: [u8; 32],
+    pub proving_key: ProvingKey,
+    pub verification_key: VerificationKey,
+    /// Arbitrary information about the bundle, e.g. the name and version of the program it was built from.
+    pub metadata: BTreeMap<String, String>,
+    /// The Solidity verifier generated from the verification key, if one was included.
+    pub contract: Option<String>,
+}
+
+impl Bundle {
+    /// Creates a bundle holding the keys returned by [`preprocess_keys`][crate::Barretenberg::preprocess_keys] for
+    /// `circuit`.
+    pub fn new(
+        circuit: &Circuit,
+        proving_key: ProvingKey,
+        verification_key: VerificationKey,
+    ) -> Bundle {
+        Bundle {
+            circuit_fingerprint: circuit_fingerprint(circuit),
+            proving_key,
+            verification_key,
+            metadata: BTreeMap::new(),
+            contract: None,
+        }
+    }
+
+    /// Returns `true` if the bundle's keys were generated for `circuit`.
+    pub fn is_for_circuit(&self, circuit: &Circuit) -> bool {
+        self.circuit_fingerprint == circuit_fingerprint(circuit)
+    }
+
+    /// Writes the bundle in the `.bbkg` format.
+    ///
+    /// Fails if a key, metadata entry or the contract is longer than `u32::MAX` bytes, or if `writer` fails.
+    pub fn write<W: Write>(&self, writer: W) -> Result<(), BackendError> {
+        Ok(write_bundle(self, writer)?)
+    }
+
+    /// Reads a bundle written by [`Bundle::write`], which must make up the whole of `reader`.
+    pub fn read<R: Read>(reader: R) -> Result<Bundle, BackendError> {
+        Ok(read_bundle(reader)?)
+    }
+}
+
+fn write_bundle<W: Write>(bundle: &Bundle, mut writer: W) -> Result<(), Error> {
+    let mut writer = BundleWriter {
+        writer: &mut writer,
+    };
+
+    writer.write_all(&framing::BUNDLE.header())?;
+    writer.write_all(&bundle.circuit_fingerprint)?;
+
+    writer.write_length_prefixed("proving key", bundle.proving_key.as_ref())?;
+    writer.write_length_prefixed("verification key", &bundle.verification_key.to_bytes())?;
+
+    writer.write_u32("metadata", bundle.metadata.len())?;
+    for (key, value) in &bundle.metadata {
+        writer.write_length_prefixed("metadata key", key.as_bytes())?;
+        writer.write_length_prefixed("metadata value", value.as_bytes())?;
+    }
+
+    match &bundle.contract {
+        Some(contract) => {
+            writer.write_all(&[1])?;
+            writer.write_length_prefixed("contract", contract.as_bytes())
+        }
+        None => writer.write_all(&[0]),
+    }
+}
+
+struct BundleWriter<'a, W> {
+    writer: &'a mut W,
+}
+
+impl<W: Write> BundleWriter<'_, W> {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.writer
+            .write_all(bytes)
+            .map_err(|err| malformed(format!("could not write bundle ({err})")))
+    }
+
+    fn write_u32(&mut self, section: &str, value: usize) -> Result<(), Error> {
+        let value = u32::try_from(value)
+            .map_err(|_| malformed(format!("{section} is too large to be written")))?;
+        self.write_all(&value.to_be_bytes())
+    }
+
+    fn write_length_prefixed(&mut self, section: &str, bytes: &[u8]) -> Result<(), Error> {
+        self.write_u32(section, bytes.len())?;
+        self.write_all(bytes)
+    }
+}
+
+fn read_bundle<R: Read>(mut reader: R) -> Result<Bundle, Error> {
+    let mut reader = BundleReader {
+        reader: &mut reader,
+    };
+
+    framing::BUNDLE
+        .check_header(&reader.read_array::<HEADER_BYTES>()?)
+        .map_err(malformed)?;
+
+    let circuit_fingerprint = reader.read_array()?;
+    let proving_key = ProvingKey(reader.read_length_prefixed()?);
+    let verification_key = VerificationKey::from_bytes(&reader.read_length_prefixed()?)
+        .map_err(|err| malformed(format!("invalid verification key ({err})")))?;
+
+    let num_metadata_entries = reader.read_u32()?;
+    let mut metadata = BTreeMap::new();
+    for _ in 0..num_metadata_entries {
+        let key = reader.read_string()?;
+        let value = reader.read_string()?;
+        metadata.insert(key, value);
+    }
+
+    let contract = match reader.read_array::<1>()? {
+        [0] => None,
+        [1] => Some(reader.read_string()?),
+        [flag] => return Err(malformed(format!("invalid contract flag {flag}"))),
+    };
+
+    if reader.read_trailing_byte()? {
+        return Err(malformed("unexpected data after end of bundle".to_owned()));
+    }
+
+    Ok(Bundle {
+        circuit_fingerprint,
+        proving_key,
+        verification_key,
+        metadata,
+        contract,
+    })
+}
+
+fn malformed(reason: String) -> Error {
+    Error::MalformedBundle(reason)
+}
+
+struct BundleReader<'a, R> {
+    reader: &'a mut R,
+}
+
+impl<R: Read> BundleReader<'_, R> {
+    fn read_array<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], Error> {
+        let mut bytes = [0u8; SIZE];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|err| malformed(format!("could not read bundle ({err})")))?;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    fn read_length_prefixed(&mut self) -> Result<Vec<u8>, Error> {
+        let len = self.read_u32()? as u64;
+
+        // Reading through `take` avoids allocating a buffer of the claimed length up front,
+        // which could be arbitrarily large in a corrupted bundle.
+        let mut bytes = Vec::new();
+        Read::take(&mut *self.reader, len)
+            .read_to_end(&mut bytes)
+            .map_err(|err| malformed(format!("could not read bundle ({err})")))?;
+        if bytes.len() as u64 != len {
+            return Err(malformed("unexpected end of bundle".to_owned()));
+        }
+        Ok(bytes)
+    }
+
+    /// Returns `true` if there is anything left to read.
+    fn read_trailing_byte(&mut self) -> Result<bool, Error> {
+        let mut byte = [0u8; 1];
+        loop {
+            match self.reader.read(&mut byte) {
+                Ok(num_read) => return Ok(num_read > 0),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(malformed(format!("could not read bundle ({err})"))),
+            }
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, Error> {
+        String::from_utf8(self.read_length_prefixed()?)
+            .map_err(|_| malformed("string is not valid utf8".to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    fn verification_key() -> VerificationKey {
+        VerificationKey {
+            composer_type: 2,
+            circuit_size: 16,
+            num_public_inputs: 0,
+            commitments: BTreeMap::new(),
+            contains_recursive_proof: false,
+            recursive_proof_public_input_indices: Vec::new(),
+        }
+    }
+
+    fn bundle() -> Bundle {
+        let mut bundle = Bundle::new(
+            &arithmetic_gates(1).circuit,
+            ProvingKey::from_bytes(vec![1, 2, 3]),
+            verification_key(),
+        );
+        bundle
+            .metadata
+            .insert("program".to_owned(), "main".to_owned());
+        bundle.contract = Some("contract UltraVerifier {}".to_owned());
+        bundle
+    }
+
+    #[test]
+    fn bundle_round_trip() -> Result<(), BackendError> {
+        for bundle in [
+            bundle(),
+            Bundle::new(
+                &arithmetic_gates(2).circuit,
+                ProvingKey::from_bytes(Vec::new()),
+                verification_key(),
+            ),
+        ] {
+            let mut bytes = Vec::new();
+            bundle.write(&mut bytes)?;
+
+            assert_eq!(Bundle::read(bytes.as_slice())?, bundle);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn checks_circuit_fingerprint() {
+        let bundle = bundle();
+
+        assert!(bundle.is_for_circuit(&arithmetic_gates(1).circuit));
+        assert!(!bundle.is_for_circuit(&arithmetic_gates(2).circuit));
+    }
+
+    #[test]
+    fn rejects_malformed_bundles() {
+        let mut bytes = Vec::new();
+        bundle().write(&mut bytes).unwrap();
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[7] = 2;
+
+        let mut trailing_data = bytes.clone();
+        trailing_data.push(0);
+
+        for bytes in [
+            &bytes[..bytes.len() - 1],
+            &wrong_version[..],
+            &bytes[1..],
+            &trailing_data[..],
+        ] {
+            assert!(Bundle::read(bytes).is_err());
+        }
+    }
+}
@@ -0,0 +1,405 @@
+use std::io::{Cursor, Read};
+
+use acvm::acir::circuit::directives::{Directive, LogInfo};
+use acvm::acir::circuit::{Circuit, Opcode};
+use acvm::acir::native_types::{Expression, Witness};
+use flate2::read::DeflateDecoder;
+use flate2::Crc;
+use serde::Deserialize;
+
+use crate::{BackendError, Error};
+
+/// The size of the length prefix read by [`read_circuit_framed`].
+const LENGTH_PREFIX_BYTES: usize = 4;
+
+/// Bounds on the size of a serialized [`Circuit`] accepted by [`read_circuit_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitLimits {
+    /// The maximum length of the compressed circuit.
+    pub max_compressed_bytes: usize,
+    /// The maximum length of the circuit once decompressed.
+    pub max_decompressed_bytes: usize,
+    /// The maximum number of opcodes in the circuit.
+    pub max_opcodes: u32,
+    /// The maximum witness index used by the circuit, whether declared as its current witness index or referenced
+    /// by one of its opcodes or public inputs.
+    pub max_witness_index: u32,
+}
+
+impl Default for CircuitLimits {
+    fn default() -> Self {
+        CircuitLimits {
+            max_compressed_bytes: 16 * 1024 * 1024,
+            max_decompressed_bytes: 256 * 1024 * 1024,
+            max_opcodes: 1 << 24,
+            max_witness_index: 1 << 26,
+        }
+    }
+}
+
+/// Deserializes a circuit written by [`Circuit::write`], returning an error rather than panicking on malformed input.
+///
+/// Unlike [`Circuit::read`], this is safe to call on untrusted bytes: the circuit is rejected if any of the given
+/// `limits` are exceeded, with the opcode count and declared witness index being checked before the circuit is decoded
+/// and the witnesses referenced by its opcodes being checked afterwards.
+pub fn read_circuit_checked(bytes: &[u8], limits: &CircuitLimits) -> Result<Circuit, BackendError> {
+    Ok(read_circuit(bytes, limits)?)
+}
+
+/// Deserializes a circuit which has been wrapped in a length-prefixed frame, as produced by some bundlers.
+///
+/// `bytes` must start with the length of the circuit as a big-endian `u32`, followed by the circuit as written by
+/// [`Circuit::write`]. Anything after the circuit, such as padding, is ignored. If `expected_crc32` is given then
+/// the CRC-32 of the circuit's bytes must match it. The circuit itself is checked against `limits` in the same way
+/// as [`read_circuit_checked`].
+pub fn read_circuit_framed(
+    bytes: &[u8],
+    expected_crc32: Option<u32>,
+    limits: &CircuitLimits,
+) -> Result<Circuit, BackendError> {
+    let payload = unframe(bytes)?;
+
+    if let Some(expected_crc32) = expected_crc32 {
+        let mut crc = Crc::new();
+        crc.update(payload);
+        if crc.sum() != expected_crc32 {
+            return Err(Error::MalformedCircuit(format!(
+                "circuit at bytes {}..{} has CRC-32 {:#010x} but {expected_crc32:#010x} was expected",
+                LENGTH_PREFIX_BYTES,
+                LENGTH_PREFIX_BYTES + payload.len(),
+                crc.sum()
+            ))
+            .into());
+        }
+    }
+
+    Ok(read_circuit(payload, limits)?)
+}
+
+/// Returns the payload of a frame read by [`read_circuit_framed`].
+fn unframe(bytes: &[u8]) -> Result<&[u8], Error> {
+    if bytes.len() < LENGTH_PREFIX_BYTES {
+        return Err(Error::MalformedCircuit(format!(
+            "expected a {LENGTH_PREFIX_BYTES} byte length prefix at byte 0 but the input is only {} bytes",
+            bytes.len()
+        )));
+    }
+    let (prefix, rest) = bytes.split_at(LENGTH_PREFIX_BYTES);
+    let payload_len = u32::from_be_bytes(prefix.try_into().expect("prefix is 4 bytes")) as usize;
+
+    rest.get(..payload_len).ok_or_else(|| {
+        Error::MalformedCircuit(format!(
+            "length prefix at byte 0 declares a {payload_len} byte circuit but only {} bytes follow it",
+            rest.len()
+        ))
+    })
+}
+
+fn read_circuit(bytes: &[u8], limits: &CircuitLimits) -> Result<Circuit, Error> {
+    if bytes.len() > limits.max_compressed_bytes {
+        return Err(Error::MalformedCircuit(format!(
+            "compressed circuit is {} bytes but at most {} are allowed",
+            bytes.len(),
+            limits.max_compressed_bytes
+        )));
+    }
+
+    // We read one byte past the limit so that we can distinguish a circuit which exactly fills the limit
+    // from one which exceeds it.
+    let mut decompressed = Vec::new();
+    DeflateDecoder::new(bytes)
+        .take(limits.max_decompressed_bytes as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|err| Error::MalformedCircuit(format!("could not decompress circuit: {err}")))?;
+    if decompressed.len() > limits.max_decompressed_bytes {
+        return Err(Error::MalformedCircuit(format!(
+            "decompressed circuit exceeds {} bytes",
+            limits.max_decompressed_bytes
+        )));
+    }
+
+    check_header(&decompressed, limits)?;
+
+    // Decoding through a cursor allows us to report how far into the circuit we got before failing,
+    // which helps to narrow down which opcode has changed between ACIR versions.
+    let mut reader = Cursor::new(decompressed.as_slice());
+    let mut deserializer = rmp_serde::Deserializer::new(&mut reader);
+    let circuit = Circuit::deserialize(&mut deserializer).map_err(|err| Error::CircuitDecode {
+        offset: reader.position(),
+        message: err.to_string(),
+    })?;
+
+    // The declared witness index doesn't bound those referenced by the opcodes, which are later used to size
+    // the witness assignment.
+    let mut max_witness_index = 0;
+    for_each_witness(&circuit, &mut |witness| {
+        max_witness_index = max_witness_index.max(witness.witness_index())
+    });
+    if max_witness_index > limits.max_witness_index {
+        return Err(Error::MalformedCircuit(format!(
+            "circuit references witness index {max_witness_index} but at most {} is allowed",
+            limits.max_witness_index
+        )));
+    }
+
+    Ok(circuit)
+}
+
+/// Checks the witness index and opcode count at the start of the encoded circuit.
+///
+/// The circuit is encoded as a MessagePack array of its fields, the first two of which are
+/// `current_witness_index` and the array of opcodes.
+fn check_header(mut encoded: &[u8], limits: &CircuitLimits) -> Result<(), Error> {
+    let malformed_header = |err: &dyn std::fmt::Display| {
+        Error::MalformedCircuit(format!("could not decode circuit header: {err}"))
+    };
+
+    rmp::decode::read_array_len(&mut encoded).map_err(|err| malformed_header(&err))?;
+
+    let current_witness_index: u32 =
+        rmp::decode::read_int(&mut encoded).map_err(|err| malformed_header(&err))?;
+    if current_witness_index > limits.max_witness_index {
+        return Err(Error::MalformedCircuit(format!(
+            "circuit uses witness index {current_witness_index} but at most {} is allowed",
+            limits.max_witness_index
+        )));
+    }
+
+    let num_opcodes =
+        rmp::decode::read_array_len(&mut encoded).map_err(|err| malformed_header(&err))?;
+    if num_opcodes > limits.max_opcodes {
+        return Err(Error::MalformedCircuit(format!(
+            "circuit contains {num_opcodes} opcodes but at most {} are allowed",
+            limits.max_opcodes
+        )));
+    }
+
+    Ok(())
+}
+
+/// Calls `visit` with every witness referenced by the opcodes and public inputs of `circuit`.
+fn for_each_witness(circuit: &Circuit, visit: &mut impl FnMut(Witness)) {
+    let public_inputs = circuit
+        .public_parameters
+        .0
+        .iter()
+        .chain(&circuit.return_values.0);
+    for witness in public_inputs {
+        visit(*witness);
+    }
+
+    for opcode in &circuit.opcodes {
+        match opcode {
+            Opcode::Arithmetic(expr) => for_each_expression_witness(expr, visit),
+            Opcode::BlackBoxFuncCall(func_call) => {
+                for input in &func_call.inputs {
+                    visit(input.witness);
+                }
+                func_call.outputs.iter().copied().for_each(&mut *visit);
+            }
+            Opcode::Directive(directive) => match directive {
+                Directive::Invert { x, result } => {
+                    visit(*x);
+                    visit(*result);
+                }
+                Directive::Quotient {
+                    a,
+                    b,
+                    q,
+                    r,
+                    predicate,
+                } => {
+                    for expr in [a, b].into_iter().chain(predicate) {
+                        for_each_expression_witness(expr, visit);
+                    }
+                    visit(*q);
+                    visit(*r);
+                }
+                Directive::ToLeRadix { a, b, .. } => {
+                    for_each_expression_witness(a, visit);
+                    b.iter().copied().for_each(&mut *visit);
+                }
+                Directive::PermutationSort { inputs, bits, .. } => {
+                    for expr in inputs.iter().flatten() {
+                        for_each_expression_witness(expr, visit);
+                    }
+                    bits.iter().copied().for_each(&mut *visit);
+                }
+                Directive::Log(LogInfo::WitnessOutput(witnesses)) => {
+                    witnesses.iter().copied().for_each(&mut *visit);
+                }
+                Directive::Log(LogInfo::FinalizedOutput(_)) => (),
+            },
+            Opcode::Block(block) | Opcode::ROM(block) | Opcode::RAM(block) => {
+                for op in &block.trace {
+                    for expr in [&op.operation, &op.index, &op.value] {
+                        for_each_expression_witness(expr, visit);
+                    }
+                }
+            }
+            Opcode::Oracle(data) => {
+                for expr in &data.inputs {
+                    for_each_expression_witness(expr, visit);
+                }
+                data.outputs.iter().copied().for_each(&mut *visit);
+            }
+        }
+    }
+}
+
+fn for_each_expression_witness(expr: &Expression, visit: &mut impl FnMut(Witness)) {
+    for (_, lhs, rhs) in &expr.mul_terms {
+        visit(*lhs);
+        visit(*rhs);
+    }
+    for (_, witness) in &expr.linear_combinations {
+        visit(*witness);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::PublicInputs;
+    use acvm::FieldElement;
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    use super::*;
+
+    fn circuit(num_opcodes: usize) -> Circuit {
+        let opcode = Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(FieldElement::one(), Witness(1))],
+            q_c: FieldElement::one(),
+        });
+        Circuit {
+            current_witness_index: 1,
+            opcodes: vec![opcode; num_opcodes],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        }
+    }
+
+    fn write(circuit: &Circuit) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        circuit.write(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn reads_circuits_written_by_acir() -> Result<(), Error> {
+        let circuit = circuit(3);
+
+        let read = read_circuit(&write(&circuit), &CircuitLimits::default())?;
+
+        assert_eq!(write(&read), write(&circuit));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_circuits_exceeding_limits() {
+        let bytes = write(&circuit(3));
+
+        for limits in [
+            CircuitLimits {
+                max_compressed_bytes: bytes.len() - 1,
+                ..CircuitLimits::default()
+            },
+            CircuitLimits {
+                max_decompressed_bytes: 8,
+                ..CircuitLimits::default()
+            },
+            CircuitLimits {
+                max_opcodes: 2,
+                ..CircuitLimits::default()
+            },
+            CircuitLimits {
+                max_witness_index: 0,
+                ..CircuitLimits::default()
+            },
+        ] {
+            assert!(matches!(
+                read_circuit(&bytes, &limits),
+                Err(Error::MalformedCircuit(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_opcodes_referencing_witnesses_beyond_the_limit() {
+        let mut circuit = circuit(2);
+        circuit.opcodes[1] = Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: vec![(FieldElement::one(), Witness(u32::MAX))],
+            q_c: FieldElement::zero(),
+        });
+        let bytes = write(&circuit);
+
+        // The declared witness index is within the limit, but the second opcode's witness is not.
+        let limits = CircuitLimits {
+            max_witness_index: 1,
+            ..CircuitLimits::default()
+        };
+        assert!(matches!(
+            read_circuit(&bytes, &limits),
+            Err(Error::MalformedCircuit(_))
+        ));
+    }
+
+    #[test]
+    fn reports_offset_of_decoding_failures() {
+        let circuit = circuit(1);
+        let mut encoded = rmp_serde::to_vec(&circuit).unwrap();
+        // Replace the marker of the final field (the return values) with one which is never valid MessagePack.
+        let return_values_offset = encoded.len() - 1;
+        encoded[return_values_offset] = 0xc1;
+
+        let mut compressed = Vec::new();
+        let mut encoder = DeflateEncoder::new(&mut compressed, Compression::default());
+        encoder.write_all(&encoded).unwrap();
+        encoder.finish().unwrap();
+
+        match read_circuit(&compressed, &CircuitLimits::default()) {
+            Err(Error::CircuitDecode { offset, .. }) => {
+                assert!(offset > 2 && offset <= encoded.len() as u64)
+            }
+            result => panic!("expected a decoding error but got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn reads_framed_circuits() -> Result<(), BackendError> {
+        let circuit_bytes = write(&circuit(2));
+        let mut crc = Crc::new();
+        crc.update(&circuit_bytes);
+        let mut framed = (circuit_bytes.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&circuit_bytes);
+        framed.extend_from_slice(&[0; 7]);
+
+        let read = read_circuit_framed(&framed, Some(crc.sum()), &CircuitLimits::default())?;
+        assert_eq!(write(&read), circuit_bytes);
+        read_circuit_framed(&framed, None, &CircuitLimits::default())?;
+
+        assert!(
+            read_circuit_framed(&framed, Some(crc.sum() ^ 1), &CircuitLimits::default()).is_err()
+        );
+        for truncated in [&framed[..3], &framed[..framed.len() - 8]] {
+            assert!(matches!(
+                unframe(truncated),
+                Err(Error::MalformedCircuit(_))
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for bytes in [&[][..], &[0xff; 64][..], &write(&circuit(1))[..4]] {
+            assert!(matches!(
+                read_circuit(bytes, &CircuitLimits::default()),
+                Err(Error::MalformedCircuit(_) | Error::CircuitDecode { .. })
+            ));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::barretenberg_structures::{Assignments, ConstraintSystem};
-use crate::crs::{CRS, G2};
-use crate::{Barretenberg, Error, FIELD_BYTES};
+use crate::crs::CRS;
+use crate::{telemetry, Barretenberg, Error, FIELD_BYTES};
+use zeroize::Zeroize;
 
 const NUM_RESERVED_GATES: u32 = 4; // this must be >= num_roots_cut_out_of_vanishing_polynomial (found under prover settings in barretenberg)
 
@@ -76,10 +77,10 @@ impl Composer for Barretenberg {
         let mut pk_addr: *mut u8 = std::ptr::null_mut();
         let pk_ptr = &mut pk_addr as *mut *mut u8;
 
-        let pk_size;
-        unsafe {
-            pk_size = barretenberg_sys::composer::init_proving_key(&cs_buf, pk_ptr);
-        }
+        let _thread_limit = self.limit_threads();
+        let pk_size = telemetry::time_ffi("init_proving_key", || unsafe {
+            barretenberg_sys::composer::init_proving_key(&cs_buf, pk_ptr)
+        });
 
         let result;
         unsafe {
@@ -96,21 +97,22 @@ impl Composer for Barretenberg {
         let circuit_size = self.get_circuit_size(constraint_system)?;
         let CRS {
             g1_data, g2_data, ..
-        } = CRS::new(circuit_size as usize);
-        let pippenger_ptr = self.get_pippenger(&g1_data)?.pointer();
+        } = self.crs(circuit_size as usize)?;
+        let pippenger = self.get_pippenger(&g1_data)?;
+        let pippenger_ptr = pippenger.pointer();
 
         let mut vk_addr: *mut u8 = std::ptr::null_mut();
         let vk_ptr = &mut vk_addr as *mut *mut u8;
 
-        let vk_size;
-        unsafe {
-            vk_size = barretenberg_sys::composer::init_verification_key(
+        let _thread_limit = self.limit_threads();
+        let vk_size = telemetry::time_ffi("init_verification_key", || unsafe {
+            barretenberg_sys::composer::init_verification_key(
                 pippenger_ptr,
                 &g2_data,
                 proving_key,
                 vk_ptr,
             )
-        }
+        });
 
         let result;
         unsafe {
@@ -122,31 +124,35 @@ impl Composer for Barretenberg {
     fn create_proof_with_pk(
         &self,
         constraint_system: &ConstraintSystem,
-        witness: Assignments,
+        mut witness: Assignments,
         proving_key: &[u8],
     ) -> Result<Vec<u8>, Error> {
         let circuit_size = self.get_circuit_size(constraint_system)?;
         let CRS {
             g1_data, g2_data, ..
-        } = CRS::new(circuit_size as usize);
-        let pippenger_ptr = self.get_pippenger(&g1_data)?.pointer();
+        } = self.crs(circuit_size as usize)?;
+        let pippenger = self.get_pippenger(&g1_data)?;
+        let pippenger_ptr = pippenger.pointer();
         let cs_buf: Vec<u8> = constraint_system.to_bytes();
-        let witness_buf = witness.to_bytes();
+        let mut witness_buf = witness.to_bytes();
+        witness.zeroize();
 
         let mut proof_addr: *mut u8 = std::ptr::null_mut();
         let p_proof = &mut proof_addr as *mut *mut u8;
 
-        let proof_size;
-        unsafe {
-            proof_size = barretenberg_sys::composer::create_proof_with_pk(
+        let _thread_limit = self.limit_threads();
+        let proof_size = telemetry::time_ffi("create_proof_with_pk", || unsafe {
+            barretenberg_sys::composer::create_proof_with_pk(
                 pippenger_ptr,
                 &g2_data,
                 proving_key,
                 &cs_buf,
                 &witness_buf,
                 p_proof,
-            );
-        }
+            )
+        });
+        witness_buf.zeroize();
+        telemetry::proof_generated();
 
         let result;
         unsafe {
@@ -170,20 +176,17 @@ impl Composer for Barretenberg {
         public_inputs: Assignments,
         verification_key: &[u8],
     ) -> Result<bool, Error> {
-        let g2_data = G2::new().data;
+        let g2_data = self.g2().data;
 
         // Barretenberg expects public inputs to be prepended onto the proof
         let proof = prepend_public_inputs(proof.to_vec(), public_inputs);
         let cs_buf = constraint_system.to_bytes();
 
-        let verified;
-        unsafe {
-            verified = barretenberg_sys::composer::verify_with_vk(
-                &g2_data,
-                verification_key,
-                &cs_buf,
-                &proof,
-            );
+        let verified = telemetry::time_ffi("verify_with_vk", || unsafe {
+            barretenberg_sys::composer::verify_with_vk(&g2_data, verification_key, &cs_buf, &proof)
+        });
+        if !verified {
+            telemetry::verification_failed();
         }
         Ok(verified)
     }
@@ -208,9 +211,12 @@ impl Composer for Barretenberg {
         // This doesn't unwrap the result because we need to free even if there is a failure
         let circuit_size = self.call("acir_proofs_get_total_circuit_size", &cs_ptr);
 
-        self.free(cs_ptr)?;
+        // A failed call poisons the backend, so its error is returned rather than that from `free`.
+        let freed = self.free(cs_ptr);
+        let circuit_size = circuit_size?;
+        freed?;
 
-        let size: u32 = circuit_size?.try_into()?;
+        let size: u32 = circuit_size.try_into()?;
 
         pow2ceil(size + NUM_RESERVED_GATES)
     }
@@ -222,9 +228,11 @@ impl Composer for Barretenberg {
         // This doesn't unwrap the result because we need to free even if there is a failure
         let circuit_size = self.call("acir_proofs_get_exact_circuit_size", &cs_ptr);
 
-        self.free(cs_ptr)?;
+        let freed = self.free(cs_ptr);
+        let circuit_size = circuit_size?;
+        freed?;
 
-        Ok(circuit_size?.try_into()?)
+        Ok(circuit_size.try_into()?)
     }
 
     fn compute_proving_key(&self, constraint_system: &ConstraintSystem) -> Result<Vec<u8>, Error> {
@@ -235,16 +243,22 @@ impl Composer for Barretenberg {
         // `pk_ptr_ptr` is a pointer to a pointer which holds the proving key.
         let pk_ptr_ptr: usize = 0;
 
-        let pk_size = self.call_multiple(
-            "acir_proofs_init_proving_key",
-            vec![&cs_ptr, &pk_ptr_ptr.into()],
-        )?;
+        let pk_size = telemetry::time_ffi("init_proving_key", || {
+            self.call_multiple(
+                "acir_proofs_init_proving_key",
+                vec![&cs_ptr, &pk_ptr_ptr.into()],
+            )
+        })?;
 
         // We then need to read the pointer at `pk_ptr_ptr` to get the key's location
         // and then slice memory again at `pk_ptr` to get the proving key.
-        let pk_ptr = self.get_pointer(pk_ptr_ptr);
+        let proving_key = WasmBufferHandle {
+            barretenberg: self,
+            ptr: self.get_pointer(pk_ptr_ptr),
+            len: pk_size.try_into()?,
+        };
 
-        Ok(self.read_memory_variable_length(pk_ptr, pk_size.try_into()?))
+        Ok(proving_key.to_vec())
     }
 
     fn compute_verification_key(
@@ -255,8 +269,9 @@ impl Composer for Barretenberg {
         let circuit_size = self.get_circuit_size(constraint_system)?;
         let CRS {
             g1_data, g2_data, ..
-        } = CRS::new(circuit_size as usize);
-        let pippenger_ptr = self.get_pippenger(&g1_data)?.pointer();
+        } = self.crs(circuit_size as usize)?;
+        let pippenger = self.get_pippenger(&g1_data)?;
+        let pippenger_ptr = pippenger.pointer();
 
         let g2_ptr = self.allocate(&g2_data)?;
         let pk_ptr = self.allocate(proving_key)?;
@@ -265,34 +280,44 @@ impl Composer for Barretenberg {
         // `vk_ptr_ptr` is a pointer to a pointer which holds the verification key.
         let vk_ptr_ptr: usize = 0;
 
-        let vk_size = self.call_multiple(
-            "acir_proofs_init_verification_key",
-            vec![&pippenger_ptr, &g2_ptr, &pk_ptr, &vk_ptr_ptr.into()],
-        )?;
+        let vk_size = telemetry::time_ffi("init_verification_key", || {
+            self.call_multiple(
+                "acir_proofs_init_verification_key",
+                vec![&pippenger_ptr, &g2_ptr, &pk_ptr, &vk_ptr_ptr.into()],
+            )
+        })?;
 
         // We then need to read the pointer at `vk_ptr_ptr` to get the key's location
         // and then slice memory again at `vk_ptr` to get the verification key.
-        let vk_ptr = self.get_pointer(vk_ptr_ptr);
+        let verification_key = WasmBufferHandle {
+            barretenberg: self,
+            ptr: self.get_pointer(vk_ptr_ptr),
+            len: vk_size.try_into()?,
+        };
 
-        Ok(self.read_memory_variable_length(vk_ptr, vk_size.try_into()?))
+        Ok(verification_key.to_vec())
     }
 
     fn create_proof_with_pk(
         &self,
         constraint_system: &ConstraintSystem,
-        witness: Assignments,
+        mut witness: Assignments,
         proving_key: &[u8],
     ) -> Result<Vec<u8>, Error> {
         let circuit_size = self.get_circuit_size(constraint_system)?;
         let CRS {
             g1_data, g2_data, ..
-        } = CRS::new(circuit_size as usize);
-        let pippenger_ptr = self.get_pippenger(&g1_data)?.pointer();
+        } = self.crs(circuit_size as usize)?;
+        let pippenger = self.get_pippenger(&g1_data)?;
+        let pippenger_ptr = pippenger.pointer();
         let cs_buf: Vec<u8> = constraint_system.to_bytes();
-        let witness_buf = witness.to_bytes();
+        let mut witness_buf = witness.to_bytes();
+        witness.zeroize();
 
         let cs_ptr = self.allocate(&cs_buf)?;
         let witness_ptr = self.allocate(&witness_buf)?;
+        let witness_len = witness_buf.len();
+        witness_buf.zeroize();
         let g2_ptr = self.allocate(&g2_data)?;
         let pk_ptr = self.allocate(proving_key)?;
 
@@ -300,23 +325,41 @@ impl Composer for Barretenberg {
         // `proof_ptr_ptr` is a pointer to a pointer which holds the proof data.
         let proof_ptr_ptr: usize = 0;
 
-        let proof_size = self.call_multiple(
-            "acir_proofs_new_proof",
-            vec![
-                &pippenger_ptr,
-                &g2_ptr,
-                &pk_ptr,
-                &cs_ptr,
-                &witness_ptr,
-                &proof_ptr_ptr.into(),
-            ],
-        )?;
+        let proof_size = telemetry::time_ffi("create_proof_with_pk", || {
+            self.call_multiple(
+                "acir_proofs_new_proof",
+                vec![
+                    &pippenger_ptr,
+                    &g2_ptr,
+                    &pk_ptr,
+                    &cs_ptr,
+                    &witness_ptr,
+                    &proof_ptr_ptr.into(),
+                ],
+            )
+        });
+
+        // Wipe the witness from the wasm heap before releasing it, whether or not proving succeeded.
+        // If proving failed then that error is returned rather than any failure to clean up after it.
+        let witness_offset: Result<usize, _> = witness_ptr.clone().try_into();
+        if let Ok(witness_offset) = witness_offset {
+            self.transfer_to_heap(&vec![0; witness_len], witness_offset);
+        }
+        let freed = self.free(witness_ptr);
+
+        let proof_size = proof_size?;
+        witness_offset?;
+        freed?;
+        telemetry::proof_generated();
 
         // We then need to read the pointer at `proof_ptr_ptr` to get the proof's location
         // and then slice memory again at `proof_ptr` to get the proof data.
-        let proof_ptr = self.get_pointer(proof_ptr_ptr);
-
-        let result = self.read_memory_variable_length(proof_ptr, proof_size.try_into()?);
+        let result = WasmBufferHandle {
+            barretenberg: self,
+            ptr: self.get_pointer(proof_ptr_ptr),
+            len: proof_size.try_into()?,
+        }
+        .to_vec();
 
         // Barretenberg returns proofs which are prepended with the public inputs.
         // This behavior is nonstandard so we strip the public inputs from the proof.
@@ -335,7 +378,7 @@ impl Composer for Barretenberg {
         public_inputs: Assignments,
         verification_key: &[u8],
     ) -> Result<bool, Error> {
-        let g2_data = G2::new().data;
+        let g2_data = self.g2().data;
 
         // Barretenberg expects public inputs to be prepended onto the proof
         let proof = prepend_public_inputs(proof.to_vec(), public_inputs);
@@ -347,14 +390,47 @@ impl Composer for Barretenberg {
         let vk_ptr = self.allocate(verification_key)?;
 
         // This doesn't unwrap the result because we need to free even if there is a failure
-        let verified = self.call_multiple(
-            "acir_proofs_verify_proof",
-            vec![&g2_ptr, &vk_ptr, &cs_ptr, &proof_ptr, &proof.len().into()],
-        );
+        let verified = telemetry::time_ffi("verify_with_vk", || {
+            self.call_multiple(
+                "acir_proofs_verify_proof",
+                vec![&g2_ptr, &vk_ptr, &cs_ptr, &proof_ptr, &proof.len().into()],
+            )
+        });
 
-        self.free(proof_ptr)?;
+        let freed = self.free(proof_ptr);
+        let verified = verified?;
+        freed?;
 
-        Ok(verified?.try_into()?)
+        let verified: bool = verified.try_into()?;
+        if !verified {
+            telemetry::verification_failed();
+        }
+        Ok(verified)
+    }
+}
+
+/// Owns a buffer which barretenberg allocated on the wasm heap to return a key, proof or verifier contract, freeing it
+/// when dropped.
+#[cfg(not(feature = "native"))]
+pub(crate) struct WasmBufferHandle<'a> {
+    pub(crate) barretenberg: &'a Barretenberg,
+    pub(crate) ptr: usize,
+    pub(crate) len: usize,
+}
+
+#[cfg(not(feature = "native"))]
+impl WasmBufferHandle<'_> {
+    pub(crate) fn to_vec(&self) -> Vec<u8> {
+        self.barretenberg
+            .read_memory_variable_length(self.ptr, self.len)
+    }
+}
+
+#[cfg(not(feature = "native"))]
+impl Drop for WasmBufferHandle<'_> {
+    fn drop(&mut self) {
+        // This buffer wasn't allocated through `Barretenberg::allocate` so allocation observers aren't told it's freed.
+        let _ = self.barretenberg.call("bbfree", &self.ptr.into());
     }
 }
 
@@ -401,7 +477,8 @@ mod test {
             ComputeMerkleRootConstraint, Constraint, Keccak256Constraint, LogicConstraint,
             PedersenConstraint, RangeConstraint, SchnorrConstraint,
         },
-        merkle::{MerkleTree, MessageHasher},
+        primitives::blake2s_to_field,
+        InMemoryMerkleTree,
     };
 
     #[test]
@@ -771,24 +848,20 @@ mod test {
 
     #[test]
     fn test_compute_merkle_root_constraint() -> Result<(), Error> {
-        use tempfile::tempdir;
-        let temp_dir = tempdir().unwrap();
-        let mut msg_hasher: blake2::Blake2s = MessageHasher::new();
-
-        let tree: MerkleTree<blake2::Blake2s, Barretenberg> = MerkleTree::new(3, &temp_dir);
+        let tree = InMemoryMerkleTree::new(3).unwrap();
 
         let empty_leaf = vec![0; 64];
 
         let index = FieldElement::zero();
-        let index_as_usize: usize = 0;
+        let index_as_u64: u64 = 0;
         let mut index_bits = index.bits();
         index_bits.reverse();
 
-        let leaf = msg_hasher.hash(&empty_leaf);
+        let leaf = blake2s_to_field(&empty_leaf);
 
         let root = tree.root();
 
-        let hash_path = tree.get_hash_path(index_as_usize);
+        let hash_path = tree.get_hash_path(index_as_u64).unwrap();
         let mut hash_path_ref = Vec::new();
         for (i, path_pair) in hash_path.into_iter().enumerate() {
             let path_bit = index_bits[i];
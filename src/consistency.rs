@@ -0,0 +1,165 @@
+//! A harness for checking that the partial witness generator and the prover agree on whether a witness
+//! satisfies a circuit.
+//!
+//! Black box function implementations in the solver can accept inputs which barretenberg's constraints reject
+//! (or vice versa). [`check`] runs a circuit through every stage of proving and reports the stage at which the
+//! two diverge.
+
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::pwg::block::Blocks;
+use acvm::{
+    FieldElement, OpcodeResolutionError, PartialWitnessGenerator, PartialWitnessGeneratorStatus,
+    ProofSystemCompiler,
+};
+use thiserror::Error;
+
+use crate::{BackendError, Barretenberg};
+
+/// The stage at which a circuit and witness failed to make it through [`check`].
+#[derive(Debug, Error)]
+pub enum Divergence {
+    #[error("the partial witness generator failed to solve the circuit")]
+    Solve(#[source] OpcodeResolutionError),
+
+    #[error("the circuit requires oracle data which cannot be provided by the harness")]
+    RequiresOracleData,
+
+    #[error("the circuit could not be preprocessed")]
+    Preprocess(#[source] BackendError),
+
+    #[error("the partial witness generator solved the circuit but the prover failed")]
+    Prove(#[source] BackendError),
+
+    #[error(
+        "the partial witness generator solved the circuit but the proof could not be verified"
+    )]
+    Verify(#[source] BackendError),
+
+    #[error("the partial witness generator solved the circuit but the proof was rejected by the verifier")]
+    ProofRejected,
+}
+
+/// Solves the witness for `circuit` from `initial_witness`, then proves and verifies it.
+///
+/// Returns the solved witness if every stage succeeds.
+pub fn check(
+    circuit: &Circuit,
+    initial_witness: BTreeMap<Witness, FieldElement>,
+) -> Result<BTreeMap<Witness, FieldElement>, Divergence> {
+    let bb = Barretenberg::default();
+
+    let mut witness = initial_witness;
+    match bb.solve(
+        &mut witness,
+        &mut Blocks::default(),
+        circuit.opcodes.clone(),
+    ) {
+        Ok(PartialWitnessGeneratorStatus::Solved) => (),
+        Ok(PartialWitnessGeneratorStatus::RequiresOracleData { .. }) => {
+            return Err(Divergence::RequiresOracleData)
+        }
+        Err(err) => return Err(Divergence::Solve(err)),
+    }
+
+    let (proving_key, verification_key) = bb.preprocess(circuit).map_err(Divergence::Preprocess)?;
+
+    let proof = bb
+        .prove_with_pk(circuit, witness.clone(), &proving_key)
+        .map_err(Divergence::Prove)?;
+
+    let public_inputs = circuit
+        .public_inputs()
+        .indices()
+        .into_iter()
+        .filter_map(|index| {
+            let witness_index = Witness(index);
+            witness
+                .get(&witness_index)
+                .map(|value| (witness_index, *value))
+        })
+        .collect();
+
+    let verified = bb
+        .verify_with_vk(&proof, public_inputs, circuit, &verification_key)
+        .map_err(Divergence::Verify)?;
+    if !verified {
+        return Err(Divergence::ProofRejected);
+    }
+
+    Ok(witness)
+}
+
+/// [Proptest](https://docs.rs/proptest) strategies for property-based tests built on [`check`].
+#[cfg(feature = "proptest")]
+pub mod strategies {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::native_types::Witness;
+    use acvm::FieldElement;
+    use proptest::prelude::*;
+
+    /// Generates field elements, weighted towards the values at which black box implementations tend to diverge.
+    pub fn field_element() -> impl Strategy<Value = FieldElement> {
+        prop_oneof![
+            Just(FieldElement::zero()),
+            Just(FieldElement::one()),
+            Just(-FieldElement::one()),
+            any::<u128>().prop_map(FieldElement::from),
+            any::<[u8; 32]>().prop_map(|bytes| FieldElement::from_be_bytes_reduce(&bytes)),
+        ]
+    }
+
+    /// Generates an initial witness assigning a [field element][field_element()] to each of `witnesses`.
+    pub fn initial_witness(
+        witnesses: Vec<Witness>,
+    ) -> impl Strategy<Value = BTreeMap<Witness, FieldElement>> {
+        let num_witnesses = witnesses.len();
+        proptest::collection::vec(field_element(), num_witnesses)
+            .prop_map(move |values| witnesses.iter().copied().zip(values).collect())
+    }
+}
+
+#[cfg(all(test, feature = "proptest"))]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::strategies::{field_element, initial_witness};
+    use super::*;
+    use crate::test_circuits;
+
+    proptest! {
+        // Every case preprocesses and proves a circuit, so keep the number of cases small.
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn pedersen_is_consistent_for_any_inputs(
+            initial_witness in initial_witness(
+                test_circuits::pedersen_hashes(2).initial_witness.into_keys().collect()
+            )
+        ) {
+            let test_circuit = test_circuits::pedersen_hashes(2);
+
+            let result = check(&test_circuit.circuit, initial_witness);
+
+            prop_assert!(result.is_ok(), "{result:?}");
+        }
+
+        #[test]
+        fn solver_rejects_what_the_prover_would(input in field_element(), output in field_element()) {
+            let test_circuit = test_circuits::arithmetic_gates(0);
+            // The circuit constrains Witness(2) to be the square of Witness(1).
+            let initial_witness = BTreeMap::from([(Witness(1), input), (Witness(2), output)]);
+
+            let result = check(&test_circuit.circuit, initial_witness);
+
+            if output == input * input {
+                prop_assert!(result.is_ok(), "{result:?}");
+            } else {
+                prop_assert!(matches!(result, Err(Divergence::Solve(_))), "{result:?}");
+            }
+        }
+    }
+}
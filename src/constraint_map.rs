@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::{Circuit, Opcode};
+use acvm::acir::native_types::Witness;
+use acvm::acir::BlackBoxFunc;
+use acvm::FieldElement;
+
+use crate::barretenberg_structures::ConstraintSystem;
+use crate::{BackendError, WitnessVector};
+
+/// The groups of constraints which make up the constraint system passed to barretenberg.
+///
+/// Constraints are numbered separately within each group, in the order in which barretenberg receives them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ConstraintKind {
+    Arithmetic,
+    Range,
+    Logic,
+    Sha256,
+    Blake2s,
+    Keccak256,
+    Pedersen,
+    ComputeMerkleRoot,
+    SchnorrVerify,
+    EcdsaSecp256k1,
+    HashToField,
+    FixedBaseScalarMul,
+}
+
+impl ConstraintKind {
+    /// Returns the kind of constraint which barretenberg receives for `opcode`, if any.
+    pub(crate) fn of_opcode(opcode: &Opcode) -> Option<ConstraintKind> {
+        match opcode {
+            Opcode::Arithmetic(_) => Some(ConstraintKind::Arithmetic),
+            Opcode::BlackBoxFuncCall(gadget_call) => match gadget_call.name {
+                BlackBoxFunc::RANGE => Some(ConstraintKind::Range),
+                BlackBoxFunc::AND | BlackBoxFunc::XOR => Some(ConstraintKind::Logic),
+                BlackBoxFunc::SHA256 => Some(ConstraintKind::Sha256),
+                BlackBoxFunc::Blake2s => Some(ConstraintKind::Blake2s),
+                BlackBoxFunc::Keccak256 => Some(ConstraintKind::Keccak256),
+                BlackBoxFunc::Pedersen => Some(ConstraintKind::Pedersen),
+                BlackBoxFunc::ComputeMerkleRoot => Some(ConstraintKind::ComputeMerkleRoot),
+                BlackBoxFunc::SchnorrVerify => Some(ConstraintKind::SchnorrVerify),
+                BlackBoxFunc::EcdsaSecp256k1 => Some(ConstraintKind::EcdsaSecp256k1),
+                BlackBoxFunc::HashToField128Security => Some(ConstraintKind::HashToField),
+                BlackBoxFunc::FixedBaseScalarMul => Some(ConstraintKind::FixedBaseScalarMul),
+                BlackBoxFunc::AES => None,
+            },
+            Opcode::Directive(_)
+            | Opcode::Oracle(_)
+            | Opcode::Block(_)
+            | Opcode::RAM(_)
+            | Opcode::ROM(_) => None,
+        }
+    }
+}
+
+/// Maps the constraints barretenberg receives for a circuit back to the ACIR opcodes they were created from.
+///
+/// Range constraints are deduplicated before being passed to barretenberg, so several opcodes may map
+/// onto a single range constraint. In this case the constraint is attributed to the first such opcode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConstraintMap {
+    opcode_indices: BTreeMap<ConstraintKind, Vec<usize>>,
+}
+
+impl ConstraintMap {
+    pub(crate) fn push(&mut self, kind: ConstraintKind, opcode_index: usize) {
+        self.opcode_indices
+            .entry(kind)
+            .or_default()
+            .push(opcode_index);
+    }
+
+    /// Returns the index of the opcode which created the `constraint_index`-th constraint of the given `kind`.
+    pub fn opcode_index(&self, kind: ConstraintKind, constraint_index: usize) -> Option<usize> {
+        self.opcode_indices
+            .get(&kind)?
+            .get(constraint_index)
+            .copied()
+    }
+
+    /// Returns the number of constraints of the given `kind`.
+    pub fn num_constraints(&self, kind: ConstraintKind) -> usize {
+        self.opcode_indices.get(&kind).map_or(0, Vec::len)
+    }
+}
+
+/// A constraint which is not satisfied by a witness, as found by [`explain_failed_constraint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailedConstraint {
+    pub kind: ConstraintKind,
+    /// The index of the constraint among those of the same `kind`.
+    pub constraint_index: usize,
+    /// The index of the opcode within the circuit which created the constraint.
+    pub opcode_index: usize,
+}
+
+/// Returns a map from each constraint created for `circuit` back to the opcode it originated from.
+///
+/// This is intended to be stored alongside a serialized circuit so that errors reported by barretenberg
+/// in terms of its constraints can be traced back to the ACIR program.
+pub fn constraint_map(circuit: &Circuit) -> Result<ConstraintMap, BackendError> {
+    let (_, constraint_map) = ConstraintSystem::from_circuit_with_map(circuit, false)?;
+    Ok(constraint_map)
+}
+
+/// Finds the constraint responsible for a proof failing to be created or verified for `witness_values`.
+///
+/// Barretenberg only reports that a witness does not satisfy the circuit, so this re-evaluates the
+/// arithmetic and range constraints against the witness and returns the first which fails, if any.
+/// Constraints created by black box functions are not checked.
+pub fn explain_failed_constraint(
+    circuit: &Circuit,
+    witness_values: &BTreeMap<Witness, FieldElement>,
+) -> Result<Option<FailedConstraint>, BackendError> {
+    let (constraint_system, constraint_map) =
+        ConstraintSystem::from_circuit_with_map(circuit, false)?;
+
+    let failed_constraint = constraint_system
+        .first_unsatisfied_constraint(&WitnessVector::from_witness_map(circuit, witness_values))
+        .and_then(|(kind, constraint_index)| {
+            Some(FailedConstraint {
+                kind,
+                constraint_index,
+                opcode_index: constraint_map.opcode_index(kind, constraint_index)?,
+            })
+        });
+    Ok(failed_constraint)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+    use acvm::acir::circuit::PublicInputs;
+    use acvm::acir::native_types::Expression;
+
+    use super::*;
+
+    fn range(witness: u32, num_bits: u32) -> Opcode {
+        Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::RANGE,
+            inputs: vec![FunctionInput {
+                witness: Witness(witness),
+                num_bits,
+            }],
+            outputs: vec![],
+        })
+    }
+
+    /// Constrains `x * y == z` and `x, y` to 8 bits.
+    fn circuit() -> Circuit {
+        let multiplication = Opcode::Arithmetic(Expression {
+            mul_terms: vec![(FieldElement::one(), Witness(1), Witness(2))],
+            linear_combinations: vec![(-FieldElement::one(), Witness(3))],
+            q_c: FieldElement::zero(),
+        });
+        Circuit {
+            current_witness_index: 3,
+            opcodes: vec![range(2, 16), range(1, 8), multiplication, range(2, 8)],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        }
+    }
+
+    fn witness(x: u128, y: u128, z: u128) -> BTreeMap<Witness, FieldElement> {
+        BTreeMap::from([
+            (Witness(1), FieldElement::from(x)),
+            (Witness(2), FieldElement::from(y)),
+            (Witness(3), FieldElement::from(z)),
+        ])
+    }
+
+    #[test]
+    fn maps_constraints_to_opcodes() -> Result<(), BackendError> {
+        let constraint_map = constraint_map(&circuit())?;
+
+        assert_eq!(
+            constraint_map.num_constraints(ConstraintKind::Arithmetic),
+            1
+        );
+        assert_eq!(
+            constraint_map.opcode_index(ConstraintKind::Arithmetic, 0),
+            Some(2)
+        );
+        assert_eq!(constraint_map.num_constraints(ConstraintKind::Range), 3);
+        assert_eq!(
+            constraint_map.opcode_index(ConstraintKind::Range, 0),
+            Some(0)
+        );
+        assert_eq!(
+            constraint_map.opcode_index(ConstraintKind::Range, 2),
+            Some(3)
+        );
+        assert_eq!(constraint_map.opcode_index(ConstraintKind::Range, 3), None);
+        Ok(())
+    }
+
+    #[test]
+    fn explains_failed_constraints() -> Result<(), BackendError> {
+        let circuit = circuit();
+
+        assert_eq!(
+            explain_failed_constraint(&circuit, &witness(3, 5, 15))?,
+            None
+        );
+        assert_eq!(
+            explain_failed_constraint(&circuit, &witness(3, 5, 16))?,
+            Some(FailedConstraint {
+                kind: ConstraintKind::Arithmetic,
+                constraint_index: 0,
+                opcode_index: 2,
+            })
+        );
+        assert_eq!(
+            explain_failed_constraint(&circuit, &witness(3, 256, 768))?,
+            Some(FailedConstraint {
+                kind: ConstraintKind::Range,
+                constraint_index: 2,
+                opcode_index: 3,
+            })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! Proving and verifying constraint systems which were built directly in barretenberg's serialized format
+//! rather than converted from ACIR.
+
+use acvm::acir::circuit::Circuit;
+use acvm::FieldElement;
+
+use crate::barretenberg_structures::{
+    is_versioned_constraint_system, Assignments, ConstraintSystem,
+};
+use crate::composer::Composer;
+use crate::{BackendError, Barretenberg, Proof, ProvingKey, VerificationKey};
+
+/// Converts `circuit` into barretenberg's constraint system format, prefixed with a header recording the version
+/// of the format.
+///
+/// The format follows the layout of barretenberg's structures so changes between releases of this crate. The
+/// header lets the constraint system be stored on disk and rejected with a clear error, rather than misread, by a
+/// release which uses a different layout. The result can be passed to any of the `*_constraint_system` methods.
+pub fn serialize_constraint_system(circuit: &Circuit) -> Result<Vec<u8>, BackendError> {
+    Ok(ConstraintSystem::try_from(circuit)?.to_versioned_bytes())
+}
+
+/// Parses either a versioned constraint system from [`serialize_constraint_system`] or an unversioned one.
+fn parse_constraint_system(bytes: &[u8]) -> Result<ConstraintSystem, BackendError> {
+    let constraint_system = if is_versioned_constraint_system(bytes) {
+        ConstraintSystem::from_versioned_bytes(bytes)?
+    } else {
+        ConstraintSystem::from_bytes(bytes)?
+    };
+    Ok(constraint_system)
+}
+
+impl Barretenberg {
+    /// Generates the proving and verification keys for a serialized constraint system.
+    ///
+    /// `constraint_system` must be in the format consumed by barretenberg's `acir_format`: the number of variables,
+    /// the public input indices and then each kind of constraint, every value big-endian and each list prefixed
+    /// by its length as a `u32`. The versioned format produced by [`serialize_constraint_system`] is also accepted.
+    pub fn preprocess_constraint_system(
+        &self,
+        constraint_system: &[u8],
+    ) -> Result<(ProvingKey, VerificationKey), BackendError> {
+        let constraint_system = parse_constraint_system(constraint_system)?;
+
+        let proving_key = self.compute_proving_key(&constraint_system)?;
+        let verification_key = self.compute_verification_key(&constraint_system, &proving_key)?;
+        Ok((
+            ProvingKey(proving_key),
+            VerificationKey::from_bytes(&verification_key)?,
+        ))
+    }
+
+    /// Creates a proof for a serialized constraint system.
+    ///
+    /// `assignments` holds the value of each variable in index order, starting from variable 1.
+    pub fn prove_constraint_system(
+        &self,
+        constraint_system: &[u8],
+        assignments: Vec<FieldElement>,
+        proving_key: &ProvingKey,
+    ) -> Result<Proof, BackendError> {
+        let constraint_system = parse_constraint_system(constraint_system)?;
+
+        Ok(Proof(self.create_proof_with_pk(
+            &constraint_system,
+            Assignments::from(assignments),
+            proving_key.as_ref(),
+        )?))
+    }
+
+    /// Verifies a proof created by [`prove_constraint_system`][Barretenberg::prove_constraint_system].
+    ///
+    /// `public_inputs` holds the value of each of the constraint system's public inputs in the order they're listed.
+    pub fn verify_constraint_system(
+        &self,
+        constraint_system: &[u8],
+        proof: &Proof,
+        public_inputs: Vec<FieldElement>,
+        verification_key: &VerificationKey,
+    ) -> Result<bool, BackendError> {
+        let constraint_system = parse_constraint_system(constraint_system)?;
+
+        Ok(Composer::verify_with_vk(
+            self,
+            &constraint_system,
+            &proof.0,
+            Assignments::from(public_inputs),
+            &verification_key.to_bytes(),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::native_types::Witness;
+    use acvm::pwg::block::Blocks;
+    use acvm::PartialWitnessGenerator;
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    #[test]
+    fn proves_serialized_constraint_system() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let test_circuit = arithmetic_gates(2);
+        let circuit = test_circuit.circuit;
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .unwrap();
+
+        let constraint_system = ConstraintSystem::try_from(&circuit)?;
+        let assignments: Vec<_> = (1..constraint_system.num_variables())
+            .map(|index| {
+                witness
+                    .get(&Witness(index))
+                    .map_or(FieldElement::zero(), |field| *field)
+            })
+            .collect();
+        let constraint_system = constraint_system.to_bytes();
+
+        let (proving_key, verification_key) =
+            bb.preprocess_constraint_system(&constraint_system)?;
+        let proof = bb.prove_constraint_system(&constraint_system, assignments, &proving_key)?;
+
+        assert!(bb.verify_constraint_system(
+            &constraint_system,
+            &proof,
+            Vec::new(),
+            &verification_key
+        )?);
+        assert!(bb
+            .preprocess_constraint_system(&constraint_system[1..])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_other_constraint_system_versions() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let circuit = arithmetic_gates(2).circuit;
+
+        let mut constraint_system = serialize_constraint_system(&circuit)?;
+        assert_eq!(
+            parse_constraint_system(&constraint_system)?.to_bytes(),
+            ConstraintSystem::try_from(&circuit)?.to_bytes()
+        );
+
+        constraint_system[7] += 1;
+        assert!(bb.preprocess_constraint_system(&constraint_system).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+use crate::Barretenberg;
+
+/// Whether the CPU supports the instructions used by barretenberg's x86_64 assembly, as returned by
+/// [`Barretenberg::cpu_features`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuFeatures {
+    /// BMI2, which provides the `mulx` instruction used for field multiplication.
+    pub bmi2: bool,
+    /// ADX, which provides the `adcx` and `adox` instructions used to accumulate field multiplications.
+    pub adx: bool,
+}
+
+impl CpuFeatures {
+    /// Returns `true` if the CPU can run a barretenberg library built with its assembly enabled. On other CPUs such a
+    /// library fails with an illegal instruction the first time it multiplies field elements, so a portable build of
+    /// barretenberg must be installed instead.
+    pub fn supports_asm(&self) -> bool {
+        self.bmi2 && self.adx
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn detect() -> CpuFeatures {
+        CpuFeatures {
+            bmi2: std::is_x86_feature_detected!("bmi2"),
+            adx: std::is_x86_feature_detected!("adx"),
+        }
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn detect() -> CpuFeatures {
+        CpuFeatures {
+            bmi2: false,
+            adx: false,
+        }
+    }
+}
+
+impl Barretenberg {
+    /// Returns which of the instructions used by barretenberg's assembly the CPU supports, e.g. so that a service can
+    /// log them at startup or refuse to start rather than crash mid-proof.
+    ///
+    /// Barretenberg only uses its assembly when built for x86_64 with it enabled. Barretenberg's wasm never uses it.
+    pub fn cpu_features(&self) -> CpuFeatures {
+        CpuFeatures::detect()
+    }
+}
@@ -1,7 +1,19 @@
-use std::{env, fs::File, io::Write, path::PathBuf};
-
+use std::{
+    collections::BTreeMap,
+    env,
+    fs::File,
+    io::Write,
+    ops::{Deref, Range},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError, Weak},
+};
+
+use fs2::FileExt;
 use futures_util::StreamExt;
 
+use crate::options::TRANSCRIPT_ENV_VAR;
+use crate::Error;
+
 // TODO(blaine): Use manifest parsing in BB instead of hardcoding these
 const G1_START: usize = 28;
 const G2_START: usize = 28 + (5_040_001 * 64);
@@ -12,8 +24,36 @@ const TRANSCRIPT_NAME: &str = "transcript00.dat";
 const TRANSCRIPT_URL: &str =
     "http://aztec-ignition.s3.amazonaws.com/MAIN%20IGNITION/monomial/transcript00.dat";
 
-fn transcript_location() -> PathBuf {
-    match env::var("BARRETENBERG_TRANSCRIPT") {
+impl crate::Barretenberg {
+    /// Returns the location of the transcript, preferring the path set in the backend's options.
+    pub(crate) fn transcript_location(&self) -> PathBuf {
+        transcript_location(self.options.transcript_path.as_deref())
+    }
+
+    /// Returns the SRS points needed for a circuit of `num_points` gates.
+    pub(crate) fn crs(&self, num_points: usize) -> Result<CRS, Error> {
+        #[cfg(feature = "dev-srs")]
+        if self.options.allow_dev_srs {
+            return crate::dev_srs::crs(num_points);
+        }
+        Ok(CRS::new(self.transcript_location(), num_points))
+    }
+
+    pub(crate) fn g2(&self) -> G2 {
+        #[cfg(feature = "dev-srs")]
+        if self.options.allow_dev_srs {
+            return crate::dev_srs::g2();
+        }
+        G2::new(self.transcript_location())
+    }
+}
+
+fn transcript_location(configured_path: Option<&Path>) -> PathBuf {
+    if let Some(path) = configured_path {
+        return path.to_path_buf();
+    }
+
+    match env::var(TRANSCRIPT_ENV_VAR) {
         Ok(dir) => PathBuf::from(dir),
         Err(_) => dirs::home_dir()
             .unwrap()
@@ -24,64 +64,150 @@ fn transcript_location() -> PathBuf {
     }
 }
 
+/// The transcripts which have been loaded by backends in this process.
+static SRS_STORE: SrsStore = SrsStore::new();
+
+/// Shares transcripts between every backend in the process, so that running many backends at once doesn't hold a
+/// separate copy of the SRS points for each of them.
+///
+/// Transcripts are keyed by their path and size so that a transcript which is replaced, e.g. by downloading it
+/// again, is reloaded. A transcript is unloaded once no backend is using it.
+pub(crate) struct SrsStore {
+    transcripts: Mutex<BTreeMap<(PathBuf, u64), Weak<Srs>>>,
+}
+
+impl SrsStore {
+    const fn new() -> SrsStore {
+        SrsStore {
+            transcripts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the transcript at `path`, downloading it if it's missing or incomplete.
+    fn load(&self, transcript: &Path) -> Arc<Srs> {
+        // If the CRS does not exist, then download it from S3
+        if !transcript.exists() {
+            download_crs(transcript.to_path_buf()).unwrap();
+        }
+
+        // Read CRS, if it's incomplete, download it
+        let srs = self.get_or_read(transcript);
+        if srs.len() < G2_END + 1 {
+            drop(srs);
+            download_crs(transcript.to_path_buf()).unwrap();
+            return self.get_or_read(transcript);
+        }
+        srs
+    }
+
+    fn get_or_read(&self, transcript: &Path) -> Arc<Srs> {
+        let file_size = std::fs::metadata(transcript).map_or(0, |metadata| metadata.len());
+        let key = (transcript.to_path_buf(), file_size);
+
+        // The lock is held whilst reading so that backends which start together share a single read.
+        let mut transcripts = self
+            .transcripts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(srs) = transcripts.get(&key).and_then(Weak::upgrade) {
+            return srs;
+        }
+
+        let srs = Arc::new(read_crs(transcript.to_path_buf()));
+        transcripts.retain(|_, srs| srs.strong_count() > 0);
+        transcripts.insert(key, Arc::downgrade(&srs));
+        srs
+    }
+}
+
+/// The contents of a transcript.
+pub(crate) enum Srs {
+    /// An uncompressed transcript, mapped read-only so that its pages are shared with every other mapping of it.
+    ///
+    /// A shared lock is held on the file for as long as it's mapped, see [`read_crs`].
+    Mapped {
+        map: memmap2::Mmap,
+        _file: File,
+    },
+    Owned(Vec<u8>),
+}
+
+impl Deref for Srs {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Srs::Mapped { map, .. } => map,
+            Srs::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// The G1 points of an SRS, borrowed from a shared transcript rather than copied out of it.
+#[derive(Clone)]
+pub(crate) struct G1Points {
+    srs: Arc<Srs>,
+    range: Range<usize>,
+}
+
+impl Deref for G1Points {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.srs[self.range.clone()]
+    }
+}
+
+impl From<Vec<u8>> for G1Points {
+    fn from(points: Vec<u8>) -> G1Points {
+        G1Points {
+            range: 0..points.len(),
+            srs: Arc::new(Srs::Owned(points)),
+        }
+    }
+}
+
+impl Default for G1Points {
+    fn default() -> Self {
+        G1Points::from(Vec::new())
+    }
+}
+
 #[allow(clippy::upper_case_acronyms)]
 #[derive(Clone, Default)]
 pub(crate) struct CRS {
-    pub(crate) g1_data: Vec<u8>,
+    pub(crate) g1_data: G1Points,
     pub(crate) g2_data: Vec<u8>,
     // num_points: usize,
 }
 
 impl CRS {
-    pub(crate) fn new(num_points: usize) -> CRS {
+    pub(crate) fn new(transcript: PathBuf, num_points: usize) -> CRS {
         // UltraPlonk requires a CRS equal to circuit size plus one!
         // We need to bump our polynomial degrees by 1 to handle zero knowledge
         let g1_end = G1_START + ((num_points + 1) * 64) - 1;
 
-        // If the CRS does not exist, then download it from S3
-        if !transcript_location().exists() {
-            download_crs(transcript_location()).unwrap();
-        }
-
-        // Read CRS, if it's incomplete, download it
-        let mut crs = read_crs(transcript_location());
-        if crs.len() < G2_END + 1 {
-            download_crs(transcript_location()).unwrap();
-            crs = read_crs(transcript_location());
-        }
-
-        let g1_data = crs[G1_START..=g1_end].to_vec();
-        let g2_data = crs[G2_START..=G2_END].to_vec();
+        let srs = SRS_STORE.load(&transcript);
+        let g2_data = srs[G2_START..=G2_END].to_vec();
 
         CRS {
-            g1_data,
+            g1_data: G1Points {
+                srs,
+                range: G1_START..g1_end + 1,
+            },
             g2_data,
             // num_points,
         }
     }
 }
 
-// TODO(blaine): Come up with a better abstraction for the CRS so we don't need to read the
-// file everytime we need the G2
 pub(crate) struct G2 {
     pub(crate) data: Vec<u8>,
 }
 
 impl G2 {
-    pub(crate) fn new() -> G2 {
-        // If the CRS does not exist, then download it from S3
-        if !transcript_location().exists() {
-            download_crs(transcript_location()).unwrap();
-        }
-
-        // Read CRS, if it's incomplete, download it
-        let mut crs = read_crs(transcript_location());
-        if crs.len() < G2_END + 1 {
-            download_crs(transcript_location()).unwrap();
-            crs = read_crs(transcript_location());
-        }
-
-        let data = crs[G2_START..=G2_END].to_vec();
+    pub(crate) fn new(transcript: PathBuf) -> G2 {
+        let data = SRS_STORE.load(&transcript)[G2_START..=G2_END].to_vec();
 
         G2 { data }
     }
@@ -89,13 +215,23 @@ impl G2 {
 
 impl Default for G2 {
     fn default() -> Self {
-        Self::new()
+        Self::new(transcript_location(None))
     }
 }
 
-fn read_crs(path: PathBuf) -> Vec<u8> {
-    match std::fs::read(&path) {
-        Ok(bytes) => bytes,
+/// Returns `true` if the transcript at `path` is compressed with Zstandard, as indicated by a `.zst` extension.
+fn is_zstd_compressed(path: &Path) -> bool {
+    path.extension()
+        .map_or(false, |extension| extension == "zst")
+}
+
+fn read_crs(path: PathBuf) -> Srs {
+    if is_zstd_compressed(&path) {
+        return Srs::Owned(read_compressed_crs(&path));
+    }
+
+    let file = match File::open(&path) {
+        Ok(file) => file,
         Err(e) => {
             assert!(
                 e.kind() != std::io::ErrorKind::PermissionDenied,
@@ -106,12 +242,84 @@ fn read_crs(path: PathBuf) -> Vec<u8> {
                 path.display()
             );
         }
+    };
+
+    // Reading a mapping of a file which another process has truncated raises SIGBUS, so the transcript is only
+    // mapped whilst we hold a shared lock on it, which waits for any writer holding an exclusive lock to finish.
+    // Filesystems which don't support locking get a private copy instead.
+    if file.lock_shared().is_err() {
+        return Srs::Owned(std::fs::read(&path).unwrap_or_else(|e| {
+            panic!(
+                "Could not read transcript at location {} ({e})",
+                path.display()
+            )
+        }));
+    }
+
+    // SAFETY: The transcript must not be modified in place whilst it's in use, as documented on
+    // `BackendOptions::transcript_path`. `download_crs` removes the old file before writing a new one, which leaves
+    // existing mappings of the old file intact, and other writers are excluded by the shared lock as long as they
+    // lock the file themselves.
+    match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(map) => Srs::Mapped { map, _file: file },
+        Err(e) => panic!(
+            "Could not map transcript at location {} ({e})",
+            path.display()
+        ),
     }
 }
 
+/// Decompresses the transcript while reading it, stopping once the G2 point has been read so that the points
+/// which are never used aren't decompressed.
+#[cfg(feature = "zstd")]
+fn read_compressed_crs(path: &Path) -> Vec<u8> {
+    use std::io::Read;
+
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            assert!(
+                e.kind() != std::io::ErrorKind::PermissionDenied,
+                "please run again with appropriate permissions."
+            );
+            panic!(
+                "Could not find compressed transcript at location {}.",
+                path.display()
+            );
+        }
+    };
+
+    let mut crs = Vec::with_capacity(G2_END + 1);
+    zstd::stream::read::Decoder::new(file)
+        .and_then(|decoder| decoder.take((G2_END + 1) as u64).read_to_end(&mut crs))
+        .unwrap_or_else(|err| {
+            panic!(
+                "Could not decompress transcript at location {} ({err})",
+                path.display()
+            )
+        });
+    crs
+}
+
+#[cfg(not(feature = "zstd"))]
+fn read_compressed_crs(path: &Path) -> Vec<u8> {
+    panic!(
+        "The transcript at {} is compressed, enable the `zstd` feature to read it.",
+        path.display()
+    );
+}
+
 // XXX: Below is the logic to download the CRS if it is not already present
 
 pub(crate) fn download_crs(path_to_transcript: PathBuf) -> Result<(), String> {
+    // Only the uncompressed transcript is published so we can't download a compressed one in its place.
+    if is_zstd_compressed(&path_to_transcript) {
+        return Err(format!(
+            "Compressed transcript at '{}' is missing or incomplete and can't be downloaded",
+            path_to_transcript.display()
+        ));
+    }
+
     tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()
@@ -183,7 +391,7 @@ async fn download_crs_async(path_to_transcript: PathBuf) -> Result<(), String> {
 fn does_not_panic() {
     let num_points = 4 * 1024;
 
-    let crs = CRS::new(num_points);
+    let crs = CRS::new(transcript_location(None), num_points);
 
     let p_points = barretenberg_sys::pippenger::new(&crs.g1_data);
 
@@ -202,3 +410,15 @@ fn downloading() {
     let res = download_crs(file_path);
     assert_eq!(res, Ok(()));
 }
+
+#[cfg(feature = "native")]
+#[test]
+fn shares_transcripts_between_backends() {
+    let transcript = transcript_location(None);
+
+    let crs = CRS::new(transcript.clone(), 16);
+    let larger_crs = CRS::new(transcript, 1024);
+
+    assert!(Arc::ptr_eq(&crs.g1_data.srs, &larger_crs.g1_data.srs));
+    assert_eq!(crs.g1_data[..], larger_crs.g1_data[..crs.g1_data.len()]);
+}
@@ -0,0 +1,90 @@
+//! A small SRS which can be generated locally, for tests and examples which shouldn't need to download
+//! the ignition transcript.
+//!
+//! The SRS is derived from [`TOXIC_WASTE`], which is public, so anybody can forge proofs against keys
+//! generated from it. It must never be used for circuits whose proofs are relied upon.
+
+use ark_bn254::{Fq, Fr, G1Projective, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+
+use crate::crs::{CRS, G2};
+use crate::Error;
+
+/// The largest circuit size supported by the dev SRS.
+pub(crate) const DEV_SRS_MAX_CIRCUIT_SIZE: usize = 1 << 12;
+
+/// The secret from which the dev SRS is generated.
+const TOXIC_WASTE: u64 = 0x6465_762d_7372_73;
+
+/// Generates the same points as [`CRS::new`] would read from the transcript for a circuit of `num_points` gates.
+pub(crate) fn crs(num_points: usize) -> Result<CRS, Error> {
+    if num_points > DEV_SRS_MAX_CIRCUIT_SIZE {
+        return Err(Error::DevSrsTooSmall {
+            circuit_size: num_points,
+        });
+    }
+
+    let tau = Fr::from(TOXIC_WASTE);
+    let mut power_of_tau = G1Projective::generator();
+    let powers_of_tau: Vec<_> = (0..=num_points)
+        .map(|_| {
+            let point = power_of_tau;
+            power_of_tau *= tau;
+            point
+        })
+        .collect();
+
+    let mut g1_data = Vec::with_capacity(powers_of_tau.len() * 64);
+    for point in G1Projective::normalize_batch(&powers_of_tau) {
+        write_fq(&mut g1_data, &point.x);
+        write_fq(&mut g1_data, &point.y);
+    }
+
+    Ok(CRS {
+        g1_data: g1_data.into(),
+        g2_data: g2().data,
+    })
+}
+
+/// Generates `[τ]₂`, as [`G2::new`] would read from the transcript.
+pub(crate) fn g2() -> G2 {
+    let point = (G2Projective::generator() * Fr::from(TOXIC_WASTE)).into_affine();
+
+    let mut data = Vec::with_capacity(128);
+    for coordinate in [&point.x.c0, &point.x.c1, &point.y.c0, &point.y.c1] {
+        write_fq(&mut data, coordinate);
+    }
+    G2 { data }
+}
+
+/// Writes a base field element in the transcript's encoding: the canonical value (barretenberg converts it to
+/// Montgomery form as it's read), least significant limb first, with each 64 bit limb in big-endian order.
+fn write_fq(buffer: &mut Vec<u8>, field: &Fq) {
+    for limb in field.into_bigint().0 {
+        buffer.extend_from_slice(&limb.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_requested_number_of_points() -> Result<(), Error> {
+        let crs = crs(16)?;
+
+        assert_eq!(crs.g1_data.len(), 17 * 64);
+        assert_eq!(crs.g2_data.len(), 128);
+        assert_eq!(crs.g2_data, g2().data);
+        // The first point is the generator (1, 2), each coordinate written as four big-endian limbs with the least
+        // significant first.
+        let mut generator = [0; 64];
+        generator[7] = 1;
+        generator[32 + 7] = 2;
+        assert_eq!(crs.g1_data[..64], generator);
+
+        assert!(super::crs(DEV_SRS_MAX_CIRCUIT_SIZE + 1).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{BackendError, Error};
+
+impl BackendError {
+    /// Returns a stable code identifying the kind of error, e.g. `BB-ERR-016` for a missing public input, so that
+    /// applications can map failures to their own guidance without matching on the message.
+    ///
+    /// Codes are never reused or reassigned. Failures inside barretenberg itself all share the code `BB-ERR-100`.
+    pub fn code(&self) -> &'static str {
+        self.0.code()
+    }
+}
+
+impl Error {
+    fn code(&self) -> &'static str {
+        match self {
+            Error::Pow2CeilOverflow(_) => "BB-ERR-001",
+            Error::MalformedBlackBoxFunc(..) => "BB-ERR-002",
+            Error::UnsupportedBlackBoxFunc(_) => "BB-ERR-003",
+            Error::MalformedCalldata(_) => "BB-ERR-004",
+            Error::MalformedProof(_) => "BB-ERR-005",
+            Error::MalformedVerificationKey(_) => "BB-ERR-006",
+            Error::MalformedArtifact(_) => "BB-ERR-007",
+            Error::KeyCircuitMismatch { .. } => "BB-ERR-008",
+            Error::MalformedBundle(_) => "BB-ERR-009",
+            #[cfg(feature = "encrypted-keys")]
+            Error::EncryptedKey(_) => "BB-ERR-010",
+            Error::NonCanonicalFieldElement => "BB-ERR-011",
+            Error::FieldMismatch { .. } => "BB-ERR-012",
+            Error::InvalidGrumpkinPoint => "BB-ERR-013",
+            Error::InvalidConfig(_) => "BB-ERR-014",
+            Error::MalformedWitnessMap(_) => "BB-ERR-015",
+            Error::MissingPublicInput(_) => "BB-ERR-016",
+            Error::PublicInputCountMismatch { .. } => "BB-ERR-017",
+            Error::ConflictingWitnessValue(_) => "BB-ERR-018",
+            Error::InvalidMerkleTreeDepth(_) => "BB-ERR-019",
+            Error::MerkleIndexOutOfBounds { .. } => "BB-ERR-020",
+            Error::PreprocessCancelled => "BB-ERR-021",
+            #[cfg(feature = "dev-srs")]
+            Error::DevSrsTooSmall { .. } => "BB-ERR-022",
+            Error::MalformedCircuit(_) => "BB-ERR-023",
+            Error::MalformedConstraintSystem(_) => "BB-ERR-024",
+            Error::CircuitDecode { .. } => "BB-ERR-025",
+            #[cfg(feature = "sealed-witness")]
+            Error::SealedWitness(_) => "BB-ERR-026",
+            Error::ProveCancelled => "BB-ERR-027",
+            Error::MalformedAbi(_) => "BB-ERR-028",
+            Error::AbiInput(_) => "BB-ERR-029",
+            #[cfg(feature = "wasm")]
+            Error::PoisonedBackend => "BB-ERR-030",
+            Error::UnknownCheckpoint(_) => "BB-ERR-031",
+            Error::PreprocessCheckpoint(_) => "BB-ERR-032",
+            #[cfg(feature = "prove-in-subprocess")]
+            Error::BackendCrashed { .. } => "BB-ERR-033",
+            #[cfg(feature = "prove-in-subprocess")]
+            Error::SubprocessFailed(_) => "BB-ERR-034",
+            Error::FixtureUnavailable { .. } => "BB-ERR-035",
+            Error::FixtureMismatch { .. } => "BB-ERR-036",
+            Error::ProverPanicked(_) => "BB-ERR-037",
+            Error::MusigNonce(_) => "BB-ERR-038",
+            #[cfg(any(feature = "native", feature = "wasm"))]
+            Error::OracleResolver(_) => "BB-ERR-039",
+            Error::FromFeature(_) => "BB-ERR-100",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_identify_the_kind_of_error() {
+        let missing_public_input = BackendError::from(Error::MissingPublicInput(3));
+        let malformed_proof = BackendError::from(Error::MalformedProof("too short".to_owned()));
+
+        assert_eq!(missing_public_input.code(), "BB-ERR-016");
+        assert_eq!(malformed_proof.code(), "BB-ERR-005");
+        assert_eq!(
+            BackendError::from(Error::MalformedProof("empty".to_owned())).code(),
+            malformed_proof.code()
+        );
+    }
+}
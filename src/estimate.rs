@@ -0,0 +1,169 @@
+use acvm::acir::circuit::Circuit;
+use acvm::acir::BlackBoxFunc;
+
+use crate::FIELD_BYTES;
+
+/// The number of group elements within an UltraPlonk proof.
+const PROOF_COMMITMENTS: usize = 13;
+
+/// The number of field elements within an UltraPlonk proof (excluding public inputs).
+const PROOF_EVALUATIONS: usize = 41;
+
+/// The size of an UltraPlonk proof excluding any public inputs.
+/// This is independent of the circuit as the proof consists of a fixed number of commitments and evaluations.
+pub(crate) const PROOF_BYTES: usize =
+    PROOF_COMMITMENTS * 2 * FIELD_BYTES + PROOF_EVALUATIONS * FIELD_BYTES;
+
+/// The base cost of any Ethereum transaction.
+const TRANSACTION_BASE_GAS: u64 = 21_000;
+
+/// The cost of a non-zero byte of calldata.
+const CALLDATA_BYTE_GAS: u64 = 16;
+
+/// Approximate cost of executing the verifier contract, excluding the work done per public input.
+/// This is dominated by the pairing check and the multi-scalar multiplication over the proof's commitments.
+const VERIFIER_EXECUTION_GAS: u64 = 390_000;
+
+/// Approximate cost of processing each public input within the verifier contract.
+const PUBLIC_INPUT_EXECUTION_GAS: u64 = 1_000;
+
+/// Returns the size in bytes of a proof for `circuit` as produced by barretenberg, i.e. with the circuit's
+/// public inputs prepended.
+///
+/// Proofs returned by [`ProofSystemCompiler::prove_with_pk`][acvm::ProofSystemCompiler::prove_with_pk] have
+/// their public inputs removed and so are always `estimate_proof_size(circuit) - 32 * num_public_inputs` bytes.
+pub fn estimate_proof_size(circuit: &Circuit) -> usize {
+    num_public_inputs(circuit) * FIELD_BYTES + PROOF_BYTES
+}
+
+/// Returns an upper bound on the gas consumed by a transaction which verifies a proof for `circuit`
+/// by calling `verify(bytes,bytes32[])` on the Solidity verifier generated from its verification key.
+///
+/// This assumes that every byte of calldata is non-zero so will tend to overestimate slightly.
+pub fn estimate_onchain_verification_gas(circuit: &Circuit) -> u64 {
+    let num_public_inputs = num_public_inputs(circuit);
+
+    // The calldata is made up of the function selector, the offsets of the two dynamic arguments,
+    // the length-prefixed proof and the length-prefixed array of public inputs.
+    let calldata_bytes = 4
+        + 2 * FIELD_BYTES
+        + FIELD_BYTES
+        + PROOF_BYTES
+        + FIELD_BYTES
+        + num_public_inputs * FIELD_BYTES;
+
+    TRANSACTION_BASE_GAS
+        + calldata_bytes as u64 * CALLDATA_BYTE_GAS
+        + VERIFIER_EXECUTION_GAS
+        + num_public_inputs as u64 * PUBLIC_INPUT_EXECUTION_GAS
+}
+
+/// The approximate number of UltraPlonk gates added to a circuit by a black box function call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GateEstimate {
+    pub gates: u64,
+}
+
+/// Returns the approximate number of gates which barretenberg's UltraPlonk composer uses to constrain a call to
+/// `func`, or `None` if the function isn't supported by this backend.
+///
+/// These are rough figures intended for comparing primitives (e.g. pedersen against SHA256), not for sizing
+/// circuits; [`ProofSystemCompiler::get_exact_circuit_size`][acvm::ProofSystemCompiler::get_exact_circuit_size]
+/// gives the exact size. The meaning of `input_size` depends upon the function:
+///
+/// - the number of input bytes for hash functions and `HashToField128Security`,
+/// - the number of field elements for `Pedersen`,
+/// - the number of bits for `RANGE`, `AND` and `XOR`,
+/// - the depth of the tree for `ComputeMerkleRoot`,
+/// - the length of the message in bytes for `SchnorrVerify`,
+/// - and is ignored for `EcdsaSecp256k1`, which takes a prehashed message, and `FixedBaseScalarMul`.
+pub fn black_box_cost(func: BlackBoxFunc, input_size: usize) -> Option<GateEstimate> {
+    let input_size = input_size as u64;
+    // Hashes pad their input, which adds a block whenever the input fills the last one.
+    let blocks = |block_bytes: u64| input_size / block_bytes + 1;
+    // Pedersen hashes field elements with lookups over 14-bit windows of each element.
+    let pedersen = |num_fields: u64| 10 + 36 * num_fields;
+    let blake2s = |num_bytes: u64| 2_900 * (num_bytes / 64 + 1);
+
+    let gates = match func {
+        BlackBoxFunc::RANGE => input_size / 14 + 1,
+        BlackBoxFunc::AND | BlackBoxFunc::XOR => input_size / 6 + 2,
+        BlackBoxFunc::SHA256 => 3_950 * blocks(64),
+        BlackBoxFunc::Blake2s => blake2s(input_size),
+        BlackBoxFunc::Keccak256 => 24_000 * blocks(136),
+        BlackBoxFunc::Pedersen => pedersen(input_size),
+        BlackBoxFunc::HashToField128Security => blake2s(input_size) + 10,
+        BlackBoxFunc::ComputeMerkleRoot => input_size * (pedersen(2) + 4),
+        BlackBoxFunc::SchnorrVerify => 26_000 + blake2s(input_size + FIELD_BYTES as u64),
+        BlackBoxFunc::EcdsaSecp256k1 => 41_000,
+        BlackBoxFunc::FixedBaseScalarMul => 2_300,
+        BlackBoxFunc::AES => return None,
+    };
+    Some(GateEstimate { gates })
+}
+
+fn num_public_inputs(circuit: &Circuit) -> usize {
+    circuit.public_inputs().indices().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acvm::acir::circuit::PublicInputs;
+    use acvm::acir::native_types::Witness;
+
+    use super::*;
+
+    fn circuit_with_public_inputs(num_public_inputs: u32) -> Circuit {
+        Circuit {
+            current_witness_index: num_public_inputs,
+            opcodes: Vec::new(),
+            public_parameters: PublicInputs(
+                (1..=num_public_inputs)
+                    .map(Witness)
+                    .collect::<BTreeSet<_>>(),
+            ),
+            return_values: PublicInputs::default(),
+        }
+    }
+
+    #[test]
+    fn proof_size_grows_with_public_inputs() {
+        assert_eq!(estimate_proof_size(&circuit_with_public_inputs(0)), 2144);
+        assert_eq!(
+            estimate_proof_size(&circuit_with_public_inputs(3)),
+            2144 + 3 * 32
+        );
+    }
+
+    #[test]
+    fn verification_gas_grows_with_public_inputs() {
+        let without_public_inputs =
+            estimate_onchain_verification_gas(&circuit_with_public_inputs(0));
+        let with_public_inputs = estimate_onchain_verification_gas(&circuit_with_public_inputs(2));
+
+        assert!(without_public_inputs > VERIFIER_EXECUTION_GAS);
+        assert_eq!(
+            with_public_inputs - without_public_inputs,
+            2 * (FIELD_BYTES as u64 * CALLDATA_BYTE_GAS + PUBLIC_INPUT_EXECUTION_GAS)
+        );
+    }
+
+    #[test]
+    fn black_box_costs_grow_with_input_size() {
+        for func in [
+            BlackBoxFunc::SHA256,
+            BlackBoxFunc::Blake2s,
+            BlackBoxFunc::Keccak256,
+            BlackBoxFunc::Pedersen,
+            BlackBoxFunc::ComputeMerkleRoot,
+        ] {
+            assert!(black_box_cost(func, 1) < black_box_cost(func, 1024));
+        }
+        assert!(
+            black_box_cost(BlackBoxFunc::Pedersen, 2) < black_box_cost(BlackBoxFunc::SHA256, 64)
+        );
+        assert_eq!(black_box_cost(BlackBoxFunc::AES, 16), None);
+    }
+}
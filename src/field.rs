@@ -0,0 +1,316 @@
+use acvm::FieldElement;
+
+use crate::{BackendError, Barretenberg, Error, FIELD_BYTES};
+
+/// Encodes a field element as 32 little-endian bytes.
+///
+/// This avoids the cost of hex encoding when passing large numbers of field elements to other languages.
+pub fn field_to_le_bytes(field: &FieldElement) -> [u8; FIELD_BYTES] {
+    let mut bytes: [u8; FIELD_BYTES] = field
+        .to_be_bytes()
+        .try_into()
+        .expect("field elements are encoded as 32 bytes");
+    bytes.reverse();
+    bytes
+}
+
+/// Decodes a field element from 32 little-endian bytes, as produced by [`field_to_le_bytes`].
+///
+/// The bytes must encode a value less than the field modulus. Values which would need to be reduced
+/// are rejected rather than silently wrapped around.
+pub fn field_from_le_bytes(bytes: &[u8; FIELD_BYTES]) -> Result<FieldElement, BackendError> {
+    let mut be_bytes = *bytes;
+    be_bytes.reverse();
+    Ok(field_from_be_bytes(&be_bytes)?)
+}
+
+/// Decodes a field element from big-endian bytes, rejecting values which aren't less than the field modulus.
+///
+/// Leading zero bytes are allowed, so `bytes` may be shorter or longer than [`FIELD_BYTES`].
+pub(crate) fn field_from_be_bytes(bytes: &[u8]) -> Result<FieldElement, Error> {
+    let strip_leading_zeros = |bytes: &[u8]| -> Vec<u8> {
+        bytes
+            .iter()
+            .copied()
+            .skip_while(|byte| *byte == 0)
+            .collect()
+    };
+
+    let field = FieldElement::from_be_bytes_reduce(bytes);
+    if strip_leading_zeros(&field.to_be_bytes()) != strip_leading_zeros(bytes) {
+        return Err(Error::NonCanonicalFieldElement);
+    }
+    Ok(field)
+}
+
+/// Decodes a string of hex digits, without a `0x` prefix, into big-endian bytes. An odd number of digits is
+/// treated as if it had a leading zero.
+///
+/// Returns `None` if `hex` is empty or contains anything other than hex digits.
+pub(crate) fn hex_to_bytes(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.bytes().all(|digit| digit.is_ascii_hexdigit()) {
+        return None;
+    }
+    let padded = if hex.len() % 2 == 0 {
+        hex.to_owned()
+    } else {
+        format!("0{hex}")
+    };
+    (0..padded.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&padded[i..i + 2], 16).ok())
+        .collect()
+}
+
+impl Barretenberg {
+    /// Returns the modulus of the field over which this backend's circuits are defined (BN254's scalar field)
+    /// as 32 big-endian bytes.
+    pub fn field_modulus(&self) -> [u8; FIELD_BYTES] {
+        let mut modulus: [u8; FIELD_BYTES] = (-FieldElement::one())
+            .to_be_bytes()
+            .try_into()
+            .expect("field elements are encoded as 32 bytes");
+        // The largest field element is one less than the modulus. The modulus is odd, so adding one never carries.
+        modulus[FIELD_BYTES - 1] += 1;
+        modulus
+    }
+
+    /// Checks that a circuit defined over the field with the big-endian `modulus` can be used with this backend.
+    ///
+    /// ACIR doesn't yet record the field of a circuit so callers which know it, e.g. from a compiler's artifact,
+    /// should check it before preprocessing the circuit.
+    pub fn check_field_modulus(&self, modulus: &[u8]) -> Result<(), BackendError> {
+        let strip_leading_zeros = |bytes: &[u8]| -> Vec<u8> {
+            bytes
+                .iter()
+                .copied()
+                .skip_while(|byte| *byte == 0)
+                .collect()
+        };
+        let expected = self.field_modulus();
+        if strip_leading_zeros(modulus) != strip_leading_zeros(&expected) {
+            return Err(Error::FieldMismatch {
+                expected: to_hex(&expected),
+                actual: to_hex(modulus),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// The order in which the bytes of each field element are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Most significant byte first, as expected by the EVM verifier.
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+/// How much space each field element takes up when encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldPadding {
+    /// Every element is padded to 32 bytes.
+    Padded,
+    /// Every element is written in the fewest bytes which hold its value (at least one), preceded by a single byte
+    /// holding that number of bytes so that the elements can be split apart again.
+    Packed,
+}
+
+/// Encodes public inputs for a verifier which expects the given byte order and padding.
+pub fn public_inputs_to_bytes(
+    public_inputs: &[FieldElement],
+    endianness: Endianness,
+    padding: FieldPadding,
+) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(public_inputs.len() * FIELD_BYTES);
+    for public_input in public_inputs {
+        let be_bytes = public_input.to_be_bytes();
+        let value_bytes = match padding {
+            FieldPadding::Padded => &be_bytes[..],
+            FieldPadding::Packed => {
+                let num_bytes = ((public_input.num_bits() as usize + 7) / 8).max(1);
+                bytes.push(num_bytes as u8);
+                &be_bytes[be_bytes.len() - num_bytes..]
+            }
+        };
+        match endianness {
+            Endianness::Big => bytes.extend_from_slice(value_bytes),
+            Endianness::Little => bytes.extend(value_bytes.iter().rev()),
+        }
+    }
+    bytes
+}
+
+/// Decodes public inputs written by [`public_inputs_to_bytes`] with the same byte order and padding.
+pub fn public_inputs_from_bytes(
+    bytes: &[u8],
+    endianness: Endianness,
+    padding: FieldPadding,
+) -> Result<Vec<FieldElement>, BackendError> {
+    let mut public_inputs = Vec::new();
+    let mut remaining = bytes;
+    while let Some((&length, rest)) = remaining.split_first() {
+        let num_bytes = match padding {
+            FieldPadding::Padded => FIELD_BYTES,
+            FieldPadding::Packed => {
+                remaining = rest;
+                length as usize
+            }
+        };
+        if num_bytes == 0 || num_bytes > FIELD_BYTES {
+            return Err(Error::MalformedCalldata(format!(
+                "public input {} has a width of {num_bytes} bytes",
+                public_inputs.len()
+            ))
+            .into());
+        }
+        if remaining.len() < num_bytes {
+            return Err(Error::MalformedCalldata(format!(
+                "public input {} is truncated",
+                public_inputs.len()
+            ))
+            .into());
+        }
+
+        let (value_bytes, rest) = remaining.split_at(num_bytes);
+        let mut be_bytes = value_bytes.to_vec();
+        if endianness == Endianness::Little {
+            be_bytes.reverse();
+        }
+        public_inputs.push(field_from_be_bytes(&be_bytes)?);
+        remaining = rest;
+    }
+    Ok(public_inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn le_bytes_round_trip() -> Result<(), BackendError> {
+        for field in [
+            FieldElement::zero(),
+            FieldElement::from(0x0102_u128),
+            -FieldElement::one(),
+        ] {
+            assert_eq!(field_from_le_bytes(&field_to_le_bytes(&field))?, field);
+        }
+
+        let mut expected = [0u8; FIELD_BYTES];
+        expected[..2].copy_from_slice(&[0x02, 0x01]);
+        assert_eq!(
+            field_to_le_bytes(&FieldElement::from(0x0102_u128)),
+            expected
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_non_canonical_bytes() {
+        // The modulus itself is the smallest value which isn't a canonical field element.
+        let mut modulus = field_to_le_bytes(&-FieldElement::one());
+        modulus[0] += 1;
+
+        assert!(field_from_le_bytes(&modulus).is_err());
+        assert!(field_from_le_bytes(&[0xff; FIELD_BYTES]).is_err());
+
+        modulus.reverse();
+        assert!(field_from_be_bytes(&modulus).is_err());
+        assert!(field_from_be_bytes(&[&[1][..], &[0; FIELD_BYTES]].concat()).is_err());
+        assert_eq!(
+            field_from_be_bytes(&[0, 0, 0x01, 0x02]).unwrap(),
+            FieldElement::from(0x0102_u128)
+        );
+    }
+
+    #[test]
+    fn decodes_hex() {
+        assert_eq!(hex_to_bytes("0102"), Some(vec![0x01, 0x02]));
+        assert_eq!(hex_to_bytes("abc"), Some(vec![0x0a, 0xbc]));
+        assert_eq!(hex_to_bytes(""), None);
+        assert_eq!(hex_to_bytes("0x01"), None);
+        assert_eq!(hex_to_bytes("+1"), None);
+    }
+
+    #[test]
+    fn checks_field_modulus() {
+        let bb = Barretenberg::new();
+        let modulus = bb.field_modulus();
+        assert_eq!(
+            to_hex(&modulus),
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+        );
+
+        assert!(bb.check_field_modulus(&modulus).is_ok());
+        assert!(bb
+            .check_field_modulus(&[&[0][..], &modulus].concat())
+            .is_ok());
+        // The Grumpkin scalar field is BN254's base field.
+        let base_field_modulus = "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
+        let base_field_modulus = hex_to_bytes(base_field_modulus).unwrap();
+        assert!(bb.check_field_modulus(&base_field_modulus).is_err());
+    }
+
+    #[test]
+    fn encodes_public_inputs_in_each_layout() {
+        let public_inputs = [FieldElement::from(0x0102_u128), FieldElement::zero()];
+
+        let padded = public_inputs_to_bytes(&public_inputs, Endianness::Big, FieldPadding::Padded);
+        assert_eq!(padded.len(), 2 * FIELD_BYTES);
+        assert_eq!(&padded[FIELD_BYTES - 2..FIELD_BYTES], &[0x01, 0x02]);
+
+        let padded_le =
+            public_inputs_to_bytes(&public_inputs, Endianness::Little, FieldPadding::Padded);
+        assert_eq!(
+            &padded_le[..FIELD_BYTES],
+            &field_to_le_bytes(&public_inputs[0])
+        );
+
+        assert_eq!(
+            public_inputs_to_bytes(&public_inputs, Endianness::Big, FieldPadding::Packed),
+            vec![2, 0x01, 0x02, 1, 0x00]
+        );
+        assert_eq!(
+            public_inputs_to_bytes(&public_inputs, Endianness::Little, FieldPadding::Packed),
+            vec![2, 0x02, 0x01, 1, 0x00]
+        );
+    }
+
+    #[test]
+    fn public_inputs_round_trip_in_each_layout() -> Result<(), BackendError> {
+        // Values of different widths would be ambiguous without the length of each packed value.
+        let public_inputs = [
+            FieldElement::from(0x0102_u128),
+            FieldElement::zero(),
+            -FieldElement::one(),
+            FieldElement::from(0x01_u128),
+        ];
+
+        for endianness in [Endianness::Big, Endianness::Little] {
+            for padding in [FieldPadding::Padded, FieldPadding::Packed] {
+                let bytes = public_inputs_to_bytes(&public_inputs, endianness, padding);
+                assert_eq!(
+                    public_inputs_from_bytes(&bytes, endianness, padding)?,
+                    public_inputs
+                );
+                assert!(
+                    public_inputs_from_bytes(&bytes[..bytes.len() - 1], endianness, padding)
+                        .is_err()
+                );
+            }
+        }
+
+        assert!(public_inputs_from_bytes(&[0], Endianness::Big, FieldPadding::Packed).is_err());
+        assert!(
+            public_inputs_from_bytes(&[33; 34], Endianness::Big, FieldPadding::Packed).is_err()
+        );
+        Ok(())
+    }
+}
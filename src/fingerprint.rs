@@ -0,0 +1,143 @@
+use acvm::acir::circuit::Circuit;
+use blake2::{Blake2s, Digest};
+
+use crate::{BackendError, VerificationKey};
+
+// Domain separators ensure that a circuit and a verification key can never share a fingerprint,
+// even if their serialized bytes happen to coincide.
+const CIRCUIT_DOMAIN: &[u8] = b"acvm-backend-barretenberg/circuit";
+const VERIFICATION_KEY_DOMAIN: &[u8] = b"acvm-backend-barretenberg/vk";
+
+/// Computes a fingerprint which uniquely identifies a circuit.
+///
+/// The fingerprint only depends upon the structure of the circuit so it is independent of any witness
+/// and is stable across repeated compilations of the same program.
+pub fn circuit_fingerprint(circuit: &Circuit) -> [u8; 32] {
+    let mut circuit_bytes = Vec::new();
+    circuit
+        .write(&mut circuit_bytes)
+        .expect("could not serialize circuit");
+
+    fingerprint(CIRCUIT_DOMAIN, &circuit_bytes)
+}
+
+/// Computes a fingerprint which uniquely identifies a verification key.
+///
+/// The fingerprint is taken over the key's canonical, uncompressed encoding, so the same key always has the same
+/// fingerprint however its commitments happened to be ordered or encoded in the bytes it was parsed from.
+pub fn vk_fingerprint(verification_key: &VerificationKey) -> [u8; 32] {
+    fingerprint(VERIFICATION_KEY_DOMAIN, &verification_key.to_bytes())
+}
+
+/// Computes the fingerprint of a serialized verification key, such as one returned by
+/// [`ProofSystemCompiler::preprocess`][acvm::ProofSystemCompiler::preprocess].
+///
+/// The key is parsed so that its fingerprint matches [`vk_fingerprint`] whichever encoding it was serialized with.
+/// Bytes which aren't a valid verification key are rejected.
+pub fn vk_fingerprint_from_bytes(verification_key: &[u8]) -> Result<[u8; 32], BackendError> {
+    Ok(vk_fingerprint(&VerificationKey::from_bytes(
+        verification_key,
+    )?))
+}
+
+fn fingerprint(domain: &[u8], bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = <Blake2s as Digest>::new();
+    hasher.update(domain);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use acvm::acir::circuit::{Opcode, PublicInputs};
+    use acvm::acir::native_types::{Expression, Witness};
+    use acvm::FieldElement;
+
+    use super::*;
+    use crate::Commitment;
+
+    fn circuit_with_constant(constant: FieldElement) -> Circuit {
+        Circuit {
+            current_witness_index: 1,
+            opcodes: vec![Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(FieldElement::one(), Witness(1))],
+                q_c: constant,
+            })],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        }
+    }
+
+    #[test]
+    fn circuit_fingerprint_is_deterministic() {
+        let circuit = circuit_with_constant(FieldElement::one());
+
+        assert_eq!(
+            circuit_fingerprint(&circuit),
+            circuit_fingerprint(&circuit.clone())
+        );
+        assert_ne!(
+            circuit_fingerprint(&circuit),
+            circuit_fingerprint(&circuit_with_constant(FieldElement::zero()))
+        );
+    }
+
+    #[test]
+    fn fingerprints_are_domain_separated() {
+        let circuit = circuit_with_constant(FieldElement::one());
+        let mut circuit_bytes = Vec::new();
+        circuit.write(&mut circuit_bytes).unwrap();
+
+        assert_ne!(
+            circuit_fingerprint(&circuit),
+            fingerprint(VERIFICATION_KEY_DOMAIN, &circuit_bytes)
+        );
+    }
+
+    #[test]
+    fn vk_fingerprint_is_independent_of_encoding() -> Result<(), BackendError> {
+        let commitment = |byte: u8| Commitment {
+            x: [byte; 32],
+            y: [byte + 1; 32],
+        };
+        let verification_key = VerificationKey {
+            composer_type: 2,
+            circuit_size: 16,
+            num_public_inputs: 1,
+            commitments: BTreeMap::from([
+                ("Q_1".to_string(), commitment(1)),
+                ("Q_2".to_string(), commitment(3)),
+            ]),
+            contains_recursive_proof: false,
+            recursive_proof_public_input_indices: Vec::new(),
+        };
+        let canonical_bytes = verification_key.to_bytes();
+
+        // The same key with its commitments written in the opposite order
+        let mut reordered_bytes = Vec::new();
+        reordered_bytes.extend_from_slice(&canonical_bytes[..16]);
+        for (label, commitment) in verification_key.commitments.iter().rev() {
+            reordered_bytes.extend_from_slice(&(label.len() as u32).to_be_bytes());
+            reordered_bytes.extend_from_slice(label.as_bytes());
+            reordered_bytes.extend_from_slice(&commitment.x);
+            reordered_bytes.extend_from_slice(&commitment.y);
+        }
+        reordered_bytes.extend_from_slice(&[0, 0, 0, 0, 0]);
+        assert_eq!(reordered_bytes.len(), canonical_bytes.len());
+        assert_ne!(reordered_bytes, canonical_bytes);
+
+        assert_eq!(
+            vk_fingerprint(&VerificationKey::from_bytes(&reordered_bytes)?),
+            vk_fingerprint(&verification_key)
+        );
+        assert_eq!(
+            vk_fingerprint_from_bytes(&reordered_bytes)?,
+            vk_fingerprint(&verification_key)
+        );
+        assert!(vk_fingerprint_from_bytes(&canonical_bytes[..20]).is_err());
+        Ok(())
+    }
+}
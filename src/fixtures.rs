@@ -0,0 +1,357 @@
+//! Small canonical circuits with golden witnesses, verification keys and proofs, for checking that an embedding
+//! of the backend behaves the same on every platform it's built for.
+//!
+//! Each fixture is stored in a directory named after it, holding the serialized circuit (`circuit.acir`), its
+//! solved witness as CBOR (`witness.cbor`), its verification key (`vk.bin`) and a proof (`proof.bin`). The golden
+//! copies live in `fixtures` at the root of this crate and are regenerated with
+//! `cargo test regenerate_golden_fixtures -- --ignored` whenever barretenberg's key or proof format changes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+use acvm::acir::native_types::Witness;
+use acvm::acir::BlackBoxFunc;
+use acvm::pwg::block::Blocks;
+use acvm::{
+    FieldElement, PartialWitnessGenerator, PartialWitnessGeneratorStatus, ProofSystemCompiler,
+};
+
+use crate::fingerprint::circuit_fingerprint;
+use crate::schnorr::SchnorrSig;
+use crate::test_circuits::merkle_membership;
+use crate::{
+    extract_public_inputs, witness_map_from_cbor, witness_map_to_cbor, BackendError, Barretenberg,
+    Error, SecretScalar,
+};
+
+/// A circuit verifying a schnorr signature over a 32 byte message.
+pub const SCHNORR_VERIFY: &str = "schnorr_verify";
+/// A circuit computing the SHA256 digest of a 64 byte message.
+pub const SHA256_64_BYTES: &str = "sha256_64_bytes";
+/// A circuit computing the root of a merkle tree of depth 4 from a leaf and its hash path.
+pub const MERKLE_MEMBERSHIP_DEPTH_4: &str = "merkle_membership_depth_4";
+
+/// The names of every fixture.
+pub const FIXTURE_NAMES: [&str; 3] = [SCHNORR_VERIFY, SHA256_64_BYTES, MERKLE_MEMBERSHIP_DEPTH_4];
+
+/// The directory holding the golden copy of each fixture.
+const GOLDEN_FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures");
+
+/// The private key which signs the message in [`SCHNORR_VERIFY`].
+const SCHNORR_PRIVATE_KEY: [u8; 32] = [1; 32];
+
+/// A fixture circuit along with the artifacts produced for it.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub name: String,
+    pub circuit: Circuit,
+    /// The solved witness, from which the circuit's inputs are taken when the fixture is verified.
+    pub witness: BTreeMap<Witness, FieldElement>,
+    pub verification_key: Vec<u8>,
+    pub proof: Vec<u8>,
+}
+
+impl Fixture {
+    /// Reads the fixture called `name` from a subdirectory of `dir`.
+    pub fn read(dir: impl AsRef<Path>, name: &str) -> Result<Fixture, BackendError> {
+        let fixture_dir = dir.as_ref().join(name);
+        let read = |file: &str| {
+            fs::read(fixture_dir.join(file)).map_err(|err| Error::FixtureUnavailable {
+                name: name.to_owned(),
+                reason: format!("could not read {file} ({err})"),
+            })
+        };
+
+        let circuit = Circuit::read(&*read("circuit.acir")?)
+            .map_err(|err| Error::MalformedCircuit(err.to_string()))?;
+        Ok(Fixture {
+            name: name.to_owned(),
+            circuit,
+            witness: witness_map_from_cbor(&read("witness.cbor")?)?,
+            verification_key: read("vk.bin")?,
+            proof: read("proof.bin")?,
+        })
+    }
+
+    /// Writes the fixture to a subdirectory of `dir` named after it, replacing any fixture already there.
+    pub fn write(&self, dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let fixture_dir = dir.as_ref().join(&self.name);
+        fs::create_dir_all(&fixture_dir)?;
+
+        let mut circuit = Vec::new();
+        self.circuit.write(&mut circuit)?;
+        fs::write(fixture_dir.join("circuit.acir"), circuit)?;
+        fs::write(
+            fixture_dir.join("witness.cbor"),
+            witness_map_to_cbor(&self.witness),
+        )?;
+        fs::write(fixture_dir.join("vk.bin"), &self.verification_key)?;
+        fs::write(fixture_dir.join("proof.bin"), &self.proof)
+    }
+}
+
+impl Barretenberg {
+    /// Solves, preprocesses and proves the fixture called `name`, producing a fresh set of its artifacts.
+    pub fn generate_fixture(&self, name: &str) -> Result<Fixture, BackendError> {
+        let (circuit, mut witness) = match name {
+            SCHNORR_VERIFY => self.schnorr_verify_fixture()?,
+            SHA256_64_BYTES => sha256_64_bytes_fixture(),
+            MERKLE_MEMBERSHIP_DEPTH_4 => {
+                let test_circuit = merkle_membership(4);
+                (test_circuit.circuit, test_circuit.initial_witness)
+            }
+            _ => return Err(unknown_fixture(name).into()),
+        };
+
+        self.solve_fixture(&circuit, &mut witness)
+            .map_err(|reason| Error::FixtureUnavailable {
+                name: name.to_owned(),
+                reason,
+            })?;
+        let (proving_key, verification_key) = self.preprocess(&circuit)?;
+        let proof = self.prove_with_pk(&circuit, witness.clone(), &proving_key)?;
+
+        Ok(Fixture {
+            name: name.to_owned(),
+            circuit,
+            witness,
+            verification_key,
+            proof,
+        })
+    }
+
+    /// Checks the fixture called `name` against the golden copy shipped with this crate.
+    ///
+    /// See [`verify_fixture_in`][Barretenberg::verify_fixture_in] for what is checked.
+    pub fn verify_fixture(&self, name: &str) -> Result<(), BackendError> {
+        self.verify_fixture_in(GOLDEN_FIXTURES_DIR, name)
+    }
+
+    /// Checks the fixture called `name` against the copy in a subdirectory of `dir`.
+    ///
+    /// The fixture's circuit must match the one this crate builds, solving it from its inputs must reproduce
+    /// the stored witness, preprocessing it must reproduce the stored verification key, and both the stored
+    /// proof and a freshly created proof must verify.
+    pub fn verify_fixture_in(&self, dir: impl AsRef<Path>, name: &str) -> Result<(), BackendError> {
+        let golden = Fixture::read(dir, name)?;
+        let mismatch = |reason: String| Error::FixtureMismatch {
+            name: name.to_owned(),
+            reason,
+        };
+
+        let expected_circuit = fixture_circuit(name).ok_or_else(|| unknown_fixture(name))?;
+        if circuit_fingerprint(&golden.circuit) != circuit_fingerprint(&expected_circuit) {
+            return Err(mismatch(
+                "the circuit differs from the one built by this crate".to_owned(),
+            )
+            .into());
+        }
+
+        let outputs = black_box_outputs(&golden.circuit);
+        let mut witness: BTreeMap<Witness, FieldElement> = golden
+            .witness
+            .iter()
+            .filter(|(witness, _)| !outputs.contains(witness))
+            .map(|(witness, value)| (*witness, *value))
+            .collect();
+        self.solve_fixture(&golden.circuit, &mut witness)
+            .map_err(mismatch)?;
+        if witness != golden.witness {
+            return Err(
+                mismatch("solving the circuit produced a different witness".to_owned()).into(),
+            );
+        }
+
+        let (proving_key, verification_key) = self.preprocess(&golden.circuit)?;
+        if verification_key != golden.verification_key {
+            return Err(
+                mismatch("preprocessing produced a different verification key".to_owned()).into(),
+            );
+        }
+
+        let public_inputs = extract_public_inputs(&golden.circuit, &golden.witness)?;
+        if !self.verify_with_vk(
+            &golden.proof,
+            public_inputs.clone(),
+            &golden.circuit,
+            &golden.verification_key,
+        )? {
+            return Err(mismatch("the golden proof does not verify".to_owned()).into());
+        }
+        let proof = self.prove_with_pk(&golden.circuit, witness, &proving_key)?;
+        if !self.verify_with_vk(&proof, public_inputs, &golden.circuit, &verification_key)? {
+            return Err(mismatch("a freshly created proof does not verify".to_owned()).into());
+        }
+        Ok(())
+    }
+
+    fn solve_fixture(
+        &self,
+        circuit: &Circuit,
+        witness: &mut BTreeMap<Witness, FieldElement>,
+    ) -> Result<(), String> {
+        match self.solve(witness, &mut Blocks::default(), circuit.opcodes.clone()) {
+            Ok(PartialWitnessGeneratorStatus::Solved) => Ok(()),
+            Ok(_) => Err("the circuit could not be fully solved".to_owned()),
+            Err(err) => Err(format!("the circuit could not be solved ({err})")),
+        }
+    }
+
+    /// Returns the [`SCHNORR_VERIFY`] circuit along with a signature over its message.
+    fn schnorr_verify_fixture(
+        &self,
+    ) -> Result<(Circuit, BTreeMap<Witness, FieldElement>), BackendError> {
+        let message: Vec<u8> = (0..32).collect();
+        let private_key = SecretScalar::from_bytes(SCHNORR_PRIVATE_KEY);
+        let public_key = self.construct_public_key(&private_key)?;
+        let (sig_s, sig_e) = self.construct_signature(&message, &private_key)?;
+
+        let values = [
+            FieldElement::from_be_bytes_reduce(&public_key[..32]),
+            FieldElement::from_be_bytes_reduce(&public_key[32..]),
+        ]
+        .into_iter()
+        .chain(
+            sig_s
+                .iter()
+                .chain(&sig_e)
+                .chain(&message)
+                .map(|byte| FieldElement::from(*byte as u128)),
+        );
+        let witness = (1..).map(Witness).zip(values).collect();
+
+        Ok((schnorr_verify_circuit(), witness))
+    }
+}
+
+/// Returns the circuit of the fixture called `name`.
+fn fixture_circuit(name: &str) -> Option<Circuit> {
+    match name {
+        SCHNORR_VERIFY => Some(schnorr_verify_circuit()),
+        SHA256_64_BYTES => Some(sha256_64_bytes_fixture().0),
+        MERKLE_MEMBERSHIP_DEPTH_4 => Some(merkle_membership(4).circuit),
+        _ => None,
+    }
+}
+
+/// Returns a circuit taking a public key, the signature's `s` and `e` and a 32 byte message, in that order.
+fn schnorr_verify_circuit() -> Circuit {
+    const NUM_INPUTS: u32 = 2 + 32 + 32 + 32;
+
+    let inputs = (1..=NUM_INPUTS)
+        .map(|index| FunctionInput {
+            witness: Witness(index),
+            num_bits: if index <= 2 {
+                FieldElement::max_num_bits()
+            } else {
+                8
+            },
+        })
+        .collect();
+    let result = Witness(NUM_INPUTS + 1);
+
+    Circuit {
+        current_witness_index: result.witness_index(),
+        opcodes: vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::SchnorrVerify,
+            inputs,
+            outputs: vec![result],
+        })],
+        public_parameters: PublicInputs::default(),
+        return_values: PublicInputs(BTreeSet::from([result])),
+    }
+}
+
+/// Returns the [`SHA256_64_BYTES`] circuit along with its message.
+fn sha256_64_bytes_fixture() -> (Circuit, BTreeMap<Witness, FieldElement>) {
+    const MESSAGE_BYTES: u32 = 64;
+    const DIGEST_BYTES: u32 = 32;
+
+    let message = (1..=MESSAGE_BYTES).map(Witness);
+    let digest: Vec<Witness> = (MESSAGE_BYTES + 1..=MESSAGE_BYTES + DIGEST_BYTES)
+        .map(Witness)
+        .collect();
+
+    let circuit = Circuit {
+        current_witness_index: MESSAGE_BYTES + DIGEST_BYTES,
+        opcodes: vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::SHA256,
+            inputs: message
+                .clone()
+                .map(|witness| FunctionInput {
+                    witness,
+                    num_bits: 8,
+                })
+                .collect(),
+            outputs: digest.clone(),
+        })],
+        public_parameters: PublicInputs::default(),
+        return_values: PublicInputs(digest.into_iter().collect()),
+    };
+    let witness = message
+        .zip(0_u128..)
+        .map(|(witness, byte)| (witness, FieldElement::from(byte)))
+        .collect();
+    (circuit, witness)
+}
+
+fn unknown_fixture(name: &str) -> Error {
+    Error::FixtureUnavailable {
+        name: name.to_owned(),
+        reason: "there is no fixture with this name".to_owned(),
+    }
+}
+
+/// Returns the witnesses written by the circuit's black box function calls, which are the only witnesses of the
+/// fixtures which aren't inputs.
+fn black_box_outputs(circuit: &Circuit) -> BTreeSet<Witness> {
+    circuit
+        .opcodes
+        .iter()
+        .filter_map(|opcode| match opcode {
+            Opcode::BlackBoxFuncCall(func_call) => Some(func_call.outputs.iter().copied()),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn generated_fixtures_verify() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let dir = tempdir().unwrap();
+
+        for name in FIXTURE_NAMES {
+            bb.generate_fixture(name)?.write(&dir).unwrap();
+            bb.verify_fixture_in(&dir, name)?;
+        }
+
+        let mut tampered = Fixture::read(&dir, SHA256_64_BYTES)?;
+        let digest_byte = tampered.witness.values_mut().last().unwrap();
+        *digest_byte = *digest_byte + FieldElement::one();
+        tampered.write(&dir).unwrap();
+        assert!(bb.verify_fixture_in(&dir, SHA256_64_BYTES).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "overwrites the golden fixtures"]
+    fn regenerate_golden_fixtures() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        for name in FIXTURE_NAMES {
+            bb.generate_fixture(name)?
+                .write(GOLDEN_FIXTURES_DIR)
+                .unwrap();
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! The header written at the start of every file produced by this crate.
+//!
+//! Each header is made up of four magic bytes identifying what the file holds, followed by the version of its
+//! layout as a big-endian `u32`. Loading a file of the wrong kind, or one written by an incompatible version of
+//! this crate, therefore fails before any of its contents are read.
+
+/// The number of bytes in a header.
+pub(crate) const HEADER_BYTES: usize = 8;
+
+/// The magic bytes and layout version identifying one kind of file.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Framing {
+    magic: &'static [u8; 4],
+    /// This must be incremented whenever the layout of the content following the header changes.
+    version: u32,
+    name: &'static str,
+}
+
+pub(crate) const PROVING_KEY: Framing = Framing {
+    magic: b"BBPK",
+    version: 1,
+    name: "proving key",
+};
+
+pub(crate) const VERIFICATION_KEY: Framing = Framing {
+    magic: b"BBVK",
+    version: 1,
+    name: "verification key",
+};
+
+pub(crate) const PROOF: Framing = Framing {
+    magic: b"BBPF",
+    version: 1,
+    name: "proof",
+};
+
+pub(crate) const BUNDLE: Framing = Framing {
+    magic: b"BBKG",
+    version: 1,
+    name: "bundle",
+};
+
+#[cfg(feature = "encrypted-keys")]
+pub(crate) const ENCRYPTED_PROVING_KEY: Framing = Framing {
+    magic: b"BBEK",
+    version: 1,
+    name: "encrypted key",
+};
+
+pub(crate) const CONSTRAINT_SYSTEM: Framing = Framing {
+    magic: b"BBCS",
+    version: 1,
+    name: "constraint system",
+};
+
+#[cfg(feature = "sealed-witness")]
+pub(crate) const SEALED_WITNESS: Framing = Framing {
+    magic: b"BBSW",
+    version: 1,
+    name: "sealed witness map",
+};
+
+impl Framing {
+    pub(crate) fn header(&self) -> [u8; HEADER_BYTES] {
+        let mut header = [0; HEADER_BYTES];
+        header[..4].copy_from_slice(self.magic);
+        header[4..].copy_from_slice(&self.version.to_be_bytes());
+        header
+    }
+
+    /// Returns `contents` prefixed by the header.
+    pub(crate) fn frame(&self, contents: &[u8]) -> Vec<u8> {
+        let mut framed = Vec::with_capacity(HEADER_BYTES + contents.len());
+        framed.extend_from_slice(&self.header());
+        framed.extend_from_slice(contents);
+        framed
+    }
+
+    /// Returns `true` if `bytes` start with this kind of header, regardless of its version.
+    pub(crate) fn is_framed(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= HEADER_BYTES && bytes.starts_with(self.magic)
+    }
+
+    /// Checks that `header` is this kind of header with the current version, returning the reason if it isn't.
+    pub(crate) fn check_header(&self, header: &[u8]) -> Result<(), String> {
+        if !self.is_framed(header) {
+            return Err(format!(
+                "expected a {} starting with {:?}",
+                self.name,
+                String::from_utf8_lossy(self.magic)
+            ));
+        }
+        let version = u32::from_be_bytes(
+            header[4..HEADER_BYTES]
+                .try_into()
+                .expect("slice is 4 bytes"),
+        );
+        if version != self.version {
+            return Err(format!(
+                "unsupported {} version {version}, expected {}",
+                self.name, self.version
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the contents following the header of `framed`, as written by [`Framing::frame`].
+    pub(crate) fn unframe<'a>(&self, framed: &'a [u8]) -> Result<&'a [u8], String> {
+        self.check_header(framed)?;
+        Ok(&framed[HEADER_BYTES..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_other_kinds_and_versions() {
+        let framed = PROVING_KEY.frame(&[1, 2, 3]);
+
+        assert_eq!(framed[..4], *b"BBPK");
+        assert_eq!(PROVING_KEY.unframe(&framed), Ok(&[1, 2, 3][..]));
+        assert!(VERIFICATION_KEY.unframe(&framed).is_err());
+        assert!(PROVING_KEY.unframe(&framed[..HEADER_BYTES - 1]).is_err());
+
+        let mut wrong_version = framed;
+        wrong_version[7] = 2;
+        assert!(PROVING_KEY.is_framed(&wrong_version));
+        assert!(PROVING_KEY.unframe(&wrong_version).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::{Circuit, Opcode};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::FieldElement;
+
+use crate::acvm_interop::referenced_witnesses;
+
+/// A summary of the arithmetic gates removed by [`pack_arithmetic_gates`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GatePackingReport {
+    /// The number of arithmetic gates in the original circuit.
+    pub gates_before: usize,
+    /// The number of arithmetic gates in the packed circuit.
+    pub gates_after: usize,
+    /// The number of gates removed as they were a scalar multiple of an earlier gate.
+    pub duplicates_removed: usize,
+    /// The number of gates removed as every coefficient was zero.
+    pub trivial_removed: usize,
+    /// The number of gates removed by merging them into the gate which follows them.
+    pub gates_merged: usize,
+}
+
+impl GatePackingReport {
+    /// Returns the number of arithmetic gates which were removed from the circuit.
+    pub fn gates_saved(&self) -> usize {
+        self.gates_before - self.gates_after
+    }
+}
+
+/// Removes arithmetic gates which don't constrain the circuit any further than the rest of its gates, and merges
+/// adjacent gates which share an intermediate witness.
+///
+/// Gates are combined when they are equivalent after like terms are merged and coefficients are scaled
+/// (e.g. `2*w1 - 2*w2 = 0` and `w2 - w1 = 0`), keeping the first occurrence. Gates which reduce to `0 = 0` are
+/// dropped entirely.
+///
+/// A gate is then merged into the gate which immediately follows it when both are linear in a witness which no
+/// other opcode references and which isn't a public input, e.g. `w1*w2 - w3 = 0` and `w3 + w1 - w4 = 0` become
+/// `w1*w2 + w1 - w4 = 0`. The shared witness is eliminated and is no longer assigned by the solver. Gates are only
+/// merged when the result still fits in a single width 3 gate, and only when the shared witness has a higher index
+/// than the other witnesses of the first gate, as it does when a compiler allocates it for the gate's output.
+/// Circuits containing opcodes whose witnesses can't be determined, such as directives, aren't merged.
+///
+/// All other opcodes are left untouched and in their original order.
+///
+/// Packing is optional: it changes the circuit and so must be applied consistently before generating keys
+/// and proving.
+pub fn pack_arithmetic_gates(circuit: &Circuit) -> (Circuit, GatePackingReport) {
+    let mut report = GatePackingReport::default();
+    let mut seen_gates: BTreeSet<Vec<u8>> = BTreeSet::new();
+
+    let opcodes = circuit
+        .opcodes
+        .iter()
+        .filter(|opcode| {
+            let Opcode::Arithmetic(expr) = opcode else {
+                return true;
+            };
+            report.gates_before += 1;
+
+            let Some(normalized) = normalize(expr) else {
+                report.trivial_removed += 1;
+                return false;
+            };
+            if !seen_gates.insert(encode(&normalized)) {
+                report.duplicates_removed += 1;
+                return false;
+            }
+
+            report.gates_after += 1;
+            true
+        })
+        .cloned()
+        .collect();
+    let opcodes = merge_adjacent_gates(circuit, opcodes, &mut report);
+
+    let packed = Circuit {
+        opcodes,
+        ..circuit.clone()
+    };
+    (packed, report)
+}
+
+/// Returns a canonical form of `expr` such that two expressions have the same canonical form
+/// if and only if they are non-zero scalar multiples of each other.
+///
+/// Returns `None` if `expr` is trivially satisfied.
+fn normalize(expr: &Expression) -> Option<Expression> {
+    let (mul_terms, linear_combinations) = collect_terms(expr);
+
+    let leading_coefficient = mul_terms
+        .values()
+        .chain(linear_combinations.values())
+        .chain(std::iter::once(&expr.q_c))
+        .find(|scale| !scale.is_zero())?;
+    let inverse = leading_coefficient.inverse();
+
+    Some(Expression {
+        mul_terms: mul_terms
+            .into_iter()
+            .map(|((w_l, w_r), scale)| (scale * inverse, w_l, w_r))
+            .collect(),
+        linear_combinations: linear_combinations
+            .into_iter()
+            .map(|(witness, scale)| (scale * inverse, witness))
+            .collect(),
+        q_c: expr.q_c * inverse,
+    })
+}
+
+fn merge_adjacent_gates(
+    circuit: &Circuit,
+    opcodes: Vec<Opcode>,
+    report: &mut GatePackingReport,
+) -> Vec<Opcode> {
+    let mut references: BTreeMap<Witness, usize> = BTreeMap::new();
+    for opcode in &opcodes {
+        let Some(witnesses) = referenced_witnesses(opcode) else {
+            return opcodes;
+        };
+        for witness in witnesses {
+            *references.entry(witness).or_default() += 1;
+        }
+    }
+    let public_inputs: BTreeSet<Witness> = circuit
+        .public_inputs()
+        .indices()
+        .into_iter()
+        .map(Witness)
+        .collect();
+
+    let mut merged_opcodes = Vec::with_capacity(opcodes.len());
+    let mut pending_gate: Option<Expression> = None;
+    for opcode in opcodes {
+        let Opcode::Arithmetic(gate) = opcode else {
+            merged_opcodes.extend(pending_gate.take().map(Opcode::Arithmetic));
+            merged_opcodes.push(opcode);
+            continue;
+        };
+        let Some(previous_gate) = pending_gate.take() else {
+            pending_gate = Some(gate);
+            continue;
+        };
+
+        let eliminable = |witness: &Witness| {
+            references.get(witness) == Some(&2) && !public_inputs.contains(witness)
+        };
+        match merge_gates(&previous_gate, &gate, eliminable) {
+            Some(merged_gate) => {
+                report.gates_merged += 1;
+                report.gates_after -= 1;
+                // Witnesses shared by both gates are now only referenced by the merged gate.
+                let gate_witnesses = referenced_witnesses(&Opcode::Arithmetic(gate.clone()));
+                for witness in referenced_witnesses(&Opcode::Arithmetic(previous_gate))
+                    .into_iter()
+                    .flatten()
+                    .filter(|witness| gate_witnesses.iter().flatten().any(|w| w == witness))
+                {
+                    *references.entry(witness).or_default() -= 1;
+                }
+                pending_gate = Some(merged_gate);
+            }
+            None => {
+                merged_opcodes.push(Opcode::Arithmetic(previous_gate));
+                pending_gate = Some(gate);
+            }
+        }
+    }
+    merged_opcodes.extend(pending_gate.map(Opcode::Arithmetic));
+    merged_opcodes
+}
+
+/// Eliminates a witness shared by `first` and `second` for which `eliminable` returns `true`, returning a single
+/// gate which is satisfied exactly when both gates are satisfied for some value of that witness.
+fn merge_gates(
+    first: &Expression,
+    second: &Expression,
+    eliminable: impl Fn(&Witness) -> bool,
+) -> Option<Expression> {
+    let (first_mul_terms, first_linear_terms) = collect_terms(first);
+    let (second_mul_terms, second_linear_terms) = collect_terms(second);
+
+    let in_mul_terms = |mul_terms: &BTreeMap<(Witness, Witness), FieldElement>,
+                        witness: &Witness| {
+        mul_terms
+            .keys()
+            .any(|(w_l, w_r)| w_l == witness || w_r == witness)
+    };
+    let first_witnesses = referenced_witnesses(&Opcode::Arithmetic(first.clone()))?;
+    let shared_witness = first_linear_terms.keys().copied().find(|witness| {
+        second_linear_terms.contains_key(witness)
+            && !in_mul_terms(&first_mul_terms, witness)
+            && !in_mul_terms(&second_mul_terms, witness)
+            && eliminable(witness)
+            && first_witnesses.iter().all(|other| other <= witness)
+    })?;
+
+    // second - (c2 / c1) * first cancels the shared witness.
+    let scale =
+        -(second_linear_terms[&shared_witness] * first_linear_terms[&shared_witness].inverse());
+    let mut mul_terms = second_mul_terms;
+    for (key, coefficient) in first_mul_terms {
+        let sum = mul_terms.entry(key).or_insert_with(FieldElement::zero);
+        *sum = *sum + coefficient * scale;
+    }
+    let mut linear_terms = second_linear_terms;
+    for (witness, coefficient) in first_linear_terms {
+        let sum = linear_terms
+            .entry(witness)
+            .or_insert_with(FieldElement::zero);
+        *sum = *sum + coefficient * scale;
+    }
+    mul_terms.retain(|_, coefficient| !coefficient.is_zero());
+    linear_terms.retain(|_, coefficient| !coefficient.is_zero());
+    debug_assert!(!linear_terms.contains_key(&shared_witness));
+
+    let merged = Expression {
+        mul_terms: mul_terms
+            .into_iter()
+            .map(|((w_l, w_r), coefficient)| (coefficient, w_l, w_r))
+            .collect(),
+        linear_combinations: linear_terms
+            .into_iter()
+            .map(|(witness, coefficient)| (coefficient, witness))
+            .collect(),
+        q_c: second.q_c + first.q_c * scale,
+    };
+    fits_in_one_gate(&merged).then_some(merged)
+}
+
+/// Returns `true` if `expr` can be written as a single barretenberg gate, `qm*a*b + ql*a + qr*b + qo*c + qc`.
+fn fits_in_one_gate(expr: &Expression) -> bool {
+    match expr.mul_terms.as_slice() {
+        [] => expr.linear_combinations.len() <= 3,
+        [(_, w_l, w_r)] => {
+            // The multiplication occupies the `a` and `b` wires, leaving only `c` for any other witness.
+            expr.linear_combinations
+                .iter()
+                .filter(|(_, witness)| witness != w_l && witness != w_r)
+                .count()
+                <= 1
+        }
+        _ => false,
+    }
+}
+
+/// Sums the coefficients of like terms in `expr`, dropping any terms which cancel out.
+///
+/// Zero coefficients must be dropped as [`merge_gates`] divides by the coefficient of the witness it eliminates.
+#[allow(clippy::type_complexity)]
+fn collect_terms(
+    expr: &Expression,
+) -> (
+    BTreeMap<(Witness, Witness), FieldElement>,
+    BTreeMap<Witness, FieldElement>,
+) {
+    let mut mul_terms: BTreeMap<(Witness, Witness), FieldElement> = BTreeMap::new();
+    for (scale, w_l, w_r) in &expr.mul_terms {
+        let key = if w_l <= w_r {
+            (*w_l, *w_r)
+        } else {
+            (*w_r, *w_l)
+        };
+        let sum = mul_terms.entry(key).or_insert_with(FieldElement::zero);
+        *sum = *sum + *scale;
+    }
+    let mut linear_combinations: BTreeMap<Witness, FieldElement> = BTreeMap::new();
+    for (scale, witness) in &expr.linear_combinations {
+        let sum = linear_combinations
+            .entry(*witness)
+            .or_insert_with(FieldElement::zero);
+        *sum = *sum + *scale;
+    }
+    mul_terms.retain(|_, scale| !scale.is_zero());
+    linear_combinations.retain(|_, scale| !scale.is_zero());
+    (mul_terms, linear_combinations)
+}
+
+fn encode(expr: &Expression) -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    buffer.extend_from_slice(&(expr.mul_terms.len() as u32).to_be_bytes());
+    for (scale, w_l, w_r) in &expr.mul_terms {
+        buffer.extend_from_slice(&scale.to_be_bytes());
+        buffer.extend_from_slice(&w_l.witness_index().to_be_bytes());
+        buffer.extend_from_slice(&w_r.witness_index().to_be_bytes());
+    }
+    for (scale, witness) in &expr.linear_combinations {
+        buffer.extend_from_slice(&scale.to_be_bytes());
+        buffer.extend_from_slice(&witness.witness_index().to_be_bytes());
+    }
+    buffer.extend_from_slice(&expr.q_c.to_be_bytes());
+
+    buffer
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::PublicInputs;
+
+    use super::*;
+
+    fn linear(terms: &[(i128, u32)], q_c: i128) -> Opcode {
+        Opcode::Arithmetic(Expression {
+            mul_terms: vec![],
+            linear_combinations: terms
+                .iter()
+                .map(|(scale, witness)| (FieldElement::from(*scale), Witness(*witness)))
+                .collect(),
+            q_c: FieldElement::from(q_c),
+        })
+    }
+
+    #[test]
+    fn removes_duplicate_and_trivial_gates() {
+        let circuit = Circuit {
+            current_witness_index: 3,
+            opcodes: vec![
+                linear(&[(1, 1), (-1, 2)], 0),
+                linear(&[(2, 2), (-2, 1)], 0),
+                linear(&[(1, 3), (-1, 3)], 0),
+                linear(&[(1, 3)], -5),
+                linear(&[(1, 1), (-1, 2)], 0),
+            ],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        };
+
+        let (packed, report) = pack_arithmetic_gates(&circuit);
+
+        assert_eq!(
+            report,
+            GatePackingReport {
+                gates_before: 5,
+                gates_after: 2,
+                duplicates_removed: 2,
+                trivial_removed: 1,
+                gates_merged: 0,
+            }
+        );
+        assert_eq!(report.gates_saved(), 3);
+        assert_eq!(packed.opcodes.len(), 2);
+    }
+
+    fn chained_gates() -> Circuit {
+        let product = Expression {
+            mul_terms: vec![(FieldElement::one(), Witness(1), Witness(2))],
+            linear_combinations: vec![(-FieldElement::one(), Witness(3))],
+            q_c: FieldElement::zero(),
+        };
+        Circuit {
+            current_witness_index: 5,
+            opcodes: vec![
+                Opcode::Arithmetic(product),
+                linear(&[(1, 3), (1, 1), (-1, 4)], 0),
+                linear(&[(1, 4), (-1, 5)], 0),
+            ],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        }
+    }
+
+    #[test]
+    fn merges_gates_through_intermediate_witnesses() {
+        let (packed, report) = pack_arithmetic_gates(&chained_gates());
+
+        assert_eq!(report.gates_merged, 2);
+        assert_eq!(report.gates_saved(), 2);
+        assert_eq!(packed.opcodes.len(), 1);
+
+        // w1*w2 + w1 - w5 = 0
+        let Opcode::Arithmetic(merged) = &packed.opcodes[0] else {
+            panic!("expected an arithmetic gate");
+        };
+        let (mul_terms, linear_terms) = collect_terms(merged);
+        assert_eq!(
+            mul_terms,
+            BTreeMap::from([((Witness(1), Witness(2)), FieldElement::one())])
+        );
+        assert_eq!(
+            linear_terms,
+            BTreeMap::from([
+                (Witness(1), FieldElement::one()),
+                (Witness(5), -FieldElement::one()),
+            ])
+        );
+        assert!(merged.q_c.is_zero());
+    }
+
+    #[test]
+    fn keeps_intermediate_witnesses_which_are_used_elsewhere() {
+        // w4 is a public input so only w3 may be eliminated.
+        let mut circuit = chained_gates();
+        circuit.return_values = PublicInputs(BTreeSet::from([Witness(4)]));
+        let (packed, report) = pack_arithmetic_gates(&circuit);
+        assert_eq!(report.gates_merged, 1);
+        assert_eq!(packed.opcodes.len(), 2);
+
+        // w3 is referenced by a third gate, so neither gate may absorb it.
+        let mut circuit = chained_gates();
+        circuit.opcodes[2] = linear(&[(1, 4), (1, 3), (-1, 5)], 0);
+        let (packed, report) = pack_arithmetic_gates(&circuit);
+        assert_eq!(report.gates_merged, 1);
+        assert_eq!(packed.opcodes.len(), 2);
+        assert_eq!(packed.opcodes[0], chained_gates().opcodes[0]);
+    }
+
+    #[test]
+    fn does_not_eliminate_witnesses_which_cancel_out() {
+        // w3 - w3 leaves w3 with a zero coefficient in the first gate, so eliminating it would drop w1 = w2.
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes: vec![
+                linear(&[(1, 1), (-1, 2), (1, 3), (-1, 3)], 0),
+                linear(&[(1, 3), (-1, 4)], 0),
+            ],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        };
+
+        let (packed, report) = pack_arithmetic_gates(&circuit);
+
+        assert_eq!(report.gates_merged, 0);
+        assert_eq!(packed.opcodes, circuit.opcodes);
+    }
+
+    #[test]
+    fn keeps_distinct_gates() {
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![linear(&[(1, 1)], -1), linear(&[(1, 1)], -2)],
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs::default(),
+        };
+
+        let (packed, report) = pack_arithmetic_gates(&circuit);
+
+        assert_eq!(report.gates_saved(), 0);
+        assert_eq!(packed.opcodes.len(), 2);
+    }
+}
@@ -0,0 +1,254 @@
+//! Arithmetic on the Grumpkin curve, `y^2 = x^3 - 17` over the BN254 scalar field.
+//!
+//! Grumpkin is the curve used by the `FixedBaseScalarMul` and `SchnorrVerify` black box functions, so these
+//! operations let application code compute values such as note commitments and public keys which match those
+//! computed within a circuit.
+//!
+//! Scalar multiplication here is not constant time and shouldn't be applied to secret scalars where timing
+//! side channels are a concern.
+
+use std::ops::{Add, Neg};
+
+use acvm::FieldElement;
+
+use crate::{BackendError, Error, FIELD_BYTES};
+
+/// The `y` coordinate of barretenberg's Grumpkin generator, whose `x` coordinate is 1.
+const GENERATOR_Y: &str = "0x0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c";
+
+/// The constant term of the curve equation.
+fn curve_b() -> FieldElement {
+    -FieldElement::from(17_u128)
+}
+
+/// A point on the Grumpkin curve in affine coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GrumpkinPoint {
+    x: FieldElement,
+    y: FieldElement,
+    is_infinity: bool,
+}
+
+impl GrumpkinPoint {
+    /// Creates a point from its coordinates, checking that it lies on the curve.
+    pub fn new(x: FieldElement, y: FieldElement) -> Result<GrumpkinPoint, BackendError> {
+        let point = GrumpkinPoint {
+            x,
+            y,
+            is_infinity: false,
+        };
+        if !point.is_on_curve() {
+            return Err(Error::InvalidGrumpkinPoint.into());
+        }
+        Ok(point)
+    }
+
+    /// The generator used by barretenberg for fixed base scalar multiplication and schnorr public keys.
+    pub fn generator() -> GrumpkinPoint {
+        GrumpkinPoint {
+            x: FieldElement::one(),
+            y: FieldElement::from_hex(GENERATOR_Y).expect("generator should be valid hex"),
+            is_infinity: false,
+        }
+    }
+
+    /// The identity element of the curve's group.
+    pub fn infinity() -> GrumpkinPoint {
+        GrumpkinPoint {
+            x: FieldElement::zero(),
+            y: FieldElement::zero(),
+            is_infinity: true,
+        }
+    }
+
+    /// Returns the point's `x` coordinate, or `None` for the point at infinity.
+    pub fn x(&self) -> Option<FieldElement> {
+        (!self.is_infinity).then_some(self.x)
+    }
+
+    /// Returns the point's `y` coordinate, or `None` for the point at infinity.
+    pub fn y(&self) -> Option<FieldElement> {
+        (!self.is_infinity).then_some(self.y)
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.is_infinity
+    }
+
+    /// Returns `true` if the point satisfies the curve equation. Grumpkin has a cofactor of 1, so every point on
+    /// the curve is in the prime order subgroup.
+    pub fn is_on_curve(&self) -> bool {
+        self.is_infinity || self.y * self.y == self.x * self.x * self.x + curve_b()
+    }
+
+    pub fn double(&self) -> GrumpkinPoint {
+        if self.is_infinity || self.y.is_zero() {
+            return GrumpkinPoint::infinity();
+        }
+
+        let three = FieldElement::from(3_u128);
+        let two = FieldElement::from(2_u128);
+        let lambda = three * self.x * self.x * (two * self.y).inverse();
+        self.with_slope(lambda, self.x)
+    }
+
+    /// Multiplies the point by a scalar, given as 32 big-endian bytes.
+    ///
+    /// The scalar is reduced modulo the order of the curve's group. The time taken depends on the scalar, so
+    /// multiplying the generator by a secret should instead go through barretenberg, e.g. with
+    /// [`Barretenberg::schnorr_compute_public_key`][crate::Barretenberg::schnorr_compute_public_key].
+    pub fn scalar_mul(&self, scalar: &[u8; FIELD_BYTES]) -> GrumpkinPoint {
+        let mut result = GrumpkinPoint::infinity();
+        for byte in scalar {
+            for bit in (0..8).rev() {
+                result = result.double();
+                if (byte >> bit) & 1 == 1 {
+                    result = result + *self;
+                }
+            }
+        }
+        result
+    }
+
+    /// Encodes the point as its big-endian `x` and `y` coordinates, the encoding barretenberg uses for schnorr
+    /// public keys. The point at infinity is encoded as `(0, 0)`, which isn't otherwise on the curve.
+    pub fn to_bytes(&self) -> [u8; 2 * FIELD_BYTES] {
+        let mut bytes = [0u8; 2 * FIELD_BYTES];
+        if !self.is_infinity {
+            bytes[..FIELD_BYTES].copy_from_slice(&self.x.to_be_bytes());
+            bytes[FIELD_BYTES..].copy_from_slice(&self.y.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes a point produced by [`to_bytes`][GrumpkinPoint::to_bytes], checking that it lies on the curve.
+    pub fn from_bytes(bytes: &[u8; 2 * FIELD_BYTES]) -> Result<GrumpkinPoint, BackendError> {
+        if bytes.iter().all(|byte| *byte == 0) {
+            return Ok(GrumpkinPoint::infinity());
+        }
+
+        let (x, y) = bytes.split_at(FIELD_BYTES);
+        let canonical = |coordinate: &[u8]| {
+            let field = FieldElement::from_be_bytes_reduce(coordinate);
+            (field.to_be_bytes() == coordinate)
+                .then_some(field)
+                .ok_or(Error::NonCanonicalFieldElement)
+        };
+        GrumpkinPoint::new(canonical(x)?, canonical(y)?)
+    }
+
+    /// Returns the third point on the line through `self` and `other_x` with gradient `lambda`, reflected in the
+    /// x axis.
+    fn with_slope(&self, lambda: FieldElement, other_x: FieldElement) -> GrumpkinPoint {
+        let x = lambda * lambda - self.x - other_x;
+        let y = lambda * (self.x - x) - self.y;
+        GrumpkinPoint {
+            x,
+            y,
+            is_infinity: false,
+        }
+    }
+}
+
+impl Add for GrumpkinPoint {
+    type Output = GrumpkinPoint;
+
+    fn add(self, other: GrumpkinPoint) -> GrumpkinPoint {
+        if self.is_infinity {
+            return other;
+        }
+        if other.is_infinity {
+            return self;
+        }
+        if self.x == other.x {
+            return if self.y == other.y {
+                self.double()
+            } else {
+                GrumpkinPoint::infinity()
+            };
+        }
+
+        let lambda = (other.y - self.y) * (other.x - self.x).inverse();
+        self.with_slope(lambda, other.x)
+    }
+}
+
+impl Neg for GrumpkinPoint {
+    type Output = GrumpkinPoint;
+
+    fn neg(self) -> GrumpkinPoint {
+        if self.is_infinity {
+            return self;
+        }
+        GrumpkinPoint { y: -self.y, ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scalar_mul::ScalarMul;
+    use crate::{Barretenberg, SecretScalar};
+
+    fn point(x: &str, y: &str) -> GrumpkinPoint {
+        GrumpkinPoint::new(
+            FieldElement::from_hex(x).unwrap(),
+            FieldElement::from_hex(y).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn scalar(value: u128) -> [u8; FIELD_BYTES] {
+        FieldElement::from(value).to_be_bytes().try_into().unwrap()
+    }
+
+    #[test]
+    fn group_law() {
+        let generator = GrumpkinPoint::generator();
+        assert!(generator.is_on_curve());
+
+        let two_g = point(
+            "0x06ce1b0827aafa85ddeb49cdaa36306d19a74caa311e13d46d8bc688cdbffffe",
+            "0x1c122f81a3a14964909ede0ba2a6855fc93faf6fa1a788bf467be7e7a43f80ac",
+        );
+        let three_g = point(
+            "0x2941b0928df1b9480273773b36397da3e495430a2a7a3857661bc7a446c94f4d",
+            "0x13ae7e938c892308bef0f45ee7386daa2d3b447349a7d0a11b5aa4cfbe69072c",
+        );
+
+        assert_eq!(generator.double(), two_g);
+        assert_eq!(generator + generator, two_g);
+        assert_eq!(two_g + generator, three_g);
+        assert_eq!(generator.scalar_mul(&scalar(3)), three_g);
+        assert_eq!(generator + -generator, GrumpkinPoint::infinity());
+        assert_eq!(GrumpkinPoint::infinity() + three_g, three_g);
+        assert_eq!(generator.scalar_mul(&scalar(0)), GrumpkinPoint::infinity());
+    }
+
+    #[test]
+    fn matches_barretenberg_fixed_base_scalar_mul() -> Result<(), Error> {
+        let bb = Barretenberg::new();
+        let scalar_value = FieldElement::from_hex("0x0123456789abcdef").unwrap();
+
+        let (x, y) = bb.fixed_base(&SecretScalar::from_field(&scalar_value))?;
+        let expected = GrumpkinPoint::new(x, y).unwrap();
+
+        let scalar = scalar_value.to_be_bytes().try_into().unwrap();
+        assert_eq!(GrumpkinPoint::generator().scalar_mul(&scalar), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        for point in [
+            GrumpkinPoint::generator().scalar_mul(&scalar(7)),
+            GrumpkinPoint::infinity(),
+        ] {
+            assert_eq!(GrumpkinPoint::from_bytes(&point.to_bytes()).unwrap(), point);
+        }
+
+        let mut off_curve = GrumpkinPoint::generator().to_bytes();
+        off_curve[63] ^= 1;
+        assert!(GrumpkinPoint::from_bytes(&off_curve).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::{FieldElement, ProofSystemCompiler};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use zeroize::Zeroizing;
+
+use crate::framing;
+use crate::{BackendError, Barretenberg, Error, Proof};
+
+const NONCE_BYTES: usize = 12;
+
+const HEADER_BYTES: usize = framing::HEADER_BYTES + NONCE_BYTES;
+
+/// Encrypts a proving key with ChaCha20-Poly1305 so that it can be stored without revealing the circuit's structure.
+///
+/// `encryption_key` is called once to obtain the 256 bit key, which is wiped from memory after use.
+/// The output is made up of the magic bytes `BBEK`, a big-endian `u32` format version, a random 96 bit nonce
+/// and the ciphertext. The header is authenticated along with the key so can't be altered undetected.
+pub fn encrypt_proving_key(
+    proving_key: &[u8],
+    encryption_key: impl FnOnce() -> [u8; 32],
+) -> Result<Vec<u8>, BackendError> {
+    let cipher = cipher(encryption_key);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut encrypted = Vec::with_capacity(HEADER_BYTES + proving_key.len() + 16);
+    encrypted.extend_from_slice(&framing::ENCRYPTED_PROVING_KEY.header());
+    encrypted.extend_from_slice(&nonce);
+
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            chacha20poly1305::aead::Payload {
+                msg: proving_key,
+                aad: &encrypted,
+            },
+        )
+        .map_err(|_| Error::EncryptedKey("could not encrypt proving key".to_owned()))?;
+    encrypted.extend_from_slice(&ciphertext);
+    Ok(encrypted)
+}
+
+/// Decrypts a proving key produced by [`encrypt_proving_key`].
+///
+/// The decrypted key is wiped from memory when the returned value is dropped.
+pub fn decrypt_proving_key(
+    encrypted: &[u8],
+    encryption_key: impl FnOnce() -> [u8; 32],
+) -> Result<Zeroizing<Vec<u8>>, BackendError> {
+    if encrypted.len() < HEADER_BYTES {
+        return Err(Error::EncryptedKey("encrypted key is truncated".to_owned()).into());
+    }
+    let (header, ciphertext) = encrypted.split_at(HEADER_BYTES);
+    framing::ENCRYPTED_PROVING_KEY
+        .check_header(header)
+        .map_err(Error::EncryptedKey)?;
+    let nonce = Nonce::from_slice(&header[framing::HEADER_BYTES..]);
+
+    let proving_key = cipher(encryption_key)
+        .decrypt(
+            nonce,
+            chacha20poly1305::aead::Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| {
+            Error::EncryptedKey(
+                "could not decrypt proving key, either the key is wrong or the data is corrupted"
+                    .to_owned(),
+            )
+        })?;
+    Ok(Zeroizing::new(proving_key))
+}
+
+fn cipher(encryption_key: impl FnOnce() -> [u8; 32]) -> ChaCha20Poly1305 {
+    // The cipher borrows the key rather than copying it into a temporary, and wipes its own copy when dropped.
+    let key = Zeroizing::new(encryption_key());
+    ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+}
+
+impl Barretenberg {
+    /// Creates a proof using a proving key which was encrypted with [`encrypt_proving_key`].
+    ///
+    /// The decrypted key held by this crate is wiped once the proof has been created. Barretenberg copies the key
+    /// into its own memory (the wasm heap, or the native library's allocations) which isn't wiped, so callers
+    /// which need the plaintext key gone from memory should prove in a short-lived process.
+    pub fn prove_with_encrypted_pk(
+        &self,
+        circuit: &Circuit,
+        witness_values: BTreeMap<Witness, FieldElement>,
+        encrypted_proving_key: &[u8],
+        encryption_key: impl FnOnce() -> [u8; 32],
+    ) -> Result<Proof, BackendError> {
+        let proving_key = decrypt_proving_key(encrypted_proving_key, encryption_key)?;
+        Ok(Proof(self.prove_with_pk(
+            circuit,
+            witness_values,
+            &proving_key,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn encrypted_key_round_trip() -> Result<(), BackendError> {
+        let proving_key = b"not really a proving key".to_vec();
+
+        let encrypted = encrypt_proving_key(&proving_key, || KEY)?;
+
+        assert!(!encrypted
+            .windows(proving_key.len())
+            .any(|window| window == proving_key));
+        assert_eq!(*decrypt_proving_key(&encrypted, || KEY)?, proving_key);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_wrong_key_and_tampering() -> Result<(), BackendError> {
+        let encrypted = encrypt_proving_key(b"proving key", || KEY)?;
+
+        assert!(decrypt_proving_key(&encrypted, || [8; 32]).is_err());
+        for index in [0, 5, HEADER_BYTES - 1, encrypted.len() - 1] {
+            let mut tampered = encrypted.clone();
+            tampered[index] ^= 1;
+            assert!(decrypt_proving_key(&tampered, || KEY).is_err());
+        }
+        assert!(decrypt_proving_key(&encrypted[..HEADER_BYTES - 1], || KEY).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeSet;
+
+use acvm::acir::circuit::Circuit;
+
+use crate::barretenberg_structures::ConstraintSystem;
+use crate::composer::Composer;
+use crate::verification_key::{VerificationKey, ULTRA_COMPOSER_TYPE};
+use crate::{BackendError, Barretenberg, Error, ProvingKey, FIELD_BYTES};
+
+/// The fields which prefix both serialized proving and verification keys.
+struct KeyHeader {
+    composer_type: u32,
+    circuit_size: u32,
+    num_public_inputs: u32,
+}
+
+impl Barretenberg {
+    /// Checks that `proving_key` was generated for `circuit`.
+    ///
+    /// This catches keys which are stale or were generated for a different circuit, which would otherwise
+    /// result in proofs which fail to verify. The key's header is compared against the circuit and the rest of
+    /// the key is checked to be a well-formed set of polynomials of the circuit's size, but the polynomials
+    /// themselves aren't recomputed, so changes to a circuit which preserve its size and number of public inputs
+    /// can't be detected.
+    pub fn validate_proving_key(
+        &self,
+        circuit: &Circuit,
+        proving_key: &ProvingKey,
+    ) -> Result<(), BackendError> {
+        let header = read_proving_key(proving_key.as_ref())?;
+        Ok(self.check_key_header("proving key", &header, &self.constraint_system(circuit)?)?)
+    }
+
+    /// Checks that `verification_key` was generated for `circuit`.
+    ///
+    /// As with [`Barretenberg::validate_proving_key`], this compares the key's metadata against the circuit
+    /// rather than recomputing its commitments.
+    pub fn validate_verification_key(
+        &self,
+        circuit: &Circuit,
+        verification_key: &VerificationKey,
+    ) -> Result<(), BackendError> {
+        let header = KeyHeader {
+            composer_type: verification_key.composer_type,
+            circuit_size: verification_key.circuit_size,
+            num_public_inputs: verification_key.num_public_inputs,
+        };
+        Ok(self.check_key_header(
+            "verification key",
+            &header,
+            &self.constraint_system(circuit)?,
+        )?)
+    }
+
+    fn check_key_header(
+        &self,
+        key: &'static str,
+        header: &KeyHeader,
+        constraint_system: &ConstraintSystem,
+    ) -> Result<(), Error> {
+        let mismatch = |reason: String| Error::KeyCircuitMismatch { key, reason };
+
+        if header.composer_type != ULTRA_COMPOSER_TYPE {
+            return Err(mismatch(format!(
+                "expected an UltraPlonk key (composer type {ULTRA_COMPOSER_TYPE}) but found composer type {}",
+                header.composer_type
+            )));
+        }
+
+        let num_public_inputs = constraint_system.public_inputs_size();
+        if header.num_public_inputs as usize != num_public_inputs {
+            return Err(mismatch(format!(
+                "circuit has {num_public_inputs} public inputs but the key has {}",
+                header.num_public_inputs
+            )));
+        }
+
+        // Barretenberg sizes the key to the smallest power of two which holds the circuit's gates, lookup tables
+        // and reserved gates, so any other size means the key was generated for a different circuit.
+        let circuit_size = self.get_circuit_size(constraint_system)?;
+        if header.circuit_size != circuit_size {
+            return Err(mismatch(format!(
+                "circuit requires a key of size {circuit_size} but the key has size {}",
+                header.circuit_size
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the header of a serialized proving key and checks that the rest of the key has barretenberg's layout:
+/// the precomputed polynomials, each a label followed by `circuit_size` or `4 * circuit_size` field elements,
+/// then the recursive proof flag and indices and the memory read and write records, with nothing after them.
+fn read_proving_key(proving_key: &[u8]) -> Result<KeyHeader, Error> {
+    let malformed = |reason: &str| Error::KeyCircuitMismatch {
+        key: "proving key",
+        reason: reason.to_string(),
+    };
+
+    let mut reader = KeyReader(proving_key);
+    let header = KeyHeader {
+        composer_type: reader.read_u32()?,
+        circuit_size: reader.read_u32()?,
+        num_public_inputs: reader.read_u32()?,
+    };
+    let circuit_size = header.circuit_size as usize;
+
+    let num_polynomials = reader.read_u32()?;
+    let mut labels = BTreeSet::new();
+    for _ in 0..num_polynomials {
+        let label_length = reader.read_u32()? as usize;
+        let label = std::str::from_utf8(reader.read_bytes(label_length)?)
+            .map_err(|_| malformed("polynomial label is not valid UTF-8"))?;
+        if !labels.insert(label) {
+            return Err(malformed(&format!(
+                "polynomial {label} appears more than once"
+            )));
+        }
+
+        let size = reader.read_u32()? as usize;
+        if size != circuit_size && size != 4 * circuit_size {
+            return Err(malformed(&format!(
+                "polynomial {label} has {size} coefficients in a key of size {circuit_size}"
+            )));
+        }
+        let num_bytes = size
+            .checked_mul(FIELD_BYTES)
+            .ok_or_else(|| malformed("polynomial is too large"))?;
+        reader.read_bytes(num_bytes)?;
+    }
+
+    let _contains_recursive_proof = reader.read_bytes(1)?;
+    for index in reader.read_u32_vec()? {
+        if index >= header.num_public_inputs {
+            return Err(malformed(&format!(
+                "recursive proof public input {index} is not one of the key's {} public inputs",
+                header.num_public_inputs
+            )));
+        }
+    }
+    let _memory_read_records = reader.read_u32_vec()?;
+    let _memory_write_records = reader.read_u32_vec()?;
+
+    if !reader.0.is_empty() {
+        return Err(malformed("unexpected data after the end of the key"));
+    }
+    Ok(header)
+}
+
+/// Reads the big-endian fields of a serialized proving key.
+struct KeyReader<'a>(&'a [u8]);
+
+impl<'a> KeyReader<'a> {
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < length {
+            return Err(Error::KeyCircuitMismatch {
+                key: "proving key",
+                reason: "key is truncated".to_string(),
+            });
+        }
+        let (bytes, rest) = self.0.split_at(length);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+    }
+
+    fn read_u32_vec(&mut self) -> Result<Vec<u32>, Error> {
+        let length = self.read_u32()?;
+        (0..length).map(|_| self.read_u32()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_circuits;
+
+    #[test]
+    fn accepts_keys_for_the_same_circuit() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let circuit = test_circuits::merkle_membership(2).circuit;
+        let (proving_key, verification_key) = bb.preprocess_keys(&circuit)?;
+
+        bb.validate_proving_key(&circuit, &proving_key)?;
+        bb.validate_verification_key(&circuit, &verification_key)?;
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_keys_for_a_different_circuit() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        // The merkle membership circuit has a public input, whereas the arithmetic circuit has none.
+        let circuit = test_circuits::merkle_membership(2).circuit;
+        let other_circuit = test_circuits::arithmetic_gates(2).circuit;
+        let (proving_key, verification_key) = bb.preprocess_keys(&other_circuit)?;
+
+        assert!(bb.validate_proving_key(&circuit, &proving_key).is_err());
+        assert!(bb
+            .validate_verification_key(&circuit, &verification_key)
+            .is_err());
+        assert!(bb
+            .validate_proving_key(&circuit, &ProvingKey::from_bytes(vec![0; 8]))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_proving_keys() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let circuit = test_circuits::merkle_membership(2).circuit;
+        let (proving_key, _) = bb.preprocess_keys(&circuit)?;
+
+        let proving_key = proving_key.into_bytes();
+        let mut truncated = proving_key.clone();
+        truncated.pop();
+        let truncated = ProvingKey::from_bytes(truncated);
+        assert!(bb.validate_proving_key(&circuit, &truncated).is_err());
+
+        let mut extended = proving_key.clone();
+        extended.push(0);
+        let extended = ProvingKey::from_bytes(extended);
+        assert!(bb.validate_proving_key(&circuit, &extended).is_err());
+
+        // The polynomials no longer match the key's size, which is also too small for the circuit.
+        let mut resized = proving_key;
+        let circuit_size = u32::from_be_bytes(resized[4..8].try_into().unwrap());
+        resized[4..8].copy_from_slice(&(circuit_size / 2).to_be_bytes());
+        let resized = ProvingKey::from_bytes(resized);
+        assert!(bb.validate_proving_key(&circuit, &resized).is_err());
+        Ok(())
+    }
+}
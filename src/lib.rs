@@ -8,21 +8,116 @@
 #[cfg(all(feature = "native", feature = "wasm"))]
 compile_error!("feature \"native\" and feature \"wasm\" cannot be enabled at the same time");
 
+// Criterion is only used by the benches, but dev-dependencies are shared with the library's test target.
+#[cfg(test)]
+use criterion as _;
+
+pub mod abi;
 mod acvm_interop;
+pub mod analyze;
+mod artifacts;
 mod barretenberg_structures;
+mod bundle;
+mod circuit;
 mod composer;
+pub mod consistency;
+mod constraint_map;
+mod constraint_system;
+mod cpu;
 #[cfg(any(feature = "native", feature = "wasm"))]
 mod crs;
-#[cfg(test)]
-mod merkle;
+#[cfg(feature = "dev-srs")]
+mod dev_srs;
+mod error_code;
+mod estimate;
+mod field;
+mod fingerprint;
+pub mod fixtures;
+mod framing;
+mod gate_packing;
+pub mod grumpkin;
+#[cfg(feature = "encrypted-keys")]
+mod key_encryption;
+mod key_validation;
+mod merkle_tree;
+pub mod musig;
+mod options;
 mod pedersen;
 mod pippenger;
+mod pool;
+mod preprocess;
+mod primitives;
+mod proof;
+mod prove_stream;
+mod prover_pool;
 mod scalar_mul;
 mod schnorr;
+mod secret;
+mod self_test;
+mod standalone_verify;
+#[cfg(feature = "prove-in-subprocess")]
+mod subprocess;
+mod telemetry;
+pub mod test_circuits;
+#[cfg(feature = "native")]
+mod threads;
+mod verification_key;
+mod witness_map;
+#[cfg(feature = "sealed-witness")]
+mod witness_sealing;
+mod witness_vector;
 
 use acvm::acir::BlackBoxFunc;
 use thiserror::Error;
 
+#[cfg(any(feature = "native", feature = "wasm"))]
+pub use acvm_interop::HttpOracleResolver;
+pub use acvm_interop::{
+    ConstraintViolation, OpcodeTiming, OracleResolver, OracleSolveError, SolveProfile, SolveTrace,
+    SolverSnapshot, TraceEntry, TraceOutcome, TracedSolveError, WitnessSolver,
+};
+pub use artifacts::ProvingKey;
+pub use bundle::Bundle;
+pub use circuit::{read_circuit_checked, read_circuit_framed, CircuitLimits};
+pub use constraint_map::{
+    constraint_map, explain_failed_constraint, ConstraintKind, ConstraintMap, FailedConstraint,
+};
+pub use constraint_system::serialize_constraint_system;
+pub use cpu::CpuFeatures;
+pub use estimate::{
+    black_box_cost, estimate_onchain_verification_gas, estimate_proof_size, GateEstimate,
+};
+pub use field::{
+    field_from_le_bytes, field_to_le_bytes, public_inputs_from_bytes, public_inputs_to_bytes,
+    Endianness, FieldPadding,
+};
+pub use fingerprint::{circuit_fingerprint, vk_fingerprint, vk_fingerprint_from_bytes};
+pub use gate_packing::{pack_arithmetic_gates, GatePackingReport};
+#[cfg(feature = "encrypted-keys")]
+pub use key_encryption::{decrypt_proving_key, encrypt_proving_key};
+pub use merkle_tree::{Checkpoint, InMemoryMerkleTree, LeafHasher};
+pub use options::{AllocationEvent, AllocationObserver, BackendOptions, BlackBoxOverride};
+pub use pool::BarretenbergPool;
+pub use preprocess::{PreprocessHandle, PreprocessStage};
+pub use primitives::{NOTE_COMMITMENT_DOMAIN_SEPARATOR, NULLIFIER_DOMAIN_SEPARATOR};
+pub use proof::{Proof, ProofComponent};
+pub use prove_stream::ProveStage;
+pub use prover_pool::{CircuitId, ProofReceiver, ProverPool};
+pub use schnorr::SchnorrError;
+pub use secret::{SecretScalar, SecretWitness};
+pub use self_test::{SelfTestCheck, SelfTestReport};
+#[cfg(feature = "prove-in-subprocess")]
+pub use subprocess::run_subprocess_worker;
+pub use verification_key::{Commitment, VerificationKey};
+pub use witness_map::{
+    diff_witness_maps, extract_public_inputs, merge_witness_maps, witness_map_from_cbor,
+    witness_map_from_json, witness_map_to_cbor, witness_map_to_json, ConflictPolicy,
+    WitnessMapDiff,
+};
+#[cfg(feature = "sealed-witness")]
+pub use witness_sealing::{seal_witness_map, sealing_public_key, unseal_witness_map};
+pub use witness_vector::WitnessVector;
+
 #[cfg(feature = "native")]
 #[derive(Debug, Error)]
 enum FeatureError {
@@ -34,6 +129,13 @@ enum FeatureError {
     FieldToArray(usize, usize),
 }
 
+#[cfg(feature = "native")]
+impl FeatureError {
+    fn is_out_of_memory(&self) -> bool {
+        false
+    }
+}
+
 #[cfg(not(feature = "native"))]
 #[derive(Debug, Error)]
 enum FeatureError {
@@ -63,6 +165,15 @@ enum FeatureError {
     },
     #[error("Value expected to be 0 or 1 representing a boolean")]
     InvalidBool,
+    #[error("Could not allocate {requested} bytes within the memory limit of {limit} bytes")]
+    OutOfMemory { requested: usize, limit: usize },
+}
+
+#[cfg(not(feature = "native"))]
+impl FeatureError {
+    fn is_out_of_memory(&self) -> bool {
+        matches!(self, FeatureError::OutOfMemory { .. })
+    }
 }
 
 #[derive(Debug, Error)]
@@ -76,6 +187,124 @@ enum Error {
     #[error("Unsupported Black Box Function: {0}")]
     UnsupportedBlackBoxFunc(BlackBoxFunc),
 
+    #[error("Malformed calldata: {0}")]
+    MalformedCalldata(String),
+
+    #[error("Malformed proof: {0}")]
+    MalformedProof(String),
+
+    #[error("Malformed verification key: {0}")]
+    MalformedVerificationKey(String),
+
+    #[error("Malformed artifact: {0}")]
+    MalformedArtifact(String),
+
+    #[error("The {key} does not match the circuit: {reason}")]
+    KeyCircuitMismatch { key: &'static str, reason: String },
+
+    #[error("Malformed bundle: {0}")]
+    MalformedBundle(String),
+
+    #[cfg(feature = "encrypted-keys")]
+    #[error("Could not use encrypted proving key: {0}")]
+    EncryptedKey(String),
+
+    #[cfg(feature = "sealed-witness")]
+    #[error("Could not use sealed witness map: {0}")]
+    SealedWitness(String),
+
+    #[error("Field element is not less than the field modulus")]
+    NonCanonicalFieldElement,
+
+    #[error("The circuit is defined over the field with modulus 0x{actual} but the backend uses 0x{expected}")]
+    FieldMismatch { expected: String, actual: String },
+
+    #[error("Point is not on the Grumpkin curve")]
+    InvalidGrumpkinPoint,
+
+    #[error("Invalid backend configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Malformed witness map: {0}")]
+    MalformedWitnessMap(String),
+
+    #[error("Witness map is missing a value for public input {0}")]
+    MissingPublicInput(u32),
+
+    #[error("The verification key expects {expected} public inputs but {actual} were provided")]
+    PublicInputCountMismatch { expected: u32, actual: usize },
+
+    #[error("Witness {0} has different values in the witness maps being merged")]
+    ConflictingWitnessValue(u32),
+
+    #[error("Merkle tree depth must be between 1 and 32 but was {0}")]
+    InvalidMerkleTreeDepth(u32),
+
+    #[error("Index {index} is out of bounds for a merkle tree of depth {depth}")]
+    MerkleIndexOutOfBounds { index: u64, depth: u32 },
+
+    #[error("Merkle tree checkpoint {0} was never taken or is no longer retained")]
+    UnknownCheckpoint(u64),
+
+    #[error("Preprocessing was cancelled")]
+    PreprocessCancelled,
+
+    #[error("Could not access preprocessing checkpoint: {0}")]
+    PreprocessCheckpoint(std::io::Error),
+
+    #[error("Proving was cancelled")]
+    ProveCancelled,
+
+    #[error("Malformed ABI: {0}")]
+    MalformedAbi(String),
+
+    #[error("Inputs do not match the ABI: {0}")]
+    AbiInput(String),
+
+    #[cfg(feature = "dev-srs")]
+    #[error(
+        "The dev SRS only supports circuits of up to {} gates but the circuit has {circuit_size}",
+        dev_srs::DEV_SRS_MAX_CIRCUIT_SIZE
+    )]
+    DevSrsTooSmall { circuit_size: usize },
+
+    #[error("Malformed circuit: {0}")]
+    MalformedCircuit(String),
+
+    #[error("Malformed constraint system: {0}")]
+    MalformedConstraintSystem(String),
+
+    #[error("Could not decode circuit at byte {offset} of the decompressed ACIR, it may have been produced by a compiler using a version of ACIR other than {}: {message}", ACIR_VERSION)]
+    CircuitDecode { offset: u64, message: String },
+
+    #[cfg(feature = "wasm")]
+    #[error("A previous call into barretenberg failed, so the backend must be reset before it can be used again")]
+    PoisonedBackend,
+
+    #[cfg(feature = "prove-in-subprocess")]
+    #[error("The barretenberg worker process exited with {status}: {stderr}")]
+    BackendCrashed { status: String, stderr: String },
+
+    #[cfg(feature = "prove-in-subprocess")]
+    #[error("Barretenberg worker process failed: {0}")]
+    SubprocessFailed(String),
+
+    #[error("Fixture {name} is unavailable: {reason}")]
+    FixtureUnavailable { name: String, reason: String },
+
+    #[error("Fixture {name} does not match its golden artifacts: {reason}")]
+    FixtureMismatch { name: String, reason: String },
+
+    #[error("Proving job panicked: {0}")]
+    ProverPanicked(String),
+
+    #[error("Invalid MuSig nonce: {0}")]
+    MusigNonce(String),
+
+    #[cfg(any(feature = "native", feature = "wasm"))]
+    #[error("Could not start HTTP oracle resolver: {0}")]
+    OracleResolver(String),
+
     #[error(transparent)]
     FromFeature(#[from] FeatureError),
 }
@@ -90,15 +319,36 @@ impl From<FeatureError> for BackendError {
     }
 }
 
+impl BackendError {
+    /// Returns `true` if the backend couldn't allocate memory within [`BackendOptions::max_memory_bytes`].
+    ///
+    /// Callers can use this to retry with a higher limit or on another machine, rather than treating the failure
+    /// as a problem with the circuit.
+    pub fn is_out_of_memory(&self) -> bool {
+        matches!(&self.0, Error::FromFeature(err) if err.is_out_of_memory())
+    }
+}
+
+/// The version of ACIR which this backend is able to deserialize.
+const ACIR_VERSION: &str = "0.11.0";
+
 /// The number of bytes necessary to store a `FieldElement`.
 const FIELD_BYTES: usize = 32;
 
+/// A handle to the Barretenberg backend.
+///
+/// Operations which pass data through the backend's scratch space must not be run concurrently
+/// on the same instance. Use [`Barretenberg::pooled`] to share backends between threads.
 #[derive(Debug)]
 pub struct Barretenberg {
     #[cfg(feature = "wasm")]
     memory: wasmer::Memory,
     #[cfg(feature = "wasm")]
     instance: wasmer::Instance,
+    /// Set once a call into the wasm fails, after which its memory can't be trusted.
+    #[cfg(feature = "wasm")]
+    poisoned: std::sync::atomic::AtomicBool,
+    options: BackendOptions,
 }
 
 impl Default for Barretenberg {
@@ -119,11 +369,20 @@ fn smoke() -> Result<(), Error> {
 
 #[cfg(feature = "native")]
 mod native {
-    use super::{Barretenberg, Error, FeatureError};
+    use super::{BackendError, BackendOptions, Barretenberg, Error, FeatureError};
 
     impl Barretenberg {
         pub(crate) fn new() -> Barretenberg {
-            Barretenberg {}
+            Barretenberg {
+                options: BackendOptions::default(),
+            }
+        }
+
+        /// Creates a backend with the given `options`.
+        ///
+        /// The native backend doesn't support memory limits or allocation observers so these options are ignored.
+        pub fn with_options(options: BackendOptions) -> Result<Barretenberg, BackendError> {
+            Ok(Barretenberg { options })
         }
     }
 
@@ -139,9 +398,10 @@ mod native {
 #[cfg(not(feature = "native"))]
 mod wasm {
     use std::cell::Cell;
+    use std::sync::atomic::{AtomicBool, Ordering};
     use wasmer::{imports, Function, Instance, Memory, MemoryType, Module, Store, Value};
 
-    use super::{Barretenberg, Error, FeatureError};
+    use super::{AllocationEvent, BackendError, BackendOptions, Barretenberg, Error, FeatureError};
 
     /// The number of bytes necessary to represent a pointer to memory inside the wasm.
     pub(super) const POINTER_BYTES: usize = 4;
@@ -161,10 +421,57 @@ mod wasm {
     #[include = "barretenberg.wasm"]
     struct Wasm;
 
+    /// The size of a page of wasm memory.
+    const WASM_PAGE_BYTES: usize = 64 * 1024;
+
+    /// The number of pages of memory which the wasm starts with.
+    const INITIAL_MEMORY_PAGES: u32 = 130;
+
     impl Barretenberg {
         pub(crate) fn new() -> Barretenberg {
-            let (instance, memory) = instance_load();
-            Barretenberg { memory, instance }
+            Barretenberg::with_options(BackendOptions::default())
+                .expect("default options place no limit on memory")
+        }
+
+        /// Creates a backend with the given `options`.
+        ///
+        /// Returns an error if `options.max_memory_bytes` is smaller than the memory the wasm requires to start.
+        pub fn with_options(options: BackendOptions) -> Result<Barretenberg, BackendError> {
+            let max_pages = match options.max_memory_bytes {
+                Some(limit) => {
+                    let max_pages = limit / WASM_PAGE_BYTES;
+                    if max_pages < INITIAL_MEMORY_PAGES as usize {
+                        return Err(Error::from(FeatureError::OutOfMemory {
+                            requested: INITIAL_MEMORY_PAGES as usize * WASM_PAGE_BYTES,
+                            limit,
+                        })
+                        .into());
+                    }
+                    Some(max_pages.min(u32::MAX as usize) as u32)
+                }
+                None => None,
+            };
+
+            let (instance, memory) = instance_load(max_pages);
+            Ok(Barretenberg {
+                memory,
+                instance,
+                poisoned: AtomicBool::new(false),
+                options,
+            })
+        }
+
+        /// Returns whether a call into the wasm has failed, leaving this backend unusable until it's
+        /// [reset][Self::reset].
+        pub fn is_poisoned(&self) -> bool {
+            self.poisoned.load(Ordering::Acquire)
+        }
+
+        /// Replaces the wasm instance with a fresh one, created with the same options, discarding any state left
+        /// behind by a failed call.
+        pub fn reset(&mut self) -> Result<(), BackendError> {
+            *self = Barretenberg::with_options(self.options.clone())?;
+            Ok(())
         }
     }
 
@@ -272,6 +579,12 @@ mod wasm {
             }
         }
 
+        /// Overwrites a secret which was transferred to the scratch space with zeroes, so it doesn't linger in the
+        /// WASM memory once barretenberg has used it.
+        pub(super) fn wipe_scratch(&self, offset: usize, length: usize) {
+            self.transfer_to_heap(&vec![0; length], offset);
+        }
+
         // TODO: Consider making this Result-returning
         pub(super) fn read_memory<const SIZE: usize>(&self, start: usize) -> [u8; SIZE] {
             self.read_memory_variable_length(start, SIZE)
@@ -307,6 +620,12 @@ mod wasm {
             name: &str,
             params: Vec<&WASMValue>,
         ) -> Result<WASMValue, Error> {
+            // A call which traps may have been part way through updating the heap, so nothing read from or written to
+            // the wasm afterwards can be relied upon.
+            if self.is_poisoned() {
+                return Err(Error::PoisonedBackend);
+            }
+
             // We take in a reference to values, since they do not implement Copy.
             // We then clone them inside of this function, so that the API does not have a bunch of Clones everywhere
 
@@ -320,12 +639,13 @@ mod wasm {
                     source,
                 }
             })?;
-            let boxed_value =
-                func.call(&args)
-                    .map_err(|source| FeatureError::FunctionCallFailed {
-                        name: name.to_string(),
-                        source,
-                    })?;
+            let boxed_value = func.call(&args).map_err(|source| {
+                self.poisoned.store(true, Ordering::Release);
+                FeatureError::FunctionCallFailed {
+                    name: name.to_string(),
+                    source,
+                }
+            })?;
             let option_value = boxed_value.first().cloned();
 
             Ok(WASMValue(option_value))
@@ -335,10 +655,28 @@ mod wasm {
         pub(super) fn allocate(&self, bytes: &[u8]) -> Result<WASMValue, Error> {
             let ptr: i32 = self.call("bbmalloc", &bytes.len().into())?.try_into()?;
 
+            // `bbmalloc` returns a null pointer if the wasm memory could not be grown to fit the allocation,
+            // which can only happen if we've placed a limit on its size.
+            if ptr == 0 {
+                if let Some(limit) = self.options.max_memory_bytes {
+                    return Err(FeatureError::OutOfMemory {
+                        requested: bytes.len(),
+                        limit,
+                    }
+                    .into());
+                }
+            }
+
             let i32_bytes = ptr.to_be_bytes();
             let u32_bytes = u32::from_be_bytes(i32_bytes);
 
             self.transfer_to_heap(bytes, u32_bytes as usize);
+            if let Some(observer) = &self.options.allocation_observer {
+                observer(AllocationEvent::Allocated {
+                    ptr: u32_bytes as usize,
+                    size: bytes.len(),
+                });
+            }
             Ok(ptr.into())
         }
 
@@ -347,6 +685,12 @@ mod wasm {
         /// It most likely is a bug
         pub(super) fn free(&self, pointer: WASMValue) -> Result<(), Error> {
             self.call("bbfree", &pointer)?;
+            if let Some(observer) = &self.options.allocation_observer {
+                let ptr: i32 = pointer.try_into()?;
+                observer(AllocationEvent::Freed {
+                    ptr: u32::from_be_bytes(ptr.to_be_bytes()) as usize,
+                });
+            }
             Ok(())
         }
     }
@@ -358,10 +702,10 @@ mod wasm {
         (module, store)
     }
 
-    fn instance_load() -> (Instance, Memory) {
+    fn instance_load(max_pages: Option<u32>) -> (Instance, Memory) {
         let (module, store) = load_module();
 
-        let mem_type = MemoryType::new(130, None, false);
+        let mem_type = MemoryType::new(INITIAL_MEMORY_PAGES, max_pages, false);
         let memory = Memory::new(&store, mem_type).unwrap();
 
         let custom_imports = imports! {
@@ -491,4 +835,87 @@ mod wasm {
     fn env_load_prover_crs(_: i32) -> i32 {
         unimplemented!("env_load_prover_crs is not implemented")
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::{Arc, Mutex};
+
+        use super::*;
+
+        #[test]
+        fn rejects_memory_limit_below_initial_memory() {
+            let options = BackendOptions {
+                max_memory_bytes: Some(WASM_PAGE_BYTES),
+                ..BackendOptions::default()
+            };
+
+            assert!(Barretenberg::with_options(options).is_err());
+        }
+
+        #[test]
+        fn allocations_exceeding_memory_limit_fail() {
+            let limit = INITIAL_MEMORY_PAGES as usize * WASM_PAGE_BYTES;
+            let options = BackendOptions {
+                max_memory_bytes: Some(limit),
+                ..BackendOptions::default()
+            };
+            let bb = Barretenberg::with_options(options).unwrap();
+
+            let result = bb.allocate(&vec![0; limit]);
+
+            assert!(matches!(
+                result,
+                Err(Error::FromFeature(FeatureError::OutOfMemory { .. }))
+            ));
+            assert!(BackendError::from(result.unwrap_err()).is_out_of_memory());
+        }
+
+        #[test]
+        fn observer_is_notified_of_allocations() -> Result<(), Error> {
+            let events = Arc::new(Mutex::new(Vec::new()));
+            let recorded_events = events.clone();
+            let options = BackendOptions {
+                allocation_observer: Some(Arc::new(move |event| {
+                    recorded_events.lock().unwrap().push(event)
+                })),
+                ..BackendOptions::default()
+            };
+            let bb = Barretenberg::with_options(options).unwrap();
+
+            let ptr = bb.allocate(&[1, 2, 3])?;
+            let ptr_value: usize = ptr.clone().try_into()?;
+            bb.free(ptr)?;
+
+            assert_eq!(
+                *events.lock().unwrap(),
+                vec![
+                    AllocationEvent::Allocated {
+                        ptr: ptr_value,
+                        size: 3
+                    },
+                    AllocationEvent::Freed { ptr: ptr_value },
+                ]
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn failed_call_poisons_backend_until_reset() -> Result<(), Error> {
+            let mut bb = Barretenberg::new();
+
+            // `bbmalloc` takes a size, so calling it without one fails.
+            assert!(bb.call_multiple("bbmalloc", vec![]).is_err());
+            assert!(bb.is_poisoned());
+            assert!(matches!(
+                bb.allocate(&[1, 2, 3]),
+                Err(Error::PoisonedBackend)
+            ));
+
+            bb.reset().unwrap();
+            assert!(!bb.is_poisoned());
+            let ptr = bb.allocate(&[1, 2, 3])?;
+            bb.free(ptr)?;
+            Ok(())
+        }
+    }
 }
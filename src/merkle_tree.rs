@@ -0,0 +1,450 @@
+use std::collections::{HashMap, VecDeque};
+
+use acvm::FieldElement;
+
+use sha2::{Digest, Sha256};
+
+use crate::pedersen::Pedersen;
+use crate::primitives::blake2s_to_field;
+use crate::{BackendError, Barretenberg, Error};
+
+/// The largest depth supported by [`InMemoryMerkleTree`].
+const MAX_DEPTH: u32 = 32;
+
+/// The number of checkpoints an [`InMemoryMerkleTree`] keeps unless configured otherwise.
+const DEFAULT_MAX_CHECKPOINTS: usize = 64;
+
+/// The hash used by [`InMemoryMerkleTree::update_message`] to turn a message into a leaf.
+///
+/// A tree's leaves must be hashed in the same way as the circuit which checks membership of them, so circuits
+/// which hash leaves themselves should use the matching hasher here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LeafHasher {
+    /// Blake2s, with the digest reduced into a field element, as used by barretenberg's `MemoryTree`.
+    #[default]
+    Blake2s,
+    /// SHA256, with the digest reduced into a field element.
+    Sha256,
+    /// Pedersen compression of the message packed into field elements as by barretenberg's `buffer_to_field`,
+    /// i.e. [`Barretenberg::pedersen_compress_bytes`].
+    ///
+    /// As in barretenberg, the final element holds the remaining bytes without padding, so messages which differ
+    /// only by trailing zeros hash differently but leading zeros within the final element are not distinguished.
+    Pedersen,
+}
+
+impl LeafHasher {
+    /// Hashes `message` into a leaf.
+    pub fn hash(
+        &self,
+        barretenberg: &Barretenberg,
+        message: &[u8],
+    ) -> Result<FieldElement, BackendError> {
+        match self {
+            LeafHasher::Blake2s => Ok(blake2s_to_field(message)),
+            LeafHasher::Sha256 => Ok(FieldElement::from_be_bytes_reduce(&Sha256::digest(message))),
+            LeafHasher::Pedersen => barretenberg.pedersen_compress_bytes(message),
+        }
+    }
+}
+
+/// A sparse merkle tree held in memory, which hashes its nodes identically to the `ComputeMerkleRoot`
+/// black box function.
+///
+/// Leaves are initialized to the hash of a message of 64 zero bytes, which matches barretenberg's `MemoryTree` when
+/// hashing with the default [`LeafHasher::Blake2s`].
+/// Only nodes which differ from those of an empty tree are stored, so deep trees are cheap to construct.
+#[derive(Debug)]
+pub struct InMemoryMerkleTree {
+    depth: u32,
+    barretenberg: Barretenberg,
+    leaf_hasher: LeafHasher,
+    /// The value of every node within an empty subtree, indexed by the node's height above the leaves.
+    empty_nodes: Vec<FieldElement>,
+    /// The nodes which have been updated, keyed by their height above the leaves and index within that layer.
+    nodes: HashMap<(u32, u64), FieldElement>,
+    /// The retained checkpoints, oldest first.
+    checkpoints: VecDeque<CheckpointState>,
+    next_checkpoint: u64,
+    max_checkpoints: usize,
+}
+
+/// Identifies the state of an [`InMemoryMerkleTree`] at the time [`InMemoryMerkleTree::checkpoint`] was called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Checkpoint(u64);
+
+#[derive(Debug)]
+struct CheckpointState {
+    id: u64,
+    /// The value each node had when the checkpoint was taken, for the nodes which were first updated between this
+    /// checkpoint and the next. Nodes updated before the next checkpoint are recorded by that checkpoint instead.
+    overwritten: HashMap<(u32, u64), FieldElement>,
+}
+
+impl InMemoryMerkleTree {
+    /// Creates an empty tree with `2^depth` leaves, whose messages are hashed with Blake2s.
+    pub fn new(depth: u32) -> Result<InMemoryMerkleTree, BackendError> {
+        InMemoryMerkleTree::with_leaf_hasher(Barretenberg::new(), depth, LeafHasher::default())
+    }
+
+    /// Creates an empty tree with `2^depth` leaves, whose messages are hashed with `leaf_hasher`, which hashes
+    /// its nodes with `barretenberg`.
+    pub fn with_leaf_hasher(
+        barretenberg: Barretenberg,
+        depth: u32,
+        leaf_hasher: LeafHasher,
+    ) -> Result<InMemoryMerkleTree, BackendError> {
+        if !(1..=MAX_DEPTH).contains(&depth) {
+            return Err(Error::InvalidMerkleTreeDepth(depth).into());
+        }
+
+        let mut empty_nodes = Vec::with_capacity(depth as usize + 1);
+        empty_nodes.push(leaf_hasher.hash(&barretenberg, &[0; 64])?);
+        for height in 0..depth as usize {
+            let empty_node = empty_nodes[height];
+            empty_nodes.push(barretenberg.compress_native(&empty_node, &empty_node)?);
+        }
+
+        Ok(InMemoryMerkleTree {
+            depth,
+            barretenberg,
+            leaf_hasher,
+            empty_nodes,
+            nodes: HashMap::new(),
+            checkpoints: VecDeque::new(),
+            next_checkpoint: 0,
+            max_checkpoints: DEFAULT_MAX_CHECKPOINTS,
+        })
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Returns the hash used to turn messages into leaves.
+    pub fn leaf_hasher(&self) -> LeafHasher {
+        self.leaf_hasher
+    }
+
+    pub fn root(&self) -> FieldElement {
+        self.node(self.depth, 0)
+    }
+
+    /// Returns the value of the leaf at `index`.
+    pub fn leaf(&self, index: u64) -> Result<FieldElement, BackendError> {
+        self.check_index(index)?;
+        Ok(self.node(0, index))
+    }
+
+    /// Sets the leaf at `index` to `leaf`, returning the new root of the tree.
+    pub fn update_leaf(
+        &mut self,
+        index: u64,
+        leaf: FieldElement,
+    ) -> Result<FieldElement, BackendError> {
+        self.check_index(index)?;
+
+        let mut index = index;
+        let mut current = leaf;
+        for height in 0..self.depth {
+            self.set_node(height, index, current);
+
+            let (left, right) = self.siblings(height, index);
+            current = self.barretenberg.compress_native(&left, &right)?;
+            index /= 2;
+        }
+        self.set_node(self.depth, 0, current);
+
+        Ok(current)
+    }
+
+    /// Sets the leaf at `index` to the hash of `message`, returning the new root of the tree.
+    pub fn update_message(
+        &mut self,
+        index: u64,
+        message: &[u8],
+    ) -> Result<FieldElement, BackendError> {
+        let leaf = self.leaf_hasher.hash(&self.barretenberg, message)?;
+        self.update_leaf(index, leaf)
+    }
+
+    /// Returns the pairs of sibling nodes along the path from the leaf at `index` up to the root,
+    /// starting with the pair which contains the leaf.
+    pub fn get_hash_path(
+        &self,
+        index: u64,
+    ) -> Result<Vec<(FieldElement, FieldElement)>, BackendError> {
+        self.check_index(index)?;
+
+        Ok((0..self.depth)
+            .map(|height| self.siblings(height, index >> height))
+            .collect())
+    }
+
+    /// Records the current state of the tree, so that its root and hash paths can still be retrieved after later
+    /// updates, e.g. for circuits which prove membership against a recent root rather than the latest one.
+    ///
+    /// Only the most recent 64 checkpoints are kept, unless changed with
+    /// [`set_max_checkpoints`][Self::set_max_checkpoints]. Keeping a checkpoint costs memory for each node updated
+    /// after it was taken.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let id = self.next_checkpoint;
+        self.next_checkpoint += 1;
+        self.checkpoints.push_back(CheckpointState {
+            id,
+            overwritten: HashMap::new(),
+        });
+        self.prune_checkpoints();
+        Checkpoint(id)
+    }
+
+    /// Sets the number of checkpoints to keep, discarding the oldest checkpoints if more than `max_checkpoints`
+    /// are already held.
+    pub fn set_max_checkpoints(&mut self, max_checkpoints: usize) {
+        self.max_checkpoints = max_checkpoints;
+        self.prune_checkpoints();
+    }
+
+    /// Returns the root of the tree at `checkpoint`.
+    pub fn root_at(&self, checkpoint: Checkpoint) -> Result<FieldElement, BackendError> {
+        let position = self.checkpoint_position(checkpoint)?;
+        Ok(self.node_at(position, self.depth, 0))
+    }
+
+    /// Returns the hash path for the leaf at `index` as it was at `checkpoint`, in the same form as
+    /// [`get_hash_path`][Self::get_hash_path].
+    pub fn get_hash_path_at(
+        &self,
+        index: u64,
+        checkpoint: Checkpoint,
+    ) -> Result<Vec<(FieldElement, FieldElement)>, BackendError> {
+        self.check_index(index)?;
+        let position = self.checkpoint_position(checkpoint)?;
+
+        Ok((0..self.depth)
+            .map(|height| {
+                let left_index = (index >> height) & !1;
+                (
+                    self.node_at(position, height, left_index),
+                    self.node_at(position, height, left_index + 1),
+                )
+            })
+            .collect())
+    }
+
+    fn checkpoint_position(&self, checkpoint: Checkpoint) -> Result<usize, Error> {
+        self.checkpoints
+            .binary_search_by_key(&checkpoint.0, |state| state.id)
+            .map_err(|_| Error::UnknownCheckpoint(checkpoint.0))
+    }
+
+    fn prune_checkpoints(&mut self) {
+        while self.checkpoints.len() > self.max_checkpoints {
+            self.checkpoints.pop_front();
+        }
+    }
+
+    fn set_node(&mut self, height: u32, index: u64, value: FieldElement) {
+        let previous = self.node(height, index);
+        if let Some(latest) = self.checkpoints.back_mut() {
+            latest
+                .overwritten
+                .entry((height, index))
+                .or_insert(previous);
+        }
+        self.nodes.insert((height, index), value);
+    }
+
+    /// Returns the value of a node at the checkpoint at `position` within `checkpoints`.
+    fn node_at(&self, position: usize, height: u32, index: u64) -> FieldElement {
+        // The earliest checkpoint since `position` to have recorded the node holds its value at `position`, as the
+        // node wasn't updated before that checkpoint was taken.
+        self.checkpoints
+            .range(position..)
+            .find_map(|state| state.overwritten.get(&(height, index)).copied())
+            .unwrap_or_else(|| self.node(height, index))
+    }
+
+    fn check_index(&self, index: u64) -> Result<(), Error> {
+        if index >> self.depth != 0 {
+            return Err(Error::MerkleIndexOutOfBounds {
+                index,
+                depth: self.depth,
+            });
+        }
+        Ok(())
+    }
+
+    fn node(&self, height: u32, index: u64) -> FieldElement {
+        self.nodes
+            .get(&(height, index))
+            .copied()
+            .unwrap_or(self.empty_nodes[height as usize])
+    }
+
+    fn siblings(&self, height: u32, index: u64) -> (FieldElement, FieldElement) {
+        let left_index = index & !1;
+        (
+            self.node(height, left_index),
+            self.node(height, left_index + 1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected values are copied from the output of barretenberg's `MemoryTree`.
+
+    #[test]
+    fn empty_tree_matches_barretenberg() -> Result<(), BackendError> {
+        let tree = InMemoryMerkleTree::new(3)?;
+
+        assert_eq!(
+            tree.root().to_hex(),
+            "04ccfbbb859b8605546e03dcaf41393476642859ff7f99446c054b841f0e05c8"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn updated_tree_matches_barretenberg() -> Result<(), BackendError> {
+        let mut tree = InMemoryMerkleTree::new(3)?;
+
+        let mut root = tree.root();
+        for index in 0..8 {
+            root = tree.update_message(index, &[index as u8; 64])?;
+        }
+
+        assert_eq!(
+            root.to_hex(),
+            "0ef8e14db4762ebddadb23b2225f93ca200a4c9bd37130b4d028c971bbad16b5"
+        );
+
+        let path: Vec<_> = tree
+            .get_hash_path(2)?
+            .into_iter()
+            .map(|(left, right)| (left.to_hex(), right.to_hex()))
+            .collect();
+        let expected_path = [
+            (
+                "06c2335d6f7acb84bbc7d0892cefebb7ca31169a89024f24814d5785e0d05324",
+                "12dc36b01cbd8a6248b04e08f0ec91aa6d11a91f030b4a7b1460281859942185",
+            ),
+            (
+                "1f399ea0d6aaf602c7cbcb6ae8cda0e6b6487836c017163888ed4fd38b548389",
+                "220dd1b310caa4a6af755b4c893d956c48f31642b487164b258f2973aac2c28f",
+            ),
+            (
+                "25cbb3084647221ffcb535945bb65bd70e0809834dc7a6d865a3f2bb046cdc29",
+                "2cc463fc8c9a4eda416f3e490876672f644708dd0330a915f6835d8396fa8f20",
+            ),
+        ];
+        for ((left, right), (expected_left, expected_right)) in path.iter().zip(expected_path) {
+            assert_eq!(left, expected_left);
+            assert_eq!(right, expected_right);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn deep_trees_are_created_lazily() -> Result<(), BackendError> {
+        let mut tree = InMemoryMerkleTree::new(MAX_DEPTH)?;
+
+        let empty_root = tree.root();
+        let path = tree.get_hash_path(0)?;
+        assert_eq!(path.len(), MAX_DEPTH as usize);
+        assert_eq!(
+            path[0].0.to_hex(),
+            "1cdcf02431ba623767fe389337d011df1048dcc24b98ed81cec97627bab454a0"
+        );
+
+        let root = tree.update_message((1 << MAX_DEPTH) - 1, &[1; 64])?;
+        assert_ne!(root, empty_root);
+        // Only the nodes along the updated path are stored.
+        assert_eq!(tree.nodes.len(), MAX_DEPTH as usize + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn leaves_are_hashed_with_configured_hasher() -> Result<(), BackendError> {
+        let message = [1; 40];
+        for leaf_hasher in [
+            LeafHasher::Blake2s,
+            LeafHasher::Sha256,
+            LeafHasher::Pedersen,
+        ] {
+            let mut tree =
+                InMemoryMerkleTree::with_leaf_hasher(Barretenberg::new(), 2, leaf_hasher)?;
+            assert_eq!(tree.leaf_hasher(), leaf_hasher);
+            assert_eq!(
+                tree.leaf(3)?,
+                leaf_hasher.hash(&tree.barretenberg, &[0; 64])?
+            );
+
+            tree.update_message(1, &message)?;
+            assert_eq!(
+                tree.leaf(1)?,
+                leaf_hasher.hash(&tree.barretenberg, &message)?
+            );
+        }
+
+        let bb = Barretenberg::new();
+        assert_ne!(
+            LeafHasher::Sha256.hash(&bb, &message)?,
+            LeafHasher::Blake2s.hash(&bb, &message)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pedersen_leaves_match_pedersen_compress_bytes() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let message = [1; 40];
+        let mut padded = [0; 64];
+        padded[..40].copy_from_slice(&message);
+
+        let leaf = LeafHasher::Pedersen.hash(&bb, &message)?;
+        assert_eq!(leaf, bb.pedersen_compress_bytes(&message)?);
+        // Trailing zeros change the value of the final element, so zero padding a message changes its leaf.
+        assert_ne!(leaf, LeafHasher::Pedersen.hash(&bb, &padded)?);
+        Ok(())
+    }
+
+    #[test]
+    fn paths_verify_against_checkpointed_roots() -> Result<(), BackendError> {
+        let mut tree = InMemoryMerkleTree::new(3)?;
+        tree.set_max_checkpoints(2);
+
+        tree.update_leaf(2, FieldElement::from(2_u128))?;
+        let first = tree.checkpoint();
+        let first_root = tree.root();
+        let first_path = tree.get_hash_path(2)?;
+
+        tree.update_leaf(3, FieldElement::from(3_u128))?;
+        tree.update_leaf(2, FieldElement::from(4_u128))?;
+        let second = tree.checkpoint();
+        tree.update_leaf(5, FieldElement::from(5_u128))?;
+
+        assert_eq!(tree.root_at(first)?, first_root);
+        assert_eq!(tree.get_hash_path_at(2, first)?, first_path);
+        assert_eq!(tree.leaf(2)?, FieldElement::from(4_u128));
+        assert_ne!(tree.root_at(second)?, tree.root());
+
+        tree.checkpoint();
+        assert!(tree.root_at(first).is_err());
+        assert!(tree.root_at(second).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_indices() -> Result<(), BackendError> {
+        let mut tree = InMemoryMerkleTree::new(2)?;
+
+        assert!(tree.update_leaf(4, FieldElement::one()).is_err());
+        assert!(tree.get_hash_path(4).is_err());
+        assert!(InMemoryMerkleTree::new(0).is_err());
+        Ok(())
+    }
+}
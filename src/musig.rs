@@ -0,0 +1,316 @@
+//! MuSig-style multi-signatures which verify as ordinary barretenberg schnorr signatures.
+//!
+//! Each signer's public key is weighted by a coefficient derived from the full set of keys, which prevents a
+//! signer from choosing their key so as to cancel out the others'. Signing then takes three rounds:
+//!
+//! 1. each signer picks a fresh random secret nonce and shares the [commitment][nonce_commitment] to its
+//!    [public nonce][Barretenberg::musig_public_nonce],
+//! 2. once every commitment has been received, the public nonces are revealed and
+//!    [aggregated][aggregate_nonces], which checks each of them against its commitment,
+//! 3. each signer computes a [partial signature][Barretenberg::musig_partial_sign] and any party can
+//!    [combine][Barretenberg::musig_combine] them into a signature for the [aggregate key][MusigKeyAggregate].
+//!
+//! Revealing nonces before every commitment has been received, or reusing a nonce, allows the other signers to
+//! forge signatures or recover the signer's private key.
+
+use acvm::FieldElement;
+use ark_bn254::Fq as GrumpkinScalar;
+use ark_ff::{BigInteger, PrimeField};
+use blake2::{Blake2s, Digest};
+use zeroize::Zeroizing;
+
+use crate::grumpkin::GrumpkinPoint;
+use crate::pedersen::Pedersen;
+use crate::scalar_mul::ScalarMul;
+use crate::{BackendError, Barretenberg, Error, SecretScalar, FIELD_BYTES};
+
+// Separate the hashes used for nonce commitments and key aggregation coefficients from each other, and from the
+// untagged schnorr challenge hash.
+const NONCE_COMMITMENT_DOMAIN: &[u8] = b"acvm-backend-barretenberg/musig-nonce";
+const KEY_AGGREGATION_DOMAIN: &[u8] = b"acvm-backend-barretenberg/musig-key-aggregation";
+
+/// The aggregate of a set of signers' public keys, along with the coefficient applied to each key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MusigKeyAggregate {
+    public_key: GrumpkinPoint,
+    coefficients: Vec<[u8; FIELD_BYTES]>,
+}
+
+impl MusigKeyAggregate {
+    /// Aggregates `public_keys`, which every signer must provide in the same order.
+    pub fn new(public_keys: &[GrumpkinPoint]) -> MusigKeyAggregate {
+        let key_set: Vec<u8> = public_keys.iter().flat_map(|key| key.to_bytes()).collect();
+
+        let coefficients: Vec<_> = public_keys
+            .iter()
+            .map(|key| {
+                let mut hasher = <Blake2s as Digest>::new();
+                hasher.update(KEY_AGGREGATION_DOMAIN);
+                hasher.update(&key_set);
+                hasher.update(key.to_bytes());
+                scalar_to_bytes(GrumpkinScalar::from_be_bytes_mod_order(&hasher.finalize()))
+            })
+            .collect();
+
+        let public_key = public_keys.iter().zip(&coefficients).fold(
+            GrumpkinPoint::infinity(),
+            |aggregate, (key, coefficient)| aggregate + key.scalar_mul(coefficient),
+        );
+
+        MusigKeyAggregate {
+            public_key,
+            coefficients,
+        }
+    }
+
+    /// The public key which the combined signature verifies against, in the encoding expected by `SchnorrVerify`.
+    pub fn public_key(&self) -> GrumpkinPoint {
+        self.public_key
+    }
+}
+
+/// Returns the commitment which a signer shares in the first round, before revealing `public_nonce`.
+pub fn nonce_commitment(public_nonce: &GrumpkinPoint) -> [u8; 32] {
+    let mut hasher = <Blake2s as Digest>::new();
+    hasher.update(NONCE_COMMITMENT_DOMAIN);
+    hasher.update(public_nonce.to_bytes());
+    hasher.finalize().into()
+}
+
+/// Sums the public nonces of every signer, checking that each matches the commitment received from that signer.
+///
+/// `public_nonces` and `commitments` must both be in signer order.
+pub fn aggregate_nonces(
+    public_nonces: &[GrumpkinPoint],
+    commitments: &[[u8; 32]],
+) -> Result<GrumpkinPoint, BackendError> {
+    if public_nonces.len() != commitments.len() {
+        return Err(Error::MusigNonce(format!(
+            "received {} public nonces but {} commitments",
+            public_nonces.len(),
+            commitments.len()
+        ))
+        .into());
+    }
+    if let Some(signer) = public_nonces
+        .iter()
+        .zip(commitments)
+        .position(|(nonce, commitment)| nonce_commitment(nonce) != *commitment)
+    {
+        return Err(Error::MusigNonce(format!(
+            "public nonce of signer {signer} does not match its commitment"
+        ))
+        .into());
+    }
+
+    Ok(public_nonces
+        .iter()
+        .fold(GrumpkinPoint::infinity(), |aggregate, nonce| {
+            aggregate + *nonce
+        }))
+}
+
+impl Barretenberg {
+    /// Returns the public nonce corresponding to a signer's `secret_nonce`.
+    ///
+    /// The nonce is multiplied by the generator inside barretenberg, as [`GrumpkinPoint::scalar_mul`] isn't
+    /// constant time and the private key can be recovered from a leaked nonce.
+    pub fn musig_public_nonce(
+        &self,
+        secret_nonce: &SecretScalar,
+    ) -> Result<GrumpkinPoint, BackendError> {
+        let (x, y) = self.fixed_base(secret_nonce)?;
+        GrumpkinPoint::new(x, y)
+    }
+
+    /// Computes the partial signature of the signer at `signer_index` in the key aggregate.
+    ///
+    /// `aggregate_nonce` is the [sum][aggregate_nonces] of every signer's public nonce, including this signer's.
+    /// `secret_nonce` must never be used for another signature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `signer_index` is out of range for the keys in `key_aggregate`.
+    pub fn musig_partial_sign(
+        &self,
+        message: &[u8],
+        private_key: &SecretScalar,
+        secret_nonce: &SecretScalar,
+        key_aggregate: &MusigKeyAggregate,
+        signer_index: usize,
+        aggregate_nonce: GrumpkinPoint,
+    ) -> Result<[u8; FIELD_BYTES], BackendError> {
+        let challenge =
+            self.schnorr_challenge(message, key_aggregate.public_key, aggregate_nonce)?;
+
+        let e = GrumpkinScalar::from_be_bytes_mod_order(&challenge);
+        let a = GrumpkinScalar::from_be_bytes_mod_order(&key_aggregate.coefficients[signer_index]);
+        let x = Zeroizing::new(GrumpkinScalar::from_be_bytes_mod_order(
+            private_key.as_bytes(),
+        ));
+        let k = Zeroizing::new(GrumpkinScalar::from_be_bytes_mod_order(
+            secret_nonce.as_bytes(),
+        ));
+        // The weighted key reveals the private key given the public coefficient and challenge.
+        let weighted_key = Zeroizing::new(a * *x * e);
+
+        Ok(scalar_to_bytes(*k - *weighted_key))
+    }
+
+    /// Combines every signer's partial signature into a schnorr signature `(s, e)` for the aggregate public key,
+    /// in the same form as signatures produced for a single private key.
+    pub fn musig_combine(
+        &self,
+        message: &[u8],
+        key_aggregate: &MusigKeyAggregate,
+        aggregate_nonce: GrumpkinPoint,
+        partial_signatures: &[[u8; FIELD_BYTES]],
+    ) -> Result<([u8; FIELD_BYTES], [u8; FIELD_BYTES]), BackendError> {
+        let s: GrumpkinScalar = partial_signatures
+            .iter()
+            .map(|partial| GrumpkinScalar::from_be_bytes_mod_order(partial))
+            .sum();
+        let e = self.schnorr_challenge(message, key_aggregate.public_key, aggregate_nonce)?;
+
+        Ok((scalar_to_bytes(s), e))
+    }
+
+    /// Computes barretenberg's schnorr challenge, the Blake2s hash of the pedersen compression of
+    /// `(R.x, pubkey.x, pubkey.y)` followed by the message.
+    fn schnorr_challenge(
+        &self,
+        message: &[u8],
+        public_key: GrumpkinPoint,
+        nonce: GrumpkinPoint,
+    ) -> Result<[u8; FIELD_BYTES], BackendError> {
+        let coordinates = |point: GrumpkinPoint| {
+            (
+                point.x().unwrap_or(FieldElement::zero()),
+                point.y().unwrap_or(FieldElement::zero()),
+            )
+        };
+        let (nonce_x, _) = coordinates(nonce);
+        let (public_key_x, public_key_y) = coordinates(public_key);
+        let compressed_keys = self.compress_many(vec![nonce_x, public_key_x, public_key_y])?;
+
+        let mut hasher = <Blake2s as Digest>::new();
+        hasher.update(compressed_keys.to_be_bytes());
+        hasher.update(message);
+        Ok(hasher.finalize().into())
+    }
+}
+
+fn scalar_to_bytes(scalar: GrumpkinScalar) -> [u8; FIELD_BYTES] {
+    scalar
+        .into_bigint()
+        .to_bytes_be()
+        .try_into()
+        .expect("grumpkin scalars are encoded as 32 bytes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schnorr::SchnorrSig;
+
+    #[test]
+    fn combined_signature_verifies_for_aggregate_key() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let message = b"transfer 10 tokens";
+        let scalar = |byte: u8| {
+            let mut bytes = [byte; 32];
+            bytes[0] = 0;
+            SecretScalar::from_bytes(bytes)
+        };
+        let private_keys = [1, 2, 3].map(scalar);
+        let secret_nonces = [4, 5, 6].map(scalar);
+
+        let public_keys: Vec<_> = private_keys
+            .iter()
+            .map(|key| GrumpkinPoint::generator().scalar_mul(key.as_bytes()))
+            .collect();
+        let key_aggregate = MusigKeyAggregate::new(&public_keys);
+        let public_nonces = secret_nonces
+            .iter()
+            .map(|nonce| bb.musig_public_nonce(nonce))
+            .collect::<Result<Vec<_>, _>>()?;
+        let commitments: Vec<_> = public_nonces.iter().map(nonce_commitment).collect();
+        let aggregate_nonce = aggregate_nonces(&public_nonces, &commitments)?;
+
+        let partial_signatures = private_keys
+            .iter()
+            .zip(&secret_nonces)
+            .enumerate()
+            .map(|(index, (private_key, secret_nonce))| {
+                bb.musig_partial_sign(
+                    message,
+                    private_key,
+                    secret_nonce,
+                    &key_aggregate,
+                    index,
+                    aggregate_nonce,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (s, e) = bb.musig_combine(
+            message,
+            &key_aggregate,
+            aggregate_nonce,
+            &partial_signatures,
+        )?;
+
+        let aggregate_key = key_aggregate.public_key().to_bytes();
+        assert!(bb
+            .verify_signature(&aggregate_key, &s, &e, message)
+            .unwrap());
+        assert!(!bb
+            .verify_signature(&aggregate_key, &s, &e, b"transfer 11 tokens")
+            .unwrap());
+        // Dropping a signer's partial signature invalidates the signature.
+        let (s, e) = bb.musig_combine(
+            message,
+            &key_aggregate,
+            aggregate_nonce,
+            &partial_signatures[1..],
+        )?;
+        assert!(!bb
+            .verify_signature(&aggregate_key, &s, &e, message)
+            .unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_nonces_which_do_not_match_their_commitments() -> Result<(), BackendError> {
+        let public_nonces = [1_u128, 2].map(|nonce| {
+            GrumpkinPoint::generator()
+                .scalar_mul(&FieldElement::from(nonce).to_be_bytes().try_into().unwrap())
+        });
+        let commitments = public_nonces.map(|nonce| nonce_commitment(&nonce));
+
+        aggregate_nonces(&public_nonces, &commitments)?;
+        assert!(aggregate_nonces(&public_nonces, &commitments[..1]).is_err());
+        // A signer who changes their nonce after seeing the others' is caught.
+        assert!(aggregate_nonces(&[public_nonces[0], public_nonces[0]], &commitments).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn key_coefficients_depend_on_the_whole_key_set() {
+        let keys: Vec<_> = (1..=3_u128)
+            .map(|key| {
+                GrumpkinPoint::generator()
+                    .scalar_mul(&FieldElement::from(key).to_be_bytes().try_into().unwrap())
+            })
+            .collect();
+
+        let aggregate = MusigKeyAggregate::new(&keys);
+        let subset_aggregate = MusigKeyAggregate::new(&keys[..2]);
+
+        assert_ne!(aggregate.coefficients[0], subset_aggregate.coefficients[0]);
+        assert_ne!(
+            aggregate.public_key(),
+            keys.iter()
+                .fold(GrumpkinPoint::infinity(), |sum, key| sum + *key)
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use acvm::acir::circuit::opcodes::BlackBoxFuncCall;
+use acvm::acir::native_types::Witness;
+use acvm::acir::BlackBoxFunc;
+use acvm::{FieldElement, OpcodeResolution, OpcodeResolutionError};
+use std::collections::BTreeMap;
+
+#[cfg(feature = "toml")]
+mod config_file;
+
+/// The environment variable which overrides the location of the transcript.
+pub(crate) const TRANSCRIPT_ENV_VAR: &str = "BARRETENBERG_TRANSCRIPT";
+
+/// An allocation made by, or on behalf of, the backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllocationEvent {
+    /// `size` bytes were allocated at `ptr`.
+    Allocated { ptr: usize, size: usize },
+    /// The allocation at `ptr` was freed.
+    Freed { ptr: usize },
+}
+
+/// Callback invoked for each [`AllocationEvent`].
+pub type AllocationObserver = Arc<dyn Fn(AllocationEvent) + Send + Sync>;
+
+/// Solves calls to a black box function in place of the backend's own implementation, e.g. to sign with a key
+/// held in an HSM or to hash on a GPU.
+///
+/// The override is given the witness map and the call, and must assign each of the call's outputs.
+pub type BlackBoxOverride = Arc<
+    dyn Fn(
+            &mut BTreeMap<Witness, FieldElement>,
+            &BlackBoxFuncCall,
+        ) -> Result<OpcodeResolution, OpcodeResolutionError>
+        + Send
+        + Sync,
+>;
+
+/// Configuration for a [`Barretenberg`][crate::Barretenberg] instance.
+///
+/// Memory limits and allocation observers are only supported by the wasm backend as the native backend
+/// allocates directly from the process heap.
+#[derive(Clone, Default)]
+pub struct BackendOptions {
+    /// The maximum size of the wasm memory. Allocations which would exceed this fail with an error
+    /// rather than growing the memory.
+    pub max_memory_bytes: Option<usize>,
+    /// Notified whenever memory is allocated or freed on the wasm heap.
+    pub allocation_observer: Option<AllocationObserver>,
+    /// The location of the SRS transcript, which is downloaded to this path if it doesn't already exist.
+    /// Defaults to `$BARRETENBERG_TRANSCRIPT`, or a file within `~/.nargo/backends` if that isn't set.
+    ///
+    /// A transcript with a `.zst` extension is decompressed as it's read when the `zstd` feature is enabled.
+    /// Compressed transcripts aren't downloaded so must already exist.
+    ///
+    /// Uncompressed transcripts are memory mapped whilst in use, holding a shared lock on the file, so they must
+    /// not be truncated or modified in place. Replace a transcript by writing a new file and renaming it over the
+    /// old one, or take an exclusive lock on it before writing.
+    pub transcript_path: Option<PathBuf>,
+    /// Functions which solve black box function calls during witness generation instead of the backend.
+    ///
+    /// The first override registered for a function is used. Inputs are still checked against their declared bit
+    /// sizes before the override is called.
+    pub black_box_overrides: Vec<(BlackBoxFunc, BlackBoxOverride)>,
+    /// The maximum number of threads barretenberg may use while computing keys and proofs, which otherwise uses
+    /// every core. The previous limit is restored once each operation completes.
+    ///
+    /// The wasm backend always runs on a single thread so ignores this.
+    pub max_threads: Option<usize>,
+    /// Merge the range constraints of each witness into the smallest of them and group them by bit size, so that
+    /// barretenberg can share range lookups between them. This reduces the gate count of circuits with many range
+    /// checks.
+    ///
+    /// The resulting circuit accepts exactly the same witnesses, but has different keys. Keys and proofs for a
+    /// circuit must therefore all be created with this set the same way.
+    pub aggregate_range_constraints: bool,
+    /// Use a locally generated SRS with publicly known toxic waste instead of the transcript.
+    ///
+    /// This supports circuits of up to 2^12 gates and lets tests run without downloading the transcript,
+    /// but proofs made with it are forgeable so it must only be used in development.
+    #[cfg(feature = "dev-srs")]
+    pub allow_dev_srs: bool,
+}
+
+impl BackendOptions {
+    /// Registers `solve` to be used for calls to `func` in place of the backend's implementation.
+    pub fn with_black_box_override(
+        mut self,
+        func: BlackBoxFunc,
+        solve: impl Fn(
+                &mut BTreeMap<Witness, FieldElement>,
+                &BlackBoxFuncCall,
+            ) -> Result<OpcodeResolution, OpcodeResolutionError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> BackendOptions {
+        self.black_box_overrides.push((func, Arc::new(solve)));
+        self
+    }
+}
+
+impl std::fmt::Debug for BackendOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("BackendOptions");
+        debug
+            .field("max_memory_bytes", &self.max_memory_bytes)
+            .field(
+                "allocation_observer",
+                &self
+                    .allocation_observer
+                    .as_ref()
+                    .map(|_| "Fn(AllocationEvent)"),
+            )
+            .field("transcript_path", &self.transcript_path)
+            .field(
+                "black_box_overrides",
+                &self
+                    .black_box_overrides
+                    .iter()
+                    .map(|(func, _)| func)
+                    .collect::<Vec<_>>(),
+            )
+            .field("max_threads", &self.max_threads)
+            .field(
+                "aggregate_range_constraints",
+                &self.aggregate_range_constraints,
+            );
+        #[cfg(feature = "dev-srs")]
+        debug.field("allow_dev_srs", &self.allow_dev_srs);
+        debug.finish()
+    }
+}
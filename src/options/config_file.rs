@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use super::{BackendOptions, TRANSCRIPT_ENV_VAR};
+use crate::{BackendError, Error};
+
+/// The environment variable which overrides the memory limit of the wasm backend.
+const MAX_MEMORY_ENV_VAR: &str = "BARRETENBERG_MAX_MEMORY_BYTES";
+
+/// The options which can be set from a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    transcript_path: Option<PathBuf>,
+    max_memory_bytes: Option<usize>,
+    max_threads: Option<usize>,
+    #[serde(default)]
+    aggregate_range_constraints: bool,
+    #[cfg(feature = "dev-srs")]
+    #[serde(default)]
+    allow_dev_srs: bool,
+}
+
+impl BackendOptions {
+    /// Reads options from a TOML configuration file such as `bb.toml`:
+    ///
+    /// ```toml
+    /// transcript_path = "srs/transcript00.dat"
+    /// max_memory_bytes = 4294967296
+    /// max_threads = 8
+    /// aggregate_range_constraints = true
+    /// ```
+    ///
+    /// `allow_dev_srs` may also be set when the `dev-srs` feature is enabled. Callbacks such as allocation
+    /// observers and black box overrides can only be registered in code.
+    ///
+    /// A relative `transcript_path` is resolved against the directory containing the file. The environment
+    /// variables `BARRETENBERG_TRANSCRIPT` and `BARRETENBERG_MAX_MEMORY_BYTES` take precedence over the file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<BackendOptions, BackendError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|err| {
+            Error::InvalidConfig(format!("could not read {}: {err}", path.display()))
+        })?;
+
+        let mut options = parse_config(&contents, path.parent().unwrap_or(Path::new("")))?;
+        apply_env_overrides(&mut options, |name| std::env::var(name).ok())?;
+        Ok(options)
+    }
+}
+
+fn parse_config(contents: &str, config_dir: &Path) -> Result<BackendOptions, Error> {
+    let config: ConfigFile =
+        toml::from_str(contents).map_err(|err| Error::InvalidConfig(err.to_string()))?;
+
+    Ok(BackendOptions {
+        max_memory_bytes: config.max_memory_bytes,
+        transcript_path: config.transcript_path.map(|path| config_dir.join(path)),
+        max_threads: config.max_threads,
+        aggregate_range_constraints: config.aggregate_range_constraints,
+        #[cfg(feature = "dev-srs")]
+        allow_dev_srs: config.allow_dev_srs,
+        ..BackendOptions::default()
+    })
+}
+
+fn apply_env_overrides(
+    options: &mut BackendOptions,
+    env_var: impl Fn(&str) -> Option<String>,
+) -> Result<(), Error> {
+    if let Some(transcript_path) = env_var(TRANSCRIPT_ENV_VAR) {
+        options.transcript_path = Some(PathBuf::from(transcript_path));
+    }
+    if let Some(max_memory_bytes) = env_var(MAX_MEMORY_ENV_VAR) {
+        let max_memory_bytes = max_memory_bytes.parse().map_err(|_| {
+            Error::InvalidConfig(format!(
+                "{MAX_MEMORY_ENV_VAR} must be a number of bytes but was {max_memory_bytes}"
+            ))
+        })?;
+        options.max_memory_bytes = Some(max_memory_bytes);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_file() -> Result<(), Error> {
+        let config = r#"
+            transcript_path = "srs/transcript00.dat"
+            max_memory_bytes = 1048576
+            max_threads = 4
+            aggregate_range_constraints = true
+        "#;
+
+        let options = parse_config(config, Path::new("/etc/bb"))?;
+
+        assert_eq!(
+            options.transcript_path,
+            Some(PathBuf::from("/etc/bb/srs/transcript00.dat"))
+        );
+        assert_eq!(options.max_memory_bytes, Some(1048576));
+        assert_eq!(options.max_threads, Some(4));
+        assert!(options.aggregate_range_constraints);
+        Ok(())
+    }
+
+    #[test]
+    fn omitted_options_take_their_defaults() -> Result<(), Error> {
+        let options = parse_config("", Path::new(""))?;
+
+        assert_eq!(options.transcript_path, None);
+        assert!(!options.aggregate_range_constraints);
+        Ok(())
+    }
+
+    #[cfg(feature = "dev-srs")]
+    #[test]
+    fn parses_allow_dev_srs() -> Result<(), Error> {
+        assert!(parse_config("allow_dev_srs = true", Path::new(""))?.allow_dev_srs);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        assert!(parse_config("threads = 4", Path::new("")).is_err());
+    }
+
+    #[test]
+    fn environment_overrides_config_file() -> Result<(), Error> {
+        let mut options = parse_config(
+            r#"transcript_path = "/srs/transcript00.dat""#,
+            Path::new(""),
+        )?;
+
+        apply_env_overrides(&mut options, |name| match name {
+            TRANSCRIPT_ENV_VAR => Some("/tmp/transcript00.dat".to_owned()),
+            MAX_MEMORY_ENV_VAR => Some("2048".to_owned()),
+            _ => None,
+        })?;
+
+        assert_eq!(
+            options.transcript_path,
+            Some(PathBuf::from("/tmp/transcript00.dat"))
+        );
+        assert_eq!(options.max_memory_bytes, Some(2048));
+
+        assert!(apply_env_overrides(&mut options, |name| {
+            (name == MAX_MEMORY_ENV_VAR).then(|| "lots".to_owned())
+        })
+        .is_err());
+        Ok(())
+    }
+}
@@ -1,38 +1,72 @@
 use crate::{Barretenberg, Error};
 
-pub(crate) struct Pippenger {
+/// Owns a pippenger instance created by barretenberg, deleting it when dropped.
+///
+/// The instance holds its own copy of the CRS points, so failing to delete it leaks memory in proportion to the
+/// circuit size on every proof.
+pub(crate) struct PippengerHandle<'a> {
     #[cfg(feature = "native")]
     pippenger_ptr: *mut std::os::raw::c_void,
     #[cfg(not(feature = "native"))]
     pippenger_ptr: crate::wasm::WASMValue,
+    #[cfg(not(feature = "native"))]
+    barretenberg: &'a Barretenberg,
+    #[cfg(feature = "native")]
+    _barretenberg: std::marker::PhantomData<&'a Barretenberg>,
 }
 
 #[cfg(feature = "native")]
-impl Pippenger {
+extern "C" {
+    // `barretenberg-sys` doesn't generate a binding for this c_bind but it's part of the library which it links.
+    fn delete_pippenger(pippenger: *mut std::os::raw::c_void);
+}
+
+#[cfg(feature = "native")]
+impl PippengerHandle<'_> {
     pub(crate) fn pointer(&self) -> *mut std::os::raw::c_void {
         self.pippenger_ptr
     }
 }
 
+#[cfg(feature = "native")]
+impl Drop for PippengerHandle<'_> {
+    fn drop(&mut self) {
+        unsafe { delete_pippenger(self.pippenger_ptr) }
+    }
+}
+
 #[cfg(not(feature = "native"))]
-impl Pippenger {
+impl PippengerHandle<'_> {
     pub(crate) fn pointer(&self) -> crate::wasm::WASMValue {
         self.pippenger_ptr.clone()
     }
 }
 
+#[cfg(not(feature = "native"))]
+impl Drop for PippengerHandle<'_> {
+    fn drop(&mut self) {
+        // A failure can't be reported from `drop`, and any call which fails poisons the backend anyway.
+        let _ = self
+            .barretenberg
+            .call("delete_pippenger", &self.pippenger_ptr);
+    }
+}
+
 #[cfg(feature = "native")]
 impl Barretenberg {
-    pub(crate) fn get_pippenger(&self, crs_data: &[u8]) -> Result<Pippenger, Error> {
+    pub(crate) fn get_pippenger(&self, crs_data: &[u8]) -> Result<PippengerHandle<'_>, Error> {
         let pippenger_ptr = barretenberg_sys::pippenger::new(crs_data);
 
-        Ok(Pippenger { pippenger_ptr })
+        Ok(PippengerHandle {
+            pippenger_ptr,
+            _barretenberg: std::marker::PhantomData,
+        })
     }
 }
 
 #[cfg(not(feature = "native"))]
 impl Barretenberg {
-    pub(crate) fn get_pippenger(&self, crs_data: &[u8]) -> Result<Pippenger, Error> {
+    pub(crate) fn get_pippenger(&self, crs_data: &[u8]) -> Result<PippengerHandle<'_>, Error> {
         use super::FIELD_BYTES;
 
         let num_points = crs_data.len() / (2 * FIELD_BYTES);
@@ -42,10 +76,14 @@ impl Barretenberg {
         // This doesn't unwrap the result because we need to free even if there is a failure
         let pippenger_ptr = self.call_multiple("new_pippenger", vec![&crs_ptr, &num_points.into()]);
 
-        self.free(crs_ptr)?;
+        // A failed call poisons the backend, so its error is returned rather than that from `free`.
+        let freed = self.free(crs_ptr);
+        let pippenger_ptr = pippenger_ptr?;
+        freed?;
 
-        Ok(Pippenger {
-            pippenger_ptr: pippenger_ptr?,
+        Ok(PippengerHandle {
+            pippenger_ptr,
+            barretenberg: self,
         })
     }
 }
@@ -0,0 +1,162 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError, TryLockError};
+
+use crate::{BackendError, BackendOptions, Barretenberg};
+
+/// A fixed set of [`Barretenberg`] instances which can be shared between threads.
+///
+/// Each instance is only ever used by a single thread at a time, so operations which rely upon
+/// the backend's internal scratch space (e.g. hashing or signing under the `wasm` feature) may be
+/// run concurrently through the pool.
+#[derive(Debug)]
+pub struct BarretenbergPool {
+    instances: Vec<Mutex<Barretenberg>>,
+    next_instance: AtomicUsize,
+}
+
+impl Barretenberg {
+    /// Creates a pool of `num_instances` independent backends.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_instances` is zero.
+    pub fn pooled(num_instances: usize) -> BarretenbergPool {
+        assert!(num_instances > 0, "a pool requires at least one instance");
+
+        BarretenbergPool {
+            instances: (0..num_instances)
+                .map(|_| Mutex::new(Barretenberg::new()))
+                .collect(),
+            next_instance: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a pool of `num_instances` independent backends, each created with `options`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_instances` is zero.
+    pub fn pooled_with_options(
+        num_instances: usize,
+        options: BackendOptions,
+    ) -> Result<BarretenbergPool, BackendError> {
+        assert!(num_instances > 0, "a pool requires at least one instance");
+
+        Ok(BarretenbergPool {
+            instances: (0..num_instances)
+                .map(|_| Barretenberg::with_options(options.clone()).map(Mutex::new))
+                .collect::<Result<_, _>>()?,
+            next_instance: AtomicUsize::new(0),
+        })
+    }
+}
+
+impl BarretenbergPool {
+    /// Runs `f` with exclusive access to one of the pool's instances.
+    ///
+    /// An idle instance is preferred. If every instance is busy then this blocks until one is released.
+    /// If `f` panics then the instance is replaced by a new one, created with the same options, before the panic
+    /// is resumed.
+    pub fn with<T>(&self, f: impl FnOnce(&Barretenberg) -> T) -> T {
+        let start = self.next_instance.fetch_add(1, Ordering::Relaxed);
+
+        let idle_instance = (0..self.instances.len())
+            .map(|offset| &self.instances[(start + offset) % self.instances.len()])
+            .find_map(|instance| match instance.try_lock() {
+                Ok(instance) => Some(instance),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            });
+
+        // Instances are replaced after a panic below, so a poisoned lock never guards a broken instance.
+        let mut instance = match idle_instance {
+            Some(instance) => instance,
+            None => self.instances[start % self.instances.len()]
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&instance)));
+
+        // A panic may have interrupted a call into barretenberg, and a failed call leaves a wasm instance
+        // poisoned, so in either case the instance is replaced before the pool hands it out again.
+        if result.is_err() || is_poisoned(&instance) {
+            if let Ok(replacement) = Barretenberg::with_options(instance.options.clone()) {
+                *instance = replacement;
+            }
+        }
+        // The lock is released before resuming the panic so that it isn't poisoned.
+        drop(instance);
+        result.unwrap_or_else(|panic| panic::resume_unwind(panic))
+    }
+
+    /// Returns the number of instances held by the pool.
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Returns `true` if the pool holds no instances, this can never be the case for a pool built through
+    /// [`Barretenberg::pooled`].
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+#[cfg(feature = "wasm")]
+fn is_poisoned(instance: &Barretenberg) -> bool {
+    instance.is_poisoned()
+}
+
+#[cfg(not(feature = "wasm"))]
+fn is_poisoned(_instance: &Barretenberg) -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pedersen::Pedersen;
+    use acvm::FieldElement;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn pool_is_send_and_sync() {
+        assert_send_sync::<BarretenbergPool>();
+    }
+
+    #[test]
+    fn replaces_instances_which_panic() {
+        let pool = Barretenberg::pooled(1);
+
+        let panicked = panic::catch_unwind(AssertUnwindSafe(|| {
+            pool.with(|_| panic!("prover failed"));
+        }));
+        assert!(panicked.is_err());
+
+        assert_eq!(pool.len(), 1);
+        assert!(pool
+            .with(|bb| bb.compress_native(&FieldElement::zero(), &FieldElement::one()))
+            .is_ok());
+    }
+
+    #[test]
+    fn pooled_instances_agree_across_threads() {
+        let pool = Barretenberg::pooled(2);
+        let expected = pool
+            .with(|bb| bb.compress_native(&FieldElement::zero(), &FieldElement::one()))
+            .unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let got = pool
+                        .with(|bb| bb.compress_native(&FieldElement::zero(), &FieldElement::one()))
+                        .unwrap();
+                    assert_eq!(got, expected);
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,220 @@
+use std::fs;
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+use acvm::acir::circuit::Circuit;
+
+use crate::composer::Composer;
+use crate::{circuit_fingerprint, BackendError, Barretenberg, Error, ProvingKey, VerificationKey};
+
+/// A circuit being preprocessed on a background thread, as started by [`Barretenberg::preprocess_async`].
+#[derive(Debug)]
+pub struct PreprocessHandle {
+    thread: JoinHandle<Result<(ProvingKey, VerificationKey), BackendError>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Barretenberg {
+    /// Generates the proving and verification keys for `circuit` on a background thread, returning
+    /// the same keys as [`Barretenberg::preprocess_keys`].
+    ///
+    /// The thread uses its own backend instance, created with this backend's options, so the caller is free to
+    /// continue using this instance, e.g. to solve the circuit's witness while its keys are generated.
+    pub fn preprocess_async(&self, circuit: Circuit) -> PreprocessHandle {
+        self.spawn_preprocess(circuit, Arc::new(AtomicBool::new(false)))
+    }
+
+    fn spawn_preprocess(&self, circuit: Circuit, cancelled: Arc<AtomicBool>) -> PreprocessHandle {
+        let options = self.options.clone();
+
+        let thread = {
+            let cancelled = Arc::clone(&cancelled);
+            thread::spawn(
+                move || -> Result<(ProvingKey, VerificationKey), BackendError> {
+                    let check_cancelled = || match cancelled.load(Ordering::Relaxed) {
+                        true => Err(Error::PreprocessCancelled),
+                        false => Ok(()),
+                    };
+
+                    check_cancelled()?;
+                    let bb = Barretenberg::with_options(options)?;
+                    let constraint_system = bb.constraint_system(&circuit)?;
+                    check_cancelled()?;
+                    let proving_key = bb.compute_proving_key(&constraint_system)?;
+                    check_cancelled()?;
+                    let verification_key =
+                        bb.compute_verification_key(&constraint_system, &proving_key)?;
+
+                    Ok((
+                        ProvingKey(proving_key),
+                        VerificationKey::from_bytes(&verification_key)?,
+                    ))
+                },
+            )
+        };
+
+        PreprocessHandle { thread, cancelled }
+    }
+}
+
+/// A stage of [`Barretenberg::preprocess_resumable`] which has completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreprocessStage {
+    /// The circuit has been converted into barretenberg's constraint system.
+    ConstraintSystemBuilt,
+    /// The proving key is available, `resumed` being `true` if it was read from a checkpoint rather than computed.
+    ProvingKeyReady { resumed: bool },
+    /// The verification key has been computed, completing preprocessing.
+    VerificationKeyComputed,
+}
+
+impl Barretenberg {
+    /// Generates the same keys as [`Barretenberg::preprocess_keys`],
+    /// saving the proving key to `checkpoint_dir` once computed so that a later call for the same circuit can
+    /// resume from it if the process dies while the verification key is computed.
+    ///
+    /// The checkpoint is named after the circuit's [fingerprint][circuit_fingerprint] and is removed once both keys
+    /// are returned. A checkpoint which doesn't match the circuit is discarded and the proving key recomputed.
+    /// `on_stage` is called as each stage completes.
+    pub fn preprocess_resumable(
+        &self,
+        circuit: &Circuit,
+        checkpoint_dir: impl AsRef<Path>,
+        mut on_stage: impl FnMut(PreprocessStage),
+    ) -> Result<(ProvingKey, VerificationKey), BackendError> {
+        let checkpoint_path = checkpoint_file(checkpoint_dir.as_ref(), circuit);
+
+        let constraint_system = self.constraint_system(circuit)?;
+        on_stage(PreprocessStage::ConstraintSystemBuilt);
+
+        let checkpoint = match fs::read(&checkpoint_path) {
+            Ok(proving_key) => Some(ProvingKey(proving_key))
+                .filter(|proving_key| self.validate_proving_key(circuit, proving_key).is_ok()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(Error::PreprocessCheckpoint(err).into()),
+        };
+        let resumed = checkpoint.is_some();
+        let proving_key = match checkpoint {
+            Some(proving_key) => proving_key,
+            None => {
+                let proving_key = ProvingKey(self.compute_proving_key(&constraint_system)?);
+                // Writing to a temporary file first means a crash mid-write can't leave a truncated checkpoint.
+                let partial_path = checkpoint_path.with_extension("pk.partial");
+                fs::write(&partial_path, &proving_key)
+                    .and_then(|()| fs::rename(&partial_path, &checkpoint_path))
+                    .map_err(Error::PreprocessCheckpoint)?;
+                proving_key
+            }
+        };
+        on_stage(PreprocessStage::ProvingKeyReady { resumed });
+
+        let verification_key =
+            self.compute_verification_key(&constraint_system, proving_key.as_ref())?;
+        let verification_key = VerificationKey::from_bytes(&verification_key)?;
+        on_stage(PreprocessStage::VerificationKeyComputed);
+
+        fs::remove_file(&checkpoint_path).map_err(Error::PreprocessCheckpoint)?;
+        Ok((proving_key, verification_key))
+    }
+}
+
+fn checkpoint_file(checkpoint_dir: &Path, circuit: &Circuit) -> PathBuf {
+    let fingerprint: String = circuit_fingerprint(circuit)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    checkpoint_dir.join(format!("{fingerprint}.pk"))
+}
+
+impl PreprocessHandle {
+    /// Requests that preprocessing stops.
+    ///
+    /// Barretenberg can't be interrupted whilst computing a key, so preprocessing stops once the key
+    /// currently being computed is complete. [`PreprocessHandle::join`] then returns an error.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` once preprocessing has completed, failed or been cancelled, at which point
+    /// [`PreprocessHandle::join`] will not block.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Waits for preprocessing to finish, returning the proving and verification keys.
+    ///
+    /// If the background thread panicked then the panic is resumed on the calling thread.
+    pub fn join(self) -> Result<(ProvingKey, VerificationKey), BackendError> {
+        match self.thread.join() {
+            Ok(keys) => keys,
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    #[test]
+    fn generates_same_keys_as_preprocess() -> Result<(), BackendError> {
+        let circuit = arithmetic_gates(4).circuit;
+
+        let bb = Barretenberg::new();
+        let handle = bb.preprocess_async(circuit.clone());
+        let keys = bb.preprocess_keys(&circuit)?;
+
+        assert_eq!(handle.join()?, keys);
+        Ok(())
+    }
+
+    #[test]
+    fn resumes_from_proving_key_checkpoint() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let circuit = arithmetic_gates(4).circuit;
+        let checkpoint_dir = tempfile::tempdir().unwrap();
+        let keys = bb.preprocess_keys(&circuit)?;
+
+        let mut stages = Vec::new();
+        assert_eq!(
+            bb.preprocess_resumable(&circuit, &checkpoint_dir, |stage| stages.push(stage))?,
+            keys
+        );
+        assert_eq!(
+            stages[1],
+            PreprocessStage::ProvingKeyReady { resumed: false }
+        );
+        assert_eq!(fs::read_dir(&checkpoint_dir).unwrap().count(), 0);
+
+        // Simulate a crash after the proving key was saved.
+        fs::write(checkpoint_file(checkpoint_dir.path(), &circuit), &keys.0).unwrap();
+
+        stages.clear();
+        assert_eq!(
+            bb.preprocess_resumable(&circuit, &checkpoint_dir, |stage| stages.push(stage))?,
+            keys
+        );
+        assert_eq!(
+            stages[1],
+            PreprocessStage::ProvingKeyReady { resumed: true }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn cancelled_preprocessing_returns_error() {
+        // Cancelling before the thread starts makes the outcome deterministic.
+        let handle = Barretenberg::new()
+            .spawn_preprocess(arithmetic_gates(4).circuit, Arc::new(AtomicBool::new(true)));
+
+        let err = handle.join().unwrap_err();
+        assert_eq!(
+            err.code(),
+            BackendError::from(Error::PreprocessCancelled).code()
+        );
+    }
+}
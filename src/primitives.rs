@@ -0,0 +1,207 @@
+//! Cryptographic primitives exposed for use outside of circuits, e.g. computing note commitments and nullifiers
+//! which must match those computed within a circuit.
+
+use acvm::FieldElement;
+use blake2::{Blake2s, Digest};
+
+use crate::pedersen::Pedersen;
+use crate::schnorr::{SchnorrError, SchnorrSig};
+use crate::{BackendError, Barretenberg, SecretScalar};
+
+/// The number of bytes packed into each field element by [`Barretenberg::pedersen_compress_bytes`].
+const PEDERSEN_BYTES_PER_ELEMENT: usize = 31;
+
+/// The domain separator hashed ahead of a note's fields by [`Barretenberg::compute_note_commitment`].
+pub const NOTE_COMMITMENT_DOMAIN_SEPARATOR: u32 = 1;
+
+/// The domain separator hashed ahead of a note's fields by [`Barretenberg::compute_nullifier`].
+pub const NULLIFIER_DOMAIN_SEPARATOR: u32 = 2;
+
+impl Barretenberg {
+    /// Compresses `inputs` into a single field element using the same pedersen hash as the `Pedersen` black box
+    /// function and merkle membership checks.
+    pub fn pedersen_compress(&self, inputs: &[FieldElement]) -> Result<FieldElement, BackendError> {
+        Ok(self.compress_many(inputs.to_vec())?)
+    }
+
+    /// Compresses an arbitrary byte buffer into a single field element, matching barretenberg's pedersen
+    /// `buffer_to_field`.
+    ///
+    /// The bytes are read as big-endian field elements, 31 bytes at a time so that each is less than the modulus,
+    /// with the final element holding any remaining bytes. These elements are then compressed as by
+    /// [`pedersen_compress`][Self::pedersen_compress].
+    pub fn pedersen_compress_bytes(&self, bytes: &[u8]) -> Result<FieldElement, BackendError> {
+        let inputs = bytes
+            .chunks(PEDERSEN_BYTES_PER_ELEMENT)
+            .map(FieldElement::from_be_bytes_reduce)
+            .collect();
+        Ok(self.compress_many(inputs)?)
+    }
+
+    /// Computes the commitment to a note made up of `fields`.
+    ///
+    /// The pedersen constraint in this version of ACIR has no generator index, so the commitment is separated
+    /// from nullifiers and other hashes of the same fields by hashing [`NOTE_COMMITMENT_DOMAIN_SEPARATOR`] as the
+    /// first input. A circuit computes the same value by passing the separator followed by `fields` to the
+    /// `Pedersen` black box function and taking the `x` coordinate of its output.
+    pub fn compute_note_commitment(
+        &self,
+        fields: &[FieldElement],
+    ) -> Result<FieldElement, BackendError> {
+        self.pedersen_compress_separated(NOTE_COMMITMENT_DOMAIN_SEPARATOR, fields)
+    }
+
+    /// Computes the nullifier of a note from `fields`, typically the note's commitment and the owner's secret.
+    ///
+    /// This is computed as by [`compute_note_commitment`][Self::compute_note_commitment] but with
+    /// [`NULLIFIER_DOMAIN_SEPARATOR`], so that a nullifier can never equal the commitment to the same fields.
+    pub fn compute_nullifier(&self, fields: &[FieldElement]) -> Result<FieldElement, BackendError> {
+        self.pedersen_compress_separated(NULLIFIER_DOMAIN_SEPARATOR, fields)
+    }
+
+    fn pedersen_compress_separated(
+        &self,
+        domain_separator: u32,
+        fields: &[FieldElement],
+    ) -> Result<FieldElement, BackendError> {
+        let inputs = std::iter::once(FieldElement::from(domain_separator as u128))
+            .chain(fields.iter().copied())
+            .collect();
+        Ok(self.compress_many(inputs)?)
+    }
+
+    /// Hashes `bytes` with Blake2s, returning the raw digest as computed by the `Blake2s` black box function.
+    pub fn blake2s(&self, bytes: &[u8]) -> [u8; 32] {
+        let mut hasher = <Blake2s as Digest>::new();
+        hasher.update(bytes);
+        hasher.finalize().into()
+    }
+
+    /// Hashes `bytes` with Blake2s and reduces the digest into a field element, matching the
+    /// `HashToField128Security` black box function.
+    pub fn blake2s_to_field(&self, bytes: &[u8]) -> FieldElement {
+        blake2s_to_field(bytes)
+    }
+
+    /// Derives the grumpkin public key for a schnorr `private_key`, encoded as the concatenation of its
+    /// big-endian `x` and `y` coordinates.
+    pub fn schnorr_compute_public_key(
+        &self,
+        private_key: &SecretScalar,
+    ) -> Result<[u8; 64], BackendError> {
+        Ok(self.construct_public_key(private_key)?)
+    }
+
+    /// Signs `message` with a schnorr `private_key`, returning the signature's `(s, e)` components.
+    pub fn schnorr_sign(
+        &self,
+        private_key: &SecretScalar,
+        message: &[u8],
+    ) -> Result<([u8; 32], [u8; 32]), BackendError> {
+        Ok(self.construct_signature(message, private_key)?)
+    }
+
+    /// Verifies a schnorr signature over `message`, with the public key encoded as by
+    /// [`schnorr_compute_public_key`][Self::schnorr_compute_public_key].
+    ///
+    /// Returns `Ok(false)` if the signature is well formed but invalid, and an error if the public key isn't on
+    /// the curve or any of the inputs has the wrong length.
+    pub fn schnorr_verify(
+        &self,
+        public_key: &[u8],
+        sig_s: &[u8],
+        sig_e: &[u8],
+        message: &[u8],
+    ) -> Result<bool, SchnorrError> {
+        self.verify_signature(public_key, sig_s, sig_e, message)
+    }
+}
+
+pub(crate) fn blake2s_to_field(bytes: &[u8]) -> FieldElement {
+    let mut hasher = <Blake2s as Digest>::new();
+    hasher.update(bytes);
+    FieldElement::from_be_bytes_reduce(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pedersen_compress_matches_compress_native() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let (left, right) = (FieldElement::zero(), FieldElement::one());
+
+        assert_eq!(
+            bb.pedersen_compress(&[left, right])?,
+            bb.compress_native(&left, &right)
+                .map_err(BackendError::from)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn byte_buffers_are_packed_into_field_elements() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let bytes: Vec<u8> = (1..=32).collect();
+
+        let mut first_element = [0; 32];
+        first_element[1..].copy_from_slice(&bytes[..31]);
+        let expected = bb.pedersen_compress(&[
+            FieldElement::from_be_bytes_reduce(&first_element),
+            FieldElement::from(32_u128),
+        ])?;
+
+        assert_eq!(bb.pedersen_compress_bytes(&bytes)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn commitments_and_nullifiers_are_domain_separated() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let fields = [FieldElement::from(7_u128), FieldElement::from(11_u128)];
+
+        let commitment = bb.compute_note_commitment(&fields)?;
+        assert_eq!(
+            commitment,
+            bb.pedersen_compress(&[
+                FieldElement::from(NOTE_COMMITMENT_DOMAIN_SEPARATOR as u128),
+                fields[0],
+                fields[1]
+            ])?
+        );
+        assert_ne!(commitment, bb.compute_nullifier(&fields)?);
+        assert_ne!(commitment, bb.pedersen_compress(&fields)?);
+        Ok(())
+    }
+
+    #[test]
+    fn blake2s_digest_matches_reference() {
+        let bb = Barretenberg::new();
+        // From the BLAKE2s-256 test vectors in RFC 7693.
+        let expected = [
+            0x50, 0x8c, 0x5e, 0x8c, 0x32, 0x7c, 0x14, 0xe2, 0xe1, 0xa7, 0x2b, 0xa3, 0x4e, 0xeb,
+            0x45, 0x2f, 0x37, 0x45, 0x8b, 0x20, 0x9e, 0xd6, 0x3a, 0x29, 0x4d, 0x99, 0x9b, 0x4c,
+            0x86, 0x67, 0x59, 0x82,
+        ];
+
+        assert_eq!(bb.blake2s(b"abc"), expected);
+        assert_eq!(
+            bb.blake2s_to_field(b"abc"),
+            FieldElement::from_be_bytes_reduce(&expected)
+        );
+    }
+
+    #[test]
+    fn signatures_verify_against_computed_public_key() -> Result<(), SchnorrError> {
+        let bb = Barretenberg::new();
+        let private_key = SecretScalar::from_bytes([2; 32]);
+        let message = b"note nullifier";
+
+        let public_key = bb.schnorr_compute_public_key(&private_key).unwrap();
+        let (sig_s, sig_e) = bb.schnorr_sign(&private_key, message).unwrap();
+
+        assert!(bb.schnorr_verify(&public_key, &sig_s, &sig_e, message)?);
+        Ok(())
+    }
+}
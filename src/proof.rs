@@ -0,0 +1,317 @@
+use acvm::FieldElement;
+
+use std::fmt;
+
+use crate::estimate::PROOF_BYTES;
+use crate::field::field_from_be_bytes;
+use crate::{BackendError, Error, FIELD_BYTES};
+
+/// The commitments at the start of an UltraPlonk proof, in the order they're written by barretenberg and read by
+/// the Solidity verifier.
+const COMMITMENT_LABELS: [&str; 11] = [
+    "W1", "W2", "W3", "W4", "S", "Z_PERM", "Z_LOOKUP", "T1", "T2", "T3", "T4",
+];
+
+/// The polynomial evaluations which follow the commitments, first at the challenge `z` and then at `z * omega`.
+const EVALUATION_LABELS: [&str; 41] = [
+    "w1_eval",
+    "w2_eval",
+    "w3_eval",
+    "w4_eval",
+    "s_eval",
+    "z_perm_eval",
+    "z_lookup_eval",
+    "q1_eval",
+    "q2_eval",
+    "q3_eval",
+    "q4_eval",
+    "qm_eval",
+    "qc_eval",
+    "q_arith_eval",
+    "q_sort_eval",
+    "q_elliptic_eval",
+    "q_aux_eval",
+    "sigma1_eval",
+    "sigma2_eval",
+    "sigma3_eval",
+    "sigma4_eval",
+    "table1_eval",
+    "table2_eval",
+    "table3_eval",
+    "table4_eval",
+    "table_type_eval",
+    "id1_eval",
+    "id2_eval",
+    "id3_eval",
+    "id4_eval",
+    "w1_omega_eval",
+    "w2_omega_eval",
+    "w3_omega_eval",
+    "w4_omega_eval",
+    "s_omega_eval",
+    "z_perm_omega_eval",
+    "z_lookup_omega_eval",
+    "table1_omega_eval",
+    "table2_omega_eval",
+    "table3_omega_eval",
+    "table4_omega_eval",
+];
+
+/// The opening proof commitments at the end of the proof.
+const OPENING_LABELS: [&str; 2] = ["PI_Z", "PI_Z_OMEGA"];
+
+/// A proof as produced by Barretenberg, with the circuit's public inputs prepended.
+///
+/// This is the layout returned by bb.js and by barretenberg's own tooling, whereas
+/// [`ProofSystemCompiler::prove_with_pk`][acvm::ProofSystemCompiler::prove_with_pk] strips the public inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proof(pub Vec<u8>);
+
+impl Proof {
+    /// Separates the first `num_public_inputs` field elements of the proof from the rest of the proof.
+    ///
+    /// The public inputs are whatever the prover embedded and haven't been verified. A verifier must check them
+    /// against the values it expects, which is what passing those values to
+    /// [`verify_with_vk`][acvm::ProofSystemCompiler::verify_with_vk] does.
+    pub fn split_public_inputs(
+        &self,
+        num_public_inputs: usize,
+    ) -> Result<(Vec<FieldElement>, &[u8]), BackendError> {
+        Ok(split_public_inputs(&self.0, num_public_inputs)?)
+    }
+
+    /// Returns `true` if the proof is the size of an UltraPlonk proof with `num_public_inputs` public inputs prepended.
+    pub fn contains_public_inputs(&self, num_public_inputs: usize) -> bool {
+        contains_public_inputs(&self.0, num_public_inputs)
+    }
+
+    /// Decodes the proof into its labelled components so that it can be compared against the values read by
+    /// another verifier, such as the Solidity verifier.
+    ///
+    /// Any data preceding the fixed size UltraPlonk proof is treated as public inputs. Each coordinate of a
+    /// commitment is listed separately, e.g. `W1.x` and `W1.y`.
+    pub fn debug_dump(&self) -> Result<Vec<ProofComponent>, BackendError> {
+        let public_inputs_len = self.0.len().checked_sub(PROOF_BYTES).ok_or_else(|| {
+            Error::MalformedProof(format!(
+                "proof of {} bytes is shorter than an UltraPlonk proof of {PROOF_BYTES} bytes",
+                self.0.len()
+            ))
+        })?;
+        if public_inputs_len % FIELD_BYTES != 0 {
+            return Err(Error::MalformedProof(format!(
+                "{public_inputs_len} bytes precede the proof which isn't a whole number of public inputs"
+            ))
+            .into());
+        }
+
+        let public_input_labels =
+            (0..public_inputs_len / FIELD_BYTES).map(|i| format!("public_input_{i}"));
+        let commitment_labels = |labels: &'static [&str]| {
+            labels
+                .iter()
+                .flat_map(|label| [format!("{label}.x"), format!("{label}.y")])
+        };
+        let labels = public_input_labels
+            .chain(commitment_labels(&COMMITMENT_LABELS))
+            .chain(EVALUATION_LABELS.iter().map(|label| label.to_string()))
+            .chain(commitment_labels(&OPENING_LABELS));
+
+        Ok(labels
+            .zip(self.0.chunks(FIELD_BYTES))
+            .map(|(label, value)| ProofComponent {
+                label,
+                value: value.to_vec(),
+            })
+            .collect())
+    }
+}
+
+/// A single labelled field element or coordinate within a proof, as returned by [`Proof::debug_dump`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofComponent {
+    pub label: String,
+    /// The big-endian bytes of the component as they appear in the proof.
+    pub value: Vec<u8>,
+}
+
+impl fmt::Display for ProofComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: 0x", self.label)?;
+        self.value
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+pub(crate) fn contains_public_inputs(proof: &[u8], num_public_inputs: usize) -> bool {
+    num_public_inputs > 0 && proof.len() == PROOF_BYTES + num_public_inputs * FIELD_BYTES
+}
+
+pub(crate) fn split_public_inputs(
+    proof: &[u8],
+    num_public_inputs: usize,
+) -> Result<(Vec<FieldElement>, &[u8]), Error> {
+    let public_inputs_len = num_public_inputs * FIELD_BYTES;
+    if proof.len() < public_inputs_len {
+        return Err(Error::MalformedProof(format!(
+            "proof of {} bytes is too short to contain {num_public_inputs} public inputs",
+            proof.len()
+        )));
+    }
+
+    let (public_inputs, proof) = proof.split_at(public_inputs_len);
+    let public_inputs = public_inputs
+        .chunks(FIELD_BYTES)
+        .map(|public_input| {
+            field_from_be_bytes(public_input).map_err(|_| {
+                Error::MalformedProof("public input is not less than the field modulus".to_owned())
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((public_inputs, proof))
+}
+
+/// Removes the public inputs from the head of `proof` if they were prepended by barretenberg, returning `None` if
+/// they differ from the `expected` values.
+///
+/// The embedded values are chosen by the prover, so they're only ever compared against the values the verifier
+/// expects and are never used in their place. Fails if the proof contains public inputs but `expected` doesn't
+/// hold a value for each of them.
+pub(crate) fn strip_public_inputs<'a>(
+    proof: &'a [u8],
+    num_public_inputs: usize,
+    expected: &[FieldElement],
+) -> Result<Option<&'a [u8]>, Error> {
+    if !contains_public_inputs(proof, num_public_inputs) {
+        return Ok(Some(proof));
+    }
+    if expected.len() != num_public_inputs {
+        return Err(Error::PublicInputCountMismatch {
+            expected: num_public_inputs as u32,
+            actual: expected.len(),
+        });
+    }
+
+    let (embedded_public_inputs, proof) = split_public_inputs(proof, num_public_inputs)?;
+    Ok((embedded_public_inputs == expected).then_some(proof))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use acvm::acir::circuit::PublicInputs;
+    use acvm::acir::native_types::Witness;
+    use acvm::pwg::block::Blocks;
+    use acvm::{PartialWitnessGenerator, ProofSystemCompiler};
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+    use crate::Barretenberg;
+
+    #[test]
+    fn splits_public_inputs() -> Result<(), BackendError> {
+        let public_inputs = [FieldElement::from(3_u128), -FieldElement::one()];
+        let mut bytes: Vec<u8> = public_inputs
+            .iter()
+            .flat_map(FieldElement::to_be_bytes)
+            .collect();
+        bytes.extend_from_slice(&[7; PROOF_BYTES]);
+        let proof = Proof(bytes);
+
+        assert!(proof.contains_public_inputs(2));
+        assert!(!proof.contains_public_inputs(1));
+        let (split_public_inputs, rest) = proof.split_public_inputs(2)?;
+        assert_eq!(split_public_inputs, public_inputs);
+        assert_eq!(rest, &[7; PROOF_BYTES][..]);
+
+        assert!(proof.split_public_inputs(100).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn labels_every_proof_component() -> Result<(), BackendError> {
+        let mut bytes = FieldElement::from(3_u128).to_be_bytes();
+        bytes.extend((0..PROOF_BYTES / FIELD_BYTES).flat_map(|i| [i as u8; FIELD_BYTES]));
+        let proof = Proof(bytes);
+
+        let components = proof.debug_dump()?;
+
+        assert_eq!(components.len(), 1 + PROOF_BYTES / FIELD_BYTES);
+        assert_eq!(
+            components[0].to_string(),
+            format!("public_input_0: 0x{}", "0".repeat(63) + "3")
+        );
+        assert_eq!(components[1].label, "W1.x");
+        assert_eq!(components[1].value, vec![0; FIELD_BYTES]);
+        assert_eq!(components[23].label, "w1_eval");
+        assert_eq!(components[23].value, vec![22; FIELD_BYTES]);
+        assert_eq!(components.last().unwrap().label, "PI_Z_OMEGA.y");
+
+        assert!(Proof(vec![0; PROOF_BYTES - 1]).debug_dump().is_err());
+        assert!(Proof(vec![0; PROOF_BYTES + 1]).debug_dump().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn verifies_proofs_containing_public_inputs() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let mut test_circuit = arithmetic_gates(2);
+        let output = Witness(test_circuit.circuit.current_witness_index);
+        test_circuit.circuit.return_values = PublicInputs(BTreeSet::from([output]));
+        let circuit = test_circuit.circuit;
+
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .unwrap();
+        let (proving_key, verification_key) = bb.preprocess(&circuit)?;
+        let proof = bb.prove_with_pk(&circuit, witness.clone(), &proving_key)?;
+
+        let mut proof_with_public_inputs = witness[&output].to_be_bytes();
+        proof_with_public_inputs.extend_from_slice(&proof);
+        let public_inputs = BTreeMap::from([(output, witness[&output])]);
+        let wrong_public_inputs = BTreeMap::from([(output, FieldElement::one())]);
+
+        assert!(bb.verify_with_vk(&proof, public_inputs.clone(), &circuit, &verification_key)?);
+        for (public_inputs, expected) in [(public_inputs, true), (wrong_public_inputs, false)] {
+            assert_eq!(
+                bb.verify_with_vk(
+                    &proof_with_public_inputs,
+                    public_inputs,
+                    &circuit,
+                    &verification_key
+                )?,
+                expected
+            );
+        }
+
+        // The verifier must say which public inputs it expects rather than accepting the prover's.
+        assert!(bb
+            .verify_with_vk(
+                &proof_with_public_inputs,
+                BTreeMap::new(),
+                &circuit,
+                &verification_key
+            )
+            .is_err());
+
+        let mut non_canonical_public_input = proof_with_public_inputs;
+        non_canonical_public_input[..FIELD_BYTES].copy_from_slice(&[0xff; FIELD_BYTES]);
+        assert!(bb
+            .verify_with_vk(
+                &non_canonical_public_input,
+                BTreeMap::from([(
+                    output,
+                    FieldElement::from_be_bytes_reduce(&[0xff; FIELD_BYTES])
+                )]),
+                &circuit,
+                &verification_key
+            )
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+
+use crate::composer::Composer;
+use crate::secret::zeroize_field_elements;
+use crate::{BackendError, Barretenberg, Error, Proof, ProvingKey, WitnessVector};
+
+/// A stage of [`Barretenberg::prove_streaming`] which has completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProveStage {
+    /// A chunk of the witness has been received, `num_values` being the number of values received so far.
+    WitnessChunkReceived { num_values: usize },
+    /// Every chunk has been received and the witness has been laid out for barretenberg.
+    WitnessComplete,
+    /// The circuit has been converted into barretenberg's constraint system.
+    ConstraintSystemBuilt,
+    /// The proof has been created.
+    ProofCreated { proof_bytes: usize },
+}
+
+impl Barretenberg {
+    /// Creates a proof in the same manner as [`prove_with_pk`][acvm::ProofSystemCompiler::prove_with_pk] from a
+    /// witness which arrives in chunks, e.g. as it's read from a request body.
+    ///
+    /// Each chunk is copied into a [`WitnessVector`] as it arrives, so the whole witness map is never held at once.
+    /// `on_stage` is called as each stage completes and can return [`ControlFlow::Break`] to abandon the proof,
+    /// e.g. once a deadline has passed. Barretenberg creates the proof in a single call which can't be interrupted,
+    /// so the proof is returned even if `on_stage` breaks at [`ProveStage::ProofCreated`].
+    pub fn prove_streaming(
+        &self,
+        circuit: &Circuit,
+        witness_chunks: impl IntoIterator<Item = BTreeMap<Witness, FieldElement>>,
+        proving_key: &ProvingKey,
+        mut on_stage: impl FnMut(ProveStage) -> ControlFlow<()>,
+    ) -> Result<Proof, BackendError> {
+        let mut report = |stage| match on_stage(stage) {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(Error::ProveCancelled),
+        };
+
+        let mut witness_values = WitnessVector::for_circuit(circuit);
+        for mut chunk in witness_chunks {
+            for (witness, value) in &chunk {
+                witness_values.insert(*witness, *value);
+            }
+            zeroize_field_elements(chunk.values_mut());
+            if let Err(err) = report(ProveStage::WitnessChunkReceived {
+                num_values: witness_values.len(),
+            }) {
+                witness_values.zeroize();
+                return Err(err.into());
+            }
+        }
+
+        let mut assignments = witness_values.to_assignments(circuit.num_vars());
+        witness_values.zeroize();
+
+        let constraint_system = report(ProveStage::WitnessComplete)
+            .and_then(|()| self.constraint_system(circuit))
+            .and_then(|constraint_system| {
+                report(ProveStage::ConstraintSystemBuilt)?;
+                Ok(constraint_system)
+            });
+        let constraint_system = match constraint_system {
+            Ok(constraint_system) => constraint_system,
+            Err(err) => {
+                // The assignments are only wiped by `create_proof_with_pk` so must be wiped here if it isn't reached.
+                assignments.zeroize();
+                return Err(err.into());
+            }
+        };
+
+        let proof =
+            self.create_proof_with_pk(&constraint_system, assignments, proving_key.as_ref())?;
+        // The proof is finished so there's nothing left to abandon.
+        let _ = report(ProveStage::ProofCreated {
+            proof_bytes: proof.len(),
+        });
+        Ok(Proof(proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::pwg::block::Blocks;
+    use acvm::PartialWitnessGenerator;
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    #[test]
+    fn proves_from_witness_chunks() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let test_circuit = arithmetic_gates(2);
+        let circuit = test_circuit.circuit;
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .unwrap();
+        let (proving_key, verification_key) = bb.preprocess_keys(&circuit)?;
+
+        let (first_half, second_half): (BTreeMap<_, _>, BTreeMap<_, _>) = witness
+            .clone()
+            .into_iter()
+            .partition(|(witness, _)| witness.witness_index() % 2 == 0);
+        let mut stages = Vec::new();
+        let proof =
+            bb.prove_streaming(&circuit, [first_half, second_half], &proving_key, |stage| {
+                stages.push(stage);
+                ControlFlow::Continue(())
+            })?;
+
+        assert_eq!(stages.len(), 5);
+        assert_eq!(
+            stages[1],
+            ProveStage::WitnessChunkReceived {
+                num_values: witness.len()
+            }
+        );
+        assert!(bb.verify(&proof, BTreeMap::new(), &circuit, &verification_key)?);
+
+        for abandon_at in [
+            ProveStage::WitnessComplete,
+            ProveStage::ConstraintSystemBuilt,
+        ] {
+            let abandoned =
+                bb.prove_streaming(&circuit, [witness.clone()], &proving_key, |stage| {
+                    if stage == abandon_at {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                });
+            assert!(abandoned.is_err());
+        }
+
+        let finished =
+            bb.prove_streaming(&circuit, [witness], &proving_key, |stage| match stage {
+                ProveStage::ProofCreated { .. } => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            })?;
+        assert!(bb.verify(&finished, BTreeMap::new(), &circuit, &verification_key)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+
+use crate::{
+    BackendError, BackendOptions, Barretenberg, BarretenbergPool, Error, Proof, ProvingKey,
+    SecretWitness,
+};
+
+/// A circuit and its proving key which have been registered with a [`ProverPool`].
+///
+/// Cloning the handle is cheap, the circuit and key are shared between all jobs which prove it.
+#[derive(Debug, Clone)]
+pub struct CircuitId(Arc<RegisteredCircuit>);
+
+#[derive(Debug)]
+struct RegisteredCircuit {
+    circuit: Circuit,
+    proving_key: ProvingKey,
+}
+
+/// The result of a proving job, which is sent once the job has been run by one of the pool's workers.
+pub type ProofReceiver = Receiver<Result<Proof, BackendError>>;
+
+/// A set of worker threads, sharing a [`BarretenbergPool`] with one instance per worker, which prove jobs in
+/// priority order.
+///
+/// Jobs with a higher priority are started first, and jobs of equal priority are started in the order in which
+/// they were submitted. A job which panics fails with an error and its worker moves on to the next job. Dropping
+/// the pool waits for running jobs to finish and discards any jobs which haven't started, whose receivers are
+/// then disconnected.
+#[derive(Debug)]
+pub struct ProverPool {
+    shared: Arc<SharedQueue>,
+    backends: Arc<BarretenbergPool>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+#[derive(Debug, Default)]
+struct SharedQueue {
+    queue: Mutex<JobQueue>,
+    job_available: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct JobQueue {
+    jobs: BinaryHeap<Job>,
+    next_sequence_number: u64,
+    shutting_down: bool,
+}
+
+#[derive(Debug)]
+struct Job {
+    priority: u32,
+    sequence_number: u64,
+    circuit: CircuitId,
+    // Wiped when the job is dropped, whether or not it was run.
+    witness: SecretWitness,
+    result: Sender<Result<Proof, BackendError>>,
+}
+
+impl PartialEq for Job {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Job {}
+
+impl PartialOrd for Job {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Job {
+    /// Orders jobs such that the greatest (i.e. the next to be popped from the heap) is the earliest submitted
+    /// job of the highest priority.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence_number.cmp(&self.sequence_number))
+    }
+}
+
+impl ProverPool {
+    /// Starts a pool of `num_workers` threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_workers` is zero.
+    pub fn new(num_workers: usize) -> ProverPool {
+        ProverPool::start(Barretenberg::pooled(num_workers))
+    }
+
+    /// Starts a pool of `num_workers` threads whose backends are created with `options`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_workers` is zero.
+    pub fn with_options(
+        num_workers: usize,
+        options: BackendOptions,
+    ) -> Result<ProverPool, BackendError> {
+        Ok(ProverPool::start(Barretenberg::pooled_with_options(
+            num_workers,
+            options,
+        )?))
+    }
+
+    fn start(backends: BarretenbergPool) -> ProverPool {
+        let shared = Arc::new(SharedQueue::default());
+        let backends = Arc::new(backends);
+        let workers = (0..backends.len())
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                let backends = Arc::clone(&backends);
+                thread::spawn(move || run_worker(&shared, &backends))
+            })
+            .collect();
+
+        ProverPool {
+            shared,
+            backends,
+            workers,
+        }
+    }
+
+    /// Registers `circuit` so that proofs can be requested for it, checking that `proving_key` belongs to it.
+    ///
+    /// The key is checked with [`Barretenberg::validate_proving_key`], so a key which is malformed or was
+    /// generated for a circuit of a different shape is rejected here rather than failing each job.
+    pub fn register_circuit(
+        &self,
+        circuit: Circuit,
+        proving_key: ProvingKey,
+    ) -> Result<CircuitId, BackendError> {
+        self.backends
+            .with(|bb| bb.validate_proving_key(&circuit, &proving_key))?;
+
+        Ok(CircuitId(Arc::new(RegisteredCircuit {
+            circuit,
+            proving_key,
+        })))
+    }
+
+    /// Queues a job to prove `circuit` with the solved `witness`, returning a receiver for the resulting proof.
+    ///
+    /// Jobs with a higher `priority` are started before those with a lower one.
+    pub fn submit(
+        &self,
+        circuit: &CircuitId,
+        witness: BTreeMap<Witness, FieldElement>,
+        priority: u32,
+    ) -> ProofReceiver {
+        let (sender, receiver) = mpsc::channel();
+
+        let mut queue = self.shared.lock();
+        let sequence_number = queue.next_sequence_number;
+        queue.next_sequence_number += 1;
+        queue.jobs.push(Job {
+            priority,
+            sequence_number,
+            circuit: circuit.clone(),
+            witness: SecretWitness::new(witness),
+            result: sender,
+        });
+        drop(queue);
+        self.shared.job_available.notify_one();
+
+        receiver
+    }
+
+    /// Returns the number of jobs which are waiting for a worker.
+    pub fn queued_jobs(&self) -> usize {
+        self.shared.lock().jobs.len()
+    }
+
+    /// Returns the number of worker threads in the pool.
+    pub fn num_workers(&self) -> usize {
+        self.workers.len()
+    }
+}
+
+impl Drop for ProverPool {
+    fn drop(&mut self) {
+        {
+            let mut queue = self.shared.lock();
+            queue.shutting_down = true;
+            queue.jobs.clear();
+        }
+        self.shared.job_available.notify_all();
+
+        for worker in self.workers.drain(..) {
+            // Panics are caught and reported for each job, so workers always exit cleanly.
+            let _ = worker.join();
+        }
+    }
+}
+
+impl SharedQueue {
+    // Jobs are only pushed and popped whilst holding the lock so the queue remains consistent after a panic.
+    fn lock(&self) -> std::sync::MutexGuard<'_, JobQueue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn run_worker(shared: &SharedQueue, backends: &BarretenbergPool) {
+    loop {
+        let job = {
+            let mut queue = shared.lock();
+            loop {
+                if queue.shutting_down {
+                    return;
+                }
+                if let Some(job) = queue.jobs.pop() {
+                    break job;
+                }
+                queue = shared
+                    .job_available
+                    .wait(queue)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+        };
+
+        let RegisteredCircuit {
+            circuit,
+            proving_key,
+        } = &*job.circuit.0;
+        let proof = panic::catch_unwind(AssertUnwindSafe(|| {
+            backends.with(|bb| bb.prove_with_secret_witness(circuit, &job.witness, proving_key))
+        }))
+        .unwrap_or_else(|panic| Err(Error::ProverPanicked(panic_message(&*panic)).into()));
+        // The caller may have dropped the receiver if they're no longer interested in the proof.
+        let _ = job.result.send(proof);
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::pwg::block::Blocks;
+    use acvm::PartialWitnessGenerator;
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    fn job(priority: u32, sequence_number: u64, circuit: &CircuitId) -> Job {
+        Job {
+            priority,
+            sequence_number,
+            circuit: circuit.clone(),
+            witness: SecretWitness::default(),
+            result: mpsc::channel().0,
+        }
+    }
+
+    #[test]
+    fn jobs_are_ordered_by_priority_then_submission() {
+        let circuit = CircuitId(Arc::new(RegisteredCircuit {
+            circuit: arithmetic_gates(1).circuit,
+            proving_key: ProvingKey::from_bytes(Vec::new()),
+        }));
+        let mut jobs = BinaryHeap::from([
+            job(1, 0, &circuit),
+            job(5, 1, &circuit),
+            job(1, 2, &circuit),
+            job(5, 3, &circuit),
+        ]);
+
+        let order: Vec<_> = std::iter::from_fn(|| jobs.pop())
+            .map(|job| job.sequence_number)
+            .collect();
+        assert_eq!(order, vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    fn proves_submitted_jobs() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let test_circuit = arithmetic_gates(3);
+        let (proving_key, verification_key) = bb.preprocess_keys(&test_circuit.circuit)?;
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            test_circuit.circuit.opcodes.clone(),
+        )
+        .unwrap();
+
+        let pool = ProverPool::with_options(2, BackendOptions::default())?;
+        let mut truncated_key = proving_key.clone().into_bytes();
+        truncated_key.truncate(truncated_key.len() / 2);
+        assert!(pool
+            .register_circuit(
+                test_circuit.circuit.clone(),
+                ProvingKey::from_bytes(truncated_key)
+            )
+            .is_err());
+
+        let circuit = pool.register_circuit(test_circuit.circuit.clone(), proving_key)?;
+        let receivers: Vec<_> = (0..4)
+            .map(|priority| pool.submit(&circuit, witness.clone(), priority))
+            .collect();
+
+        for receiver in receivers {
+            let proof = receiver.recv().expect("worker should send a result")?;
+            assert!(bb.verify(
+                &proof,
+                BTreeMap::new(),
+                &test_circuit.circuit,
+                &verification_key
+            )?);
+        }
+        Ok(())
+    }
+}
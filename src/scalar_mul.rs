@@ -1,17 +1,15 @@
 use acvm::FieldElement;
 
-use super::{Barretenberg, Error, FIELD_BYTES};
+use super::{Barretenberg, Error, SecretScalar, FIELD_BYTES};
 
 pub(crate) trait ScalarMul {
-    fn fixed_base(&self, input: &FieldElement) -> Result<(FieldElement, FieldElement), Error>;
+    fn fixed_base(&self, input: &SecretScalar) -> Result<(FieldElement, FieldElement), Error>;
 }
 
 #[cfg(feature = "native")]
 impl ScalarMul for Barretenberg {
-    fn fixed_base(&self, input: &FieldElement) -> Result<(FieldElement, FieldElement), Error> {
-        use super::native::field_to_array;
-
-        let result_bytes = barretenberg_sys::schnorr::construct_public_key(&field_to_array(input)?);
+    fn fixed_base(&self, input: &SecretScalar) -> Result<(FieldElement, FieldElement), Error> {
+        let result_bytes = barretenberg_sys::schnorr::construct_public_key(input.as_bytes());
 
         let (pubkey_x_bytes, pubkey_y_bytes) = result_bytes.split_at(FIELD_BYTES);
         assert!(pubkey_x_bytes.len() == FIELD_BYTES);
@@ -25,15 +23,18 @@ impl ScalarMul for Barretenberg {
 
 #[cfg(not(feature = "native"))]
 impl ScalarMul for Barretenberg {
-    fn fixed_base(&self, input: &FieldElement) -> Result<(FieldElement, FieldElement), Error> {
+    fn fixed_base(&self, input: &SecretScalar) -> Result<(FieldElement, FieldElement), Error> {
         let lhs_ptr: usize = 0;
         let result_ptr: usize = lhs_ptr + FIELD_BYTES;
-        self.transfer_to_heap(&input.to_be_bytes(), lhs_ptr);
+        self.transfer_to_heap(input.as_bytes(), lhs_ptr);
 
-        self.call_multiple(
+        // This doesn't unwrap the result because we need to wipe the scalar even if there is a failure
+        let result = self.call_multiple(
             "compute_public_key",
             vec![&lhs_ptr.into(), &result_ptr.into()],
-        )?;
+        );
+        self.wipe_scratch(lhs_ptr, FIELD_BYTES);
+        result?;
 
         let result_bytes: [u8; 2 * FIELD_BYTES] = self.read_memory(result_ptr);
         let (pubkey_x_bytes, pubkey_y_bytes) = result_bytes.split_at(FIELD_BYTES);
@@ -55,7 +56,7 @@ mod test {
         let barretenberg = Barretenberg::new();
         let input = FieldElement::one();
 
-        let res = barretenberg.fixed_base(&input)?;
+        let res = barretenberg.fixed_base(&SecretScalar::from_field(&input))?;
         let x = "0000000000000000000000000000000000000000000000000000000000000001";
         let y = "0000000000000002cf135e7506a45d632d270d45f1181294833fc48d823f272c";
 
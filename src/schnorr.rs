@@ -1,19 +1,76 @@
-use super::{Barretenberg, Error};
+use thiserror::Error;
+
+use super::{BackendError, Barretenberg, Error, SecretScalar, FIELD_BYTES};
+use crate::grumpkin::GrumpkinPoint;
+
+/// Why a schnorr signature couldn't be checked, as opposed to having been checked and found invalid.
+#[derive(Debug, Error)]
+pub enum SchnorrError {
+    #[error("The public key is not a point on the Grumpkin curve")]
+    InvalidPublicKey,
+
+    #[error("The {component} should be {expected} bytes but was {actual}")]
+    InvalidLength {
+        component: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error(transparent)]
+    Backend(#[from] BackendError),
+}
+
+impl From<Error> for SchnorrError {
+    fn from(err: Error) -> SchnorrError {
+        SchnorrError::Backend(err.into())
+    }
+}
+
+/// Checks the encoding of a public key and signature before they're passed to barretenberg, which would otherwise
+/// report a malformed key as an invalid signature.
+fn check_signature_inputs(
+    pub_key: &[u8],
+    sig_s: &[u8],
+    sig_e: &[u8],
+) -> Result<([u8; 64], [u8; 32], [u8; 32]), SchnorrError> {
+    fn fixed_length<const N: usize>(
+        component: &'static str,
+        bytes: &[u8],
+    ) -> Result<[u8; N], SchnorrError> {
+        bytes.try_into().map_err(|_| SchnorrError::InvalidLength {
+            component,
+            expected: N,
+            actual: bytes.len(),
+        })
+    }
+
+    let pub_key: [u8; 2 * FIELD_BYTES] = fixed_length("public key", pub_key)?;
+    match GrumpkinPoint::from_bytes(&pub_key) {
+        Ok(point) if !point.is_infinity() => (),
+        _ => return Err(SchnorrError::InvalidPublicKey),
+    }
+    Ok((
+        pub_key,
+        fixed_length("signature's s", sig_s)?,
+        fixed_length("signature's e", sig_e)?,
+    ))
+}
 
 pub(crate) trait SchnorrSig {
     fn construct_signature(
         &self,
         message: &[u8],
-        private_key: [u8; 32],
+        private_key: &SecretScalar,
     ) -> Result<([u8; 32], [u8; 32]), Error>;
-    fn construct_public_key(&self, private_key: [u8; 32]) -> Result<[u8; 64], Error>;
+    fn construct_public_key(&self, private_key: &SecretScalar) -> Result<[u8; 64], Error>;
+    /// Returns whether the signature is valid, or an error if the public key or signature is malformed.
     fn verify_signature(
         &self,
-        pub_key: [u8; 64],
-        sig_s: [u8; 32],
-        sig_e: [u8; 32],
+        pub_key: &[u8],
+        sig_s: &[u8],
+        sig_e: &[u8],
         message: &[u8],
-    ) -> Result<bool, Error>;
+    ) -> Result<bool, SchnorrError>;
 }
 
 #[cfg(feature = "native")]
@@ -21,27 +78,30 @@ impl SchnorrSig for Barretenberg {
     fn construct_signature(
         &self,
         message: &[u8],
-        private_key: [u8; 32],
+        private_key: &SecretScalar,
     ) -> Result<([u8; 32], [u8; 32]), Error> {
+        // `barretenberg-sys` takes the key by value, so the copy made for this call isn't wiped.
         Ok(barretenberg_sys::schnorr::construct_signature(
             message,
-            private_key,
+            *private_key.as_bytes(),
         ))
     }
 
-    fn construct_public_key(&self, private_key: [u8; 32]) -> Result<[u8; 64], Error> {
+    fn construct_public_key(&self, private_key: &SecretScalar) -> Result<[u8; 64], Error> {
         Ok(barretenberg_sys::schnorr::construct_public_key(
-            &private_key,
+            private_key.as_bytes(),
         ))
     }
 
     fn verify_signature(
         &self,
-        pub_key: [u8; 64],
-        sig_s: [u8; 32],
-        sig_e: [u8; 32],
+        pub_key: &[u8],
+        sig_s: &[u8],
+        sig_e: &[u8],
         message: &[u8],
-    ) -> Result<bool, Error> {
+    ) -> Result<bool, SchnorrError> {
+        let (pub_key, sig_s, sig_e) = check_signature_inputs(pub_key, sig_s, sig_e)?;
+
         Ok(barretenberg_sys::schnorr::verify_signature(
             pub_key, sig_s, sig_e, message,
         ))
@@ -56,22 +116,23 @@ impl SchnorrSig for Barretenberg {
     fn construct_signature(
         &self,
         message: &[u8],
-        private_key: [u8; 32],
+        private_key: &SecretScalar,
     ) -> Result<([u8; 32], [u8; 32]), Error> {
-        use super::{wasm::WASM_SCRATCH_BYTES, FIELD_BYTES};
+        use super::wasm::WASM_SCRATCH_BYTES;
 
         let sig_s_ptr: usize = 0;
         let sig_e_ptr: usize = sig_s_ptr + FIELD_BYTES;
         let private_key_ptr: usize = sig_e_ptr + FIELD_BYTES;
-        let message_ptr: usize = private_key_ptr + private_key.len();
+        let message_ptr: usize = private_key_ptr + FIELD_BYTES;
         assert!(
             message_ptr + message.len() < WASM_SCRATCH_BYTES,
             "Message overran wasm scratch space"
         );
 
-        self.transfer_to_heap(&private_key, private_key_ptr);
+        self.transfer_to_heap(private_key.as_bytes(), private_key_ptr);
         self.transfer_to_heap(message, message_ptr);
-        self.call_multiple(
+        // This doesn't unwrap the result because we need to wipe the key even if there is a failure
+        let result = self.call_multiple(
             "construct_signature",
             vec![
                 &message_ptr.into(),
@@ -80,7 +141,9 @@ impl SchnorrSig for Barretenberg {
                 &sig_s_ptr.into(),
                 &sig_e_ptr.into(),
             ],
-        )?;
+        );
+        self.wipe_scratch(private_key_ptr, FIELD_BYTES);
+        result?;
 
         let sig_s: [u8; FIELD_BYTES] = self.read_memory(sig_s_ptr);
         let sig_e: [u8; FIELD_BYTES] = self.read_memory(sig_e_ptr);
@@ -89,31 +152,34 @@ impl SchnorrSig for Barretenberg {
     }
 
     #[allow(dead_code)]
-    fn construct_public_key(&self, private_key: [u8; 32]) -> Result<[u8; 64], Error> {
-        use super::FIELD_BYTES;
-
+    fn construct_public_key(&self, private_key: &SecretScalar) -> Result<[u8; 64], Error> {
         let private_key_ptr: usize = 0;
         let result_ptr: usize = private_key_ptr + FIELD_BYTES;
 
-        self.transfer_to_heap(&private_key, private_key_ptr);
+        self.transfer_to_heap(private_key.as_bytes(), private_key_ptr);
 
-        self.call_multiple(
+        // This doesn't unwrap the result because we need to wipe the key even if there is a failure
+        let result = self.call_multiple(
             "compute_public_key",
             vec![&private_key_ptr.into(), &result_ptr.into()],
-        )?;
+        );
+        self.wipe_scratch(private_key_ptr, FIELD_BYTES);
+        result?;
 
         Ok(self.read_memory(result_ptr))
     }
 
     fn verify_signature(
         &self,
-        pub_key: [u8; 64],
-        sig_s: [u8; 32],
-        sig_e: [u8; 32],
+        pub_key: &[u8],
+        sig_s: &[u8],
+        sig_e: &[u8],
         message: &[u8],
-    ) -> Result<bool, Error> {
+    ) -> Result<bool, SchnorrError> {
         use super::wasm::WASM_SCRATCH_BYTES;
 
+        let (pub_key, sig_s, sig_e) = check_signature_inputs(pub_key, sig_s, sig_e)?;
+
         let public_key_ptr: usize = 0;
         let sig_s_ptr: usize = public_key_ptr + pub_key.len();
         let sig_e_ptr: usize = sig_s_ptr + sig_s.len();
@@ -141,59 +207,89 @@ impl SchnorrSig for Barretenberg {
 
         // Note, currently for Barretenberg plonk, if the signature fails
         // then the whole circuit fails.
-        Ok(verified.try_into()?)
+        Ok(verified.try_into().map_err(Error::from)?)
     }
 }
 
 #[test]
-fn basic_interop() -> Result<(), Error> {
+fn basic_interop() -> Result<(), SchnorrError> {
     let barretenberg = Barretenberg::new();
 
     // First case should pass, standard procedure for Schnorr
-    let private_key = [2; 32];
+    let private_key = SecretScalar::from_bytes([2; 32]);
     let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-    let public_key = barretenberg.construct_public_key(private_key)?;
-    let (sig_s, sig_e) = barretenberg.construct_signature(&message, private_key)?;
-    let valid_signature = barretenberg.verify_signature(public_key, sig_s, sig_e, &message)?;
+    let public_key = barretenberg.construct_public_key(&private_key)?;
+    let (sig_s, sig_e) = barretenberg.construct_signature(&message, &private_key)?;
+    let valid_signature = barretenberg.verify_signature(&public_key, &sig_s, &sig_e, &message)?;
     assert!(valid_signature);
 
     // Should fail, since the messages are different
-    let private_key = [2; 32];
+    let private_key = SecretScalar::from_bytes([2; 32]);
     let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-    let public_key = barretenberg.construct_public_key(private_key)?;
-    let (sig_s, sig_e) = barretenberg.construct_signature(&message, private_key)?;
-    let valid_signature = barretenberg.verify_signature(public_key, sig_s, sig_e, &[0, 2])?;
+    let public_key = barretenberg.construct_public_key(&private_key)?;
+    let (sig_s, sig_e) = barretenberg.construct_signature(&message, &private_key)?;
+    let valid_signature = barretenberg.verify_signature(&public_key, &sig_s, &sig_e, &[0, 2])?;
     assert!(!valid_signature);
 
     // Should fail, since the signature is not valid
-    let private_key = [2; 32];
+    let private_key = SecretScalar::from_bytes([2; 32]);
     let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
     let sig_s = [1; 32];
     let sig_e = [1; 32];
 
-    let public_key = barretenberg.construct_public_key(private_key)?;
-    let valid_signature = barretenberg.verify_signature(public_key, sig_s, sig_e, &message)?;
+    let public_key = barretenberg.construct_public_key(&private_key)?;
+    let valid_signature = barretenberg.verify_signature(&public_key, &sig_s, &sig_e, &message)?;
     assert!(!valid_signature);
 
     // Should fail, since the public key does not match
-    let private_key_a = [1; 32];
-    let private_key_b = [2; 32];
+    let private_key_a = SecretScalar::from_bytes([1; 32]);
+    let private_key_b = SecretScalar::from_bytes([2; 32]);
     let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-    let public_key_b = barretenberg.construct_public_key(private_key_b)?;
-    let (sig_s, sig_e) = barretenberg.construct_signature(&message, private_key_a)?;
-    let valid_signature = barretenberg.verify_signature(public_key_b, sig_s, sig_e, &message)?;
+    let public_key_b = barretenberg.construct_public_key(&private_key_b)?;
+    let (sig_s, sig_e) = barretenberg.construct_signature(&message, &private_key_a)?;
+    let valid_signature = barretenberg.verify_signature(&public_key_b, &sig_s, &sig_e, &message)?;
     assert!(!valid_signature);
 
     // Test the first case again, to check if memory is being freed and overwritten properly
-    let private_key = [2; 32];
+    let private_key = SecretScalar::from_bytes([2; 32]);
     let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
 
-    let public_key = barretenberg.construct_public_key(private_key)?;
-    let (sig_s, sig_e) = barretenberg.construct_signature(&message, private_key)?;
-    let valid_signature = barretenberg.verify_signature(public_key, sig_s, sig_e, &message)?;
+    let public_key = barretenberg.construct_public_key(&private_key)?;
+    let (sig_s, sig_e) = barretenberg.construct_signature(&message, &private_key)?;
+    let valid_signature = barretenberg.verify_signature(&public_key, &sig_s, &sig_e, &message)?;
     assert!(valid_signature);
     Ok(())
 }
+
+#[test]
+fn malformed_inputs_are_errors() -> Result<(), SchnorrError> {
+    let barretenberg = Barretenberg::new();
+    let private_key = SecretScalar::from_bytes([2; 32]);
+    let message = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    let public_key = barretenberg.construct_public_key(&private_key)?;
+    let (sig_s, sig_e) = barretenberg.construct_signature(&message, &private_key)?;
+
+    let mut off_curve = public_key;
+    off_curve[63] ^= 1;
+    assert!(matches!(
+        barretenberg.verify_signature(&off_curve, &sig_s, &sig_e, &message),
+        Err(SchnorrError::InvalidPublicKey)
+    ));
+    assert!(matches!(
+        barretenberg.verify_signature(&[0; 64], &sig_s, &sig_e, &message),
+        Err(SchnorrError::InvalidPublicKey)
+    ));
+    assert!(matches!(
+        barretenberg.verify_signature(&public_key, &sig_s[1..], &sig_e, &message),
+        Err(SchnorrError::InvalidLength {
+            expected: 32,
+            actual: 31,
+            ..
+        })
+    ));
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{compiler_fence, Ordering};
+
+use acvm::acir::{circuit::Circuit, native_types::Witness};
+use acvm::{FieldElement, ProofSystemCompiler};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{BackendError, Barretenberg, Proof, ProvingKey, FIELD_BYTES};
+
+/// Overwrites each of `values` with zero in a manner which won't be optimized away by the compiler.
+pub(crate) fn zeroize_field_elements<'a>(values: impl IntoIterator<Item = &'a mut FieldElement>) {
+    for value in values {
+        // SAFETY: `value` is a valid, aligned and exclusive reference to a `FieldElement`.
+        unsafe { std::ptr::write_volatile(value, FieldElement::zero()) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// A witness map containing secret values which are wiped from memory when it is dropped.
+///
+/// The witness may be solved in place as `SecretWitness` dereferences to the underlying map.
+/// Its [`Debug`][std::fmt::Debug] implementation doesn't reveal any witness values.
+#[derive(Default)]
+pub struct SecretWitness(BTreeMap<Witness, FieldElement>);
+
+impl SecretWitness {
+    pub fn new(witness_values: BTreeMap<Witness, FieldElement>) -> SecretWitness {
+        SecretWitness(witness_values)
+    }
+}
+
+impl From<BTreeMap<Witness, FieldElement>> for SecretWitness {
+    fn from(witness_values: BTreeMap<Witness, FieldElement>) -> SecretWitness {
+        SecretWitness::new(witness_values)
+    }
+}
+
+impl Deref for SecretWitness {
+    type Target = BTreeMap<Witness, FieldElement>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for SecretWitness {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl std::fmt::Debug for SecretWitness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretWitness")
+            .field("num_witnesses", &self.0.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Zeroize for SecretWitness {
+    fn zeroize(&mut self) {
+        zeroize_field_elements(self.0.values_mut());
+    }
+}
+
+impl Drop for SecretWitness {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// A secret scalar, such as a schnorr private key, which is wiped from memory when it is dropped.
+///
+/// The scalar is held as the 32 big-endian bytes which barretenberg expects, so it's passed across the FFI
+/// boundary without any further conversion. Comparisons take the same time whatever the values being compared and
+/// its [`Debug`][std::fmt::Debug] implementation doesn't reveal the scalar.
+#[derive(Clone)]
+pub struct SecretScalar([u8; FIELD_BYTES]);
+
+impl SecretScalar {
+    pub fn from_bytes(bytes: [u8; FIELD_BYTES]) -> SecretScalar {
+        SecretScalar(bytes)
+    }
+
+    /// Encodes a field element as a scalar, wiping the intermediate encoding.
+    pub fn from_field(value: &FieldElement) -> SecretScalar {
+        let encoded = Zeroizing::new(value.to_be_bytes());
+        let mut bytes = [0; FIELD_BYTES];
+        // Field elements are always encoded in `FIELD_BYTES` bytes, so this copy doesn't depend on the value.
+        bytes.copy_from_slice(&encoded);
+        SecretScalar(bytes)
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8; FIELD_BYTES] {
+        &self.0
+    }
+}
+
+impl ConstantTimeEq for SecretScalar {
+    fn ct_eq(&self, other: &SecretScalar) -> Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
+impl PartialEq for SecretScalar {
+    fn eq(&self, other: &SecretScalar) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for SecretScalar {}
+
+impl std::fmt::Debug for SecretScalar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretScalar").finish_non_exhaustive()
+    }
+}
+
+impl Zeroize for SecretScalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretScalar {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Barretenberg {
+    /// Creates a proof in the same manner as [`ProofSystemCompiler::prove_with_pk`] without consuming `witness`.
+    ///
+    /// Every copy of the witness made whilst proving is wiped before returning.
+    pub fn prove_with_secret_witness(
+        &self,
+        circuit: &Circuit,
+        witness: &SecretWitness,
+        proving_key: &ProvingKey,
+    ) -> Result<Proof, BackendError> {
+        // `prove_with_pk` zeroizes the witness map which it's given.
+        let proof = self.prove_with_pk(circuit, witness.0.clone(), proving_key.as_ref())?;
+        Ok(Proof(proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zeroizes_field_elements() {
+        let mut values = vec![FieldElement::one(), FieldElement::from(12345_u128)];
+
+        zeroize_field_elements(values.iter_mut());
+
+        assert!(values.iter().all(FieldElement::is_zero));
+    }
+
+    #[test]
+    fn secret_scalars_match_field_encoding() {
+        let value = FieldElement::from(12345_u128);
+        let scalar = SecretScalar::from_field(&value);
+
+        assert_eq!(scalar.as_bytes().as_slice(), value.to_be_bytes());
+        assert_eq!(
+            scalar,
+            SecretScalar::from_bytes(value.to_be_bytes().try_into().unwrap())
+        );
+        assert_ne!(scalar, SecretScalar::from_field(&FieldElement::one()));
+        assert!(!format!("{scalar:?}").contains("12345"));
+    }
+
+    #[test]
+    fn debug_output_is_redacted() {
+        let witness = SecretWitness::new(BTreeMap::from([(
+            Witness(1),
+            FieldElement::from(12345_u128),
+        )]));
+
+        let debug_output = format!("{witness:?}");
+
+        assert!(!debug_output.contains("12345"));
+        assert!(!debug_output.contains(&FieldElement::from(12345_u128).to_hex()));
+    }
+}
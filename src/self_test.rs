@@ -0,0 +1,221 @@
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::acir::BlackBoxFunc;
+use acvm::pwg::block::Blocks;
+use acvm::{FieldElement, PartialWitnessGenerator, ProofSystemCompiler};
+
+use crate::Barretenberg;
+
+/// The outcome of a single check run by [`Barretenberg::self_test`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestCheck {
+    pub name: &'static str,
+    /// A description of what went wrong, or `None` if the check passed.
+    pub failure: Option<String>,
+    pub duration: Duration,
+}
+
+/// The results of [`Barretenberg::self_test`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Returns `true` if every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|check| check.failure.is_none())
+    }
+
+    /// Returns the checks which failed.
+    pub fn failures(&self) -> impl Iterator<Item = &SelfTestCheck> {
+        self.checks.iter().filter(|check| check.failure.is_some())
+    }
+}
+
+impl Barretenberg {
+    /// Checks that the backend works end to end, e.g. when a service starts up.
+    ///
+    /// This compares barretenberg's pedersen and blake2s hashes against known answers, then preprocesses, proves
+    /// and verifies a small circuit, which requires the SRS to be present and intact. Failures, including panics
+    /// from within barretenberg, are recorded in the report rather than returned early so that every check is
+    /// always run.
+    pub fn self_test(&self) -> SelfTestReport {
+        let checks = vec![
+            run_check("pedersen", || self.check_pedersen()),
+            run_check("blake2s", || self.check_blake2s()),
+            run_check("prove_and_verify", || self.check_prove_and_verify()),
+        ];
+        SelfTestReport { checks }
+    }
+
+    fn check_pedersen(&self) -> Result<(), String> {
+        // Taken from barretenberg's `crypto::pedersen::compress_native`.
+        let expected = FieldElement::from_hex(
+            "0x11831f49876c313f2a9ec6d8d521c7ce0b6311c852117e340bfe27fd1ac096ef",
+        )
+        .expect("known answer should be valid hex");
+
+        let hash = self
+            .pedersen_compress(&[FieldElement::zero(), FieldElement::one()])
+            .map_err(|err| err.to_string())?;
+        expect_known_answer(hash.to_hex(), expected.to_hex())
+    }
+
+    /// Proves that the blake2s hash of `abc` is the test vector from appendix B of RFC 7693.
+    ///
+    /// The witness is written out rather than solved, as the solver hashes with the `blake2` crate, so the proof
+    /// only verifies if barretenberg's blake2s constraints agree with the known answer.
+    fn check_blake2s(&self) -> Result<(), String> {
+        const MESSAGE: &[u8] = b"abc";
+        const DIGEST: [u8; 32] = [
+            0x50, 0x8c, 0x5e, 0x8c, 0x32, 0x7c, 0x14, 0xe2, 0xe1, 0xa7, 0x2b, 0xa3, 0x4e, 0xeb,
+            0x45, 0x2f, 0x37, 0x45, 0x8b, 0x20, 0x9e, 0xd6, 0x3a, 0x29, 0x4d, 0x99, 0x9b, 0x4c,
+            0x86, 0x67, 0x59, 0x82,
+        ];
+
+        // The message is held in the first witnesses, followed by the digest.
+        let witness: BTreeMap<Witness, FieldElement> = (1..)
+            .map(Witness)
+            .zip(MESSAGE.iter().chain(&DIGEST))
+            .map(|(witness, byte)| (witness, FieldElement::from(*byte as u128)))
+            .collect();
+        let num_message_bytes = MESSAGE.len() as u32;
+        let circuit = circuit(
+            witness.len() as u32,
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+                name: BlackBoxFunc::Blake2s,
+                inputs: (1..=num_message_bytes)
+                    .map(|index| FunctionInput {
+                        witness: Witness(index),
+                        num_bits: 8,
+                    })
+                    .collect(),
+                outputs: (num_message_bytes + 1..=witness.len() as u32)
+                    .map(Witness)
+                    .collect(),
+            }),
+        );
+
+        self.prove_and_verify(&circuit, witness)
+            .map_err(|err| format!("blake2s of {MESSAGE:?} did not match the known answer: {err}"))
+    }
+
+    fn check_prove_and_verify(&self) -> Result<(), String> {
+        // w1 * w1 - w2 = 0
+        let circuit = circuit(
+            2,
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![(FieldElement::one(), Witness(1), Witness(1))],
+                linear_combinations: vec![(-FieldElement::one(), Witness(2))],
+                q_c: FieldElement::zero(),
+            }),
+        );
+        let mut witness = BTreeMap::from([(Witness(1), FieldElement::from(3_u128))]);
+        self.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .map_err(|err| format!("could not solve witness: {err}"))?;
+
+        self.prove_and_verify(&circuit, witness)
+    }
+
+    fn prove_and_verify(
+        &self,
+        circuit: &Circuit,
+        witness: BTreeMap<Witness, FieldElement>,
+    ) -> Result<(), String> {
+        let (proving_key, verification_key) = self
+            .preprocess(circuit)
+            .map_err(|err| format!("could not preprocess circuit: {err}"))?;
+        let proof = self
+            .prove_with_pk(circuit, witness, &proving_key)
+            .map_err(|err| format!("could not create proof: {err}"))?;
+        let valid = self
+            .verify_with_vk(&proof, BTreeMap::new(), circuit, &verification_key)
+            .map_err(|err| format!("could not verify proof: {err}"))?;
+
+        if !valid {
+            return Err("a valid proof failed to verify".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// Returns a circuit over the witnesses `1..=num_witnesses` made up of a single opcode.
+fn circuit(num_witnesses: u32, opcode: Opcode) -> Circuit {
+    Circuit {
+        current_witness_index: num_witnesses,
+        opcodes: vec![opcode],
+        public_parameters: PublicInputs::default(),
+        return_values: PublicInputs::default(),
+    }
+}
+
+fn expect_known_answer(got: String, expected: String) -> Result<(), String> {
+    if got != expected {
+        return Err(format!("expected {expected} but got {got}"));
+    }
+    Ok(())
+}
+
+fn run_check(name: &'static str, check: impl FnOnce() -> Result<(), String>) -> SelfTestCheck {
+    let start = Instant::now();
+    let failure = match panic::catch_unwind(AssertUnwindSafe(check)) {
+        Ok(result) => result.err(),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_owned());
+            Some(format!("panicked: {message}"))
+        }
+    };
+
+    SelfTestCheck {
+        name,
+        failure,
+        duration: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_test_passes() {
+        let report = Barretenberg::new().self_test();
+
+        assert!(
+            report.passed(),
+            "{:?}",
+            report.failures().collect::<Vec<_>>()
+        );
+        assert_eq!(report.checks.len(), 3);
+    }
+
+    #[test]
+    fn reports_failures_and_panics() {
+        let failed = run_check("failed", || Err("wrong answer".to_owned()));
+        let panicked = run_check("panicked", || panic!("broken SRS"));
+        let report = SelfTestReport {
+            checks: vec![failed, panicked],
+        };
+
+        assert!(!report.passed());
+        let failures: Vec<_> = report
+            .failures()
+            .map(|check| check.failure.clone().unwrap())
+            .collect();
+        assert_eq!(failures, ["wrong answer", "panicked: broken SRS"]);
+    }
+}
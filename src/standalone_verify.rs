@@ -0,0 +1,105 @@
+//! Verifying proofs using only a verification key, for verifiers which don't have access to the circuit.
+
+use acvm::FieldElement;
+
+use crate::barretenberg_structures::ConstraintSystem;
+use crate::composer::Composer;
+use crate::proof::strip_public_inputs;
+use crate::{BackendError, Barretenberg, Error, Proof, VerificationKey};
+
+impl Barretenberg {
+    /// Verifies a proof against `verification_key` without needing the circuit it was created for.
+    ///
+    /// `public_inputs` holds the value of each public input in witness index order. As with
+    /// [`Barretenberg::verify`], `proof` may also have its public inputs prepended, in which case they must match
+    /// `public_inputs`.
+    pub fn verify_with_vk_standalone(
+        &self,
+        proof: &Proof,
+        public_inputs: Vec<FieldElement>,
+        verification_key: &VerificationKey,
+    ) -> Result<bool, BackendError> {
+        let num_public_inputs = verification_key.num_public_inputs;
+
+        if public_inputs.len() != num_public_inputs as usize {
+            return Err(Error::PublicInputCountMismatch {
+                expected: num_public_inputs,
+                actual: public_inputs.len(),
+            }
+            .into());
+        }
+        let Some(proof) =
+            strip_public_inputs(&proof.0, num_public_inputs as usize, &public_inputs)?
+        else {
+            return Ok(false);
+        };
+
+        Ok(Composer::verify_with_vk(
+            self,
+            &ConstraintSystem::public_inputs_only(num_public_inputs),
+            proof,
+            public_inputs.into(),
+            &verification_key.to_bytes(),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use acvm::acir::circuit::PublicInputs;
+    use acvm::acir::native_types::Witness;
+    use acvm::pwg::block::Blocks;
+    use acvm::PartialWitnessGenerator;
+
+    use super::*;
+    use crate::test_circuits::arithmetic_gates;
+
+    #[test]
+    fn verifies_without_circuit() -> Result<(), BackendError> {
+        let bb = Barretenberg::new();
+        let mut test_circuit = arithmetic_gates(2);
+        let output = Witness(test_circuit.circuit.current_witness_index);
+        test_circuit.circuit.return_values = PublicInputs(BTreeSet::from([output]));
+        let circuit = test_circuit.circuit;
+
+        let mut witness = test_circuit.initial_witness;
+        bb.solve(
+            &mut witness,
+            &mut Blocks::default(),
+            circuit.opcodes.clone(),
+        )
+        .unwrap();
+        let (proving_key, verification_key) = bb.preprocess_keys(&circuit)?;
+        let (proof, _) = bb.prove_with_public_inputs(&circuit, witness.clone(), &proving_key)?;
+        let output_value = witness[&output];
+
+        let proof_with_public_inputs = bb.prove(&circuit, witness, &proving_key)?;
+
+        assert!(bb.verify_with_vk_standalone(&proof, vec![output_value], &verification_key)?);
+        assert!(bb.verify_with_vk_standalone(
+            &proof_with_public_inputs,
+            vec![output_value],
+            &verification_key
+        )?);
+        assert!(!bb.verify_with_vk_standalone(
+            &proof_with_public_inputs,
+            vec![FieldElement::one()],
+            &verification_key
+        )?);
+        // The prover's embedded public inputs are never accepted in place of the verifier's.
+        assert!(bb
+            .verify_with_vk_standalone(&proof_with_public_inputs, Vec::new(), &verification_key)
+            .is_err());
+        assert!(!bb.verify_with_vk_standalone(
+            &proof,
+            vec![FieldElement::one()],
+            &verification_key
+        )?);
+        assert!(bb
+            .verify_with_vk_standalone(&proof, Vec::new(), &verification_key)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,373 @@
+//! Runs preprocessing and proving in a separate process, so that an abort inside barretenberg is reported as an
+//! error rather than killing the caller.
+//!
+//! The worker is the caller's own executable, re-run with `BARRETENBERG_SUBPROCESS_WORKER` set. Programs which
+//! use [`Barretenberg::prove_in_subprocess`] or [`Barretenberg::preprocess_in_subprocess`] must therefore call
+//! [`run_subprocess_worker`] at the start of `main`, before doing anything else.
+//!
+//! The request is written to the worker's stdin as an operation byte followed by length-prefixed fields, the first of
+//! which holds the backend's options so that the worker computes the same constraint system. The worker
+//! writes its response as a trailer at the very end of its stdout, so that anything barretenberg prints beforehand
+//! is ignored: a status byte and the length-prefixed fields or error message, followed by the length of all this as
+//! a big-endian `u64` and the magic bytes `BBSP`.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::{FieldElement, ProofSystemCompiler};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{
+    witness_map_from_cbor, witness_map_to_cbor, BackendError, BackendOptions, Barretenberg, Error,
+    Proof, ProvingKey, VerificationKey,
+};
+
+/// The environment variable which tells a process to act as a worker.
+const WORKER_ENV_VAR: &str = "BARRETENBERG_SUBPROCESS_WORKER";
+
+/// The bytes at the end of every response written by a worker.
+const RESPONSE_MAGIC: &[u8; 4] = b"BBSP";
+
+const LENGTH_BYTES: usize = 8;
+
+const PREPROCESS: u8 = 0;
+const PROVE: u8 = 1;
+
+const STATUS_OK: u8 = 0;
+const STATUS_ERROR: u8 = 1;
+
+impl Barretenberg {
+    /// Generates the same keys as [`Barretenberg::preprocess_keys`] in a worker process.
+    ///
+    /// The worker uses a backend with the same options as this one. Black box overrides and allocation observers
+    /// can't be passed to another process, so this fails if either is set.
+    pub fn preprocess_in_subprocess(
+        &self,
+        circuit: &Circuit,
+    ) -> Result<(ProvingKey, VerificationKey), BackendError> {
+        let mut request = vec![PREPROCESS];
+        write_field(
+            &mut request,
+            &WorkerOptions::new(&self.options)?.to_bytes()?,
+        );
+        write_field(&mut request, &circuit_bytes(circuit));
+
+        let mut fields = self.run_in_subprocess(&request)?.into_iter();
+        match (fields.next(), fields.next()) {
+            (Some(proving_key), Some(verification_key)) => Ok((
+                ProvingKey(proving_key),
+                VerificationKey::from_bytes(&verification_key)?,
+            )),
+            _ => Err(subprocess_failed("worker returned too few keys").into()),
+        }
+    }
+
+    /// Creates the same proof as [`ProofSystemCompiler::prove_with_pk`] in a worker process.
+    ///
+    /// The witness is passed to the worker through a pipe and is never written to disk. The worker's options are
+    /// as described for [`Barretenberg::preprocess_in_subprocess`].
+    pub fn prove_in_subprocess(
+        &self,
+        circuit: &Circuit,
+        witness_values: &BTreeMap<Witness, FieldElement>,
+        proving_key: &ProvingKey,
+    ) -> Result<Proof, BackendError> {
+        let mut request = Zeroizing::new(vec![PROVE]);
+        write_field(
+            &mut request,
+            &WorkerOptions::new(&self.options)?.to_bytes()?,
+        );
+        write_field(&mut request, &circuit_bytes(circuit));
+        write_field(
+            &mut request,
+            &Zeroizing::new(witness_map_to_cbor(witness_values)),
+        );
+        write_field(&mut request, proving_key.as_ref());
+
+        self.run_in_subprocess(&request)?
+            .into_iter()
+            .next()
+            .map(Proof)
+            .ok_or_else(|| subprocess_failed("worker returned no proof").into())
+    }
+
+    fn run_in_subprocess(&self, request: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        let program = std::env::current_exe()
+            .map_err(|err| subprocess_failed(&format!("could not locate executable: {err}")))?;
+        let mut command = Command::new(program);
+        command
+            .env(WORKER_ENV_VAR, "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .map_err(|err| subprocess_failed(&format!("could not start worker: {err}")))?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // The request is written from another thread so that a worker which fills its output pipes before reading
+        // all of it can't deadlock us.
+        let output = std::thread::scope(|scope| {
+            scope.spawn(move || {
+                // A worker which dies early closes the pipe, which is reported below as a crash.
+                let _ = stdin.write_all(request);
+            });
+            child.wait_with_output()
+        })
+        .map_err(|err| subprocess_failed(&format!("could not read worker output: {err}")))?;
+
+        match parse_response(&output.stdout) {
+            Some(response) if output.status.success() => response,
+            _ => Err(Error::BackendCrashed {
+                status: output.status.to_string(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            }),
+        }
+    }
+}
+
+/// Handles a request from [`Barretenberg::prove_in_subprocess`] or [`Barretenberg::preprocess_in_subprocess`] and
+/// exits, if the current process was started as a worker. Otherwise this returns immediately.
+pub fn run_subprocess_worker() {
+    if std::env::var_os(WORKER_ENV_VAR).is_none() {
+        return;
+    }
+
+    let mut request = Zeroizing::new(Vec::new());
+    let result = match std::io::stdin().read_to_end(&mut request) {
+        Ok(_) => handle_request(&request),
+        Err(err) => Err(subprocess_failed(&format!("could not read request: {err}")).into()),
+    };
+
+    let mut response = Vec::new();
+    match result {
+        Ok(fields) => {
+            response.push(STATUS_OK);
+            for field in fields {
+                write_field(&mut response, &field);
+            }
+        }
+        Err(err) => {
+            response.push(STATUS_ERROR);
+            write_field(&mut response, format!("{}: {err}", err.code()).as_bytes());
+        }
+    }
+    response.extend_from_slice(&(response.len() as u64).to_be_bytes());
+    response.extend_from_slice(RESPONSE_MAGIC);
+
+    let mut stdout = std::io::stdout().lock();
+    let written = stdout.write_all(&response).and_then(|()| stdout.flush());
+    std::process::exit(if written.is_ok() { 0 } else { 1 });
+}
+
+fn handle_request(mut request: &[u8]) -> Result<Vec<Vec<u8>>, BackendError> {
+    let malformed = || subprocess_failed("malformed request");
+
+    let (&operation, rest) = request.split_first().ok_or_else(malformed)?;
+    request = rest;
+    let options = read_field(&mut request).ok_or_else(malformed)?;
+    let options = WorkerOptions::from_bytes(options).ok_or_else(malformed)?;
+    let circuit_bytes = read_field(&mut request).ok_or_else(malformed)?;
+    let circuit =
+        Circuit::read(circuit_bytes).map_err(|err| Error::MalformedCircuit(err.to_string()))?;
+
+    let bb = Barretenberg::with_options(options.into())?;
+    match operation {
+        PREPROCESS => {
+            let (proving_key, verification_key) = bb.preprocess(&circuit)?;
+            Ok(vec![proving_key, verification_key])
+        }
+        PROVE => {
+            let witness_values =
+                witness_map_from_cbor(read_field(&mut request).ok_or_else(malformed)?)?;
+            let proving_key = read_field(&mut request).ok_or_else(malformed)?;
+            Ok(vec![bb.prove_with_pk(
+                &circuit,
+                witness_values,
+                proving_key,
+            )?])
+        }
+        _ => Err(malformed().into()),
+    }
+}
+
+/// The [`BackendOptions`] which are passed on to a worker.
+#[derive(Debug, Serialize, Deserialize)]
+struct WorkerOptions {
+    transcript_path: Option<PathBuf>,
+    max_memory_bytes: Option<usize>,
+    max_threads: Option<usize>,
+    aggregate_range_constraints: bool,
+    #[cfg(feature = "dev-srs")]
+    allow_dev_srs: bool,
+}
+
+impl WorkerOptions {
+    /// Fails if `options` holds callbacks, which only exist within this process.
+    fn new(options: &BackendOptions) -> Result<WorkerOptions, Error> {
+        if !options.black_box_overrides.is_empty() {
+            return Err(Error::InvalidConfig(
+                "black box overrides can't be passed to a worker process".to_owned(),
+            ));
+        }
+        if options.allocation_observer.is_some() {
+            return Err(Error::InvalidConfig(
+                "allocation observers can't be passed to a worker process".to_owned(),
+            ));
+        }
+
+        Ok(WorkerOptions {
+            transcript_path: options.transcript_path.clone(),
+            max_memory_bytes: options.max_memory_bytes,
+            max_threads: options.max_threads,
+            aggregate_range_constraints: options.aggregate_range_constraints,
+            #[cfg(feature = "dev-srs")]
+            allow_dev_srs: options.allow_dev_srs,
+        })
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        rmp_serde::to_vec(self).map_err(|err| {
+            Error::InvalidConfig(format!("could not pass options to a worker process: {err}"))
+        })
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<WorkerOptions> {
+        rmp_serde::from_slice(bytes).ok()
+    }
+}
+
+impl From<WorkerOptions> for BackendOptions {
+    fn from(options: WorkerOptions) -> BackendOptions {
+        BackendOptions {
+            transcript_path: options.transcript_path,
+            max_memory_bytes: options.max_memory_bytes,
+            max_threads: options.max_threads,
+            aggregate_range_constraints: options.aggregate_range_constraints,
+            #[cfg(feature = "dev-srs")]
+            allow_dev_srs: options.allow_dev_srs,
+            ..BackendOptions::default()
+        }
+    }
+}
+
+/// Returns the fields or error from the response trailer at the end of a worker's stdout, or `None` if there's no
+/// trailer because the worker crashed.
+fn parse_response(stdout: &[u8]) -> Option<Result<Vec<Vec<u8>>, Error>> {
+    let stdout = stdout.strip_suffix(RESPONSE_MAGIC)?;
+    let length_start = stdout.len().checked_sub(LENGTH_BYTES)?;
+    let length = u64::from_be_bytes(stdout[length_start..].try_into().ok()?);
+    let response_start = length_start.checked_sub(usize::try_from(length).ok()?)?;
+    let (&status, mut body) = stdout[response_start..length_start].split_first()?;
+
+    match status {
+        STATUS_OK => {
+            let mut fields = Vec::new();
+            while !body.is_empty() {
+                fields.push(read_field(&mut body)?.to_vec());
+            }
+            Some(Ok(fields))
+        }
+        STATUS_ERROR => {
+            let message = String::from_utf8_lossy(read_field(&mut body)?);
+            Some(Err(subprocess_failed(&message)))
+        }
+        _ => None,
+    }
+}
+
+fn circuit_bytes(circuit: &Circuit) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    circuit
+        .write(&mut bytes)
+        .expect("could not serialize circuit");
+    bytes
+}
+
+fn write_field(buffer: &mut Vec<u8>, field: &[u8]) {
+    buffer.extend_from_slice(&(field.len() as u64).to_be_bytes());
+    buffer.extend_from_slice(field);
+}
+
+fn read_field<'a>(buffer: &mut &'a [u8]) -> Option<&'a [u8]> {
+    if buffer.len() < LENGTH_BYTES {
+        return None;
+    }
+    let (length, rest) = buffer.split_at(LENGTH_BYTES);
+    let length = usize::try_from(u64::from_be_bytes(length.try_into().ok()?)).ok()?;
+    if rest.len() < length {
+        return None;
+    }
+    let (field, rest) = rest.split_at(length);
+    *buffer = rest;
+    Some(field)
+}
+
+fn subprocess_failed(message: &str) -> Error {
+    Error::SubprocessFailed(message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trailer(body: &[u8]) -> Vec<u8> {
+        let mut trailer = body.to_vec();
+        trailer.extend_from_slice(&(body.len() as u64).to_be_bytes());
+        trailer.extend_from_slice(RESPONSE_MAGIC);
+        trailer
+    }
+
+    #[test]
+    fn reads_response_after_barretenberg_output() {
+        let mut body = vec![STATUS_OK];
+        write_field(&mut body, b"proving key");
+        write_field(&mut body, b"");
+        let stdout = [b"barretenberg logging\n".as_slice(), &trailer(&body)].concat();
+
+        let fields = parse_response(&stdout).unwrap().unwrap();
+        assert_eq!(fields, vec![b"proving key".to_vec(), Vec::new()]);
+
+        let mut body = vec![STATUS_ERROR];
+        write_field(&mut body, b"BB-ERR-005: Malformed proof");
+        assert!(parse_response(&trailer(&body)).unwrap().is_err());
+    }
+
+    #[test]
+    fn options_with_callbacks_are_rejected() {
+        let options = BackendOptions {
+            aggregate_range_constraints: true,
+            max_threads: Some(2),
+            ..BackendOptions::default()
+        };
+        let bytes = WorkerOptions::new(&options).unwrap().to_bytes().unwrap();
+        let options = BackendOptions::from(WorkerOptions::from_bytes(&bytes).unwrap());
+        assert!(options.aggregate_range_constraints);
+        assert_eq!(options.max_threads, Some(2));
+
+        let options = BackendOptions::default()
+            .with_black_box_override(acvm::acir::BlackBoxFunc::AND, |_, _| {
+                Ok(acvm::OpcodeResolution::Solved)
+            });
+        assert!(matches!(
+            WorkerOptions::new(&options),
+            Err(Error::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn missing_or_truncated_trailer_is_a_crash() {
+        let mut body = vec![STATUS_OK];
+        write_field(&mut body, b"proof");
+        let stdout = trailer(&body);
+
+        assert!(parse_response(b"terminate called after throwing an instance").is_none());
+        assert!(parse_response(&stdout[1..]).is_none());
+        assert!(parse_response(&stdout[..stdout.len() - 1]).is_none());
+    }
+}
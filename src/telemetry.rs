@@ -0,0 +1,41 @@
+//! Metrics reported through the [`metrics`](https://docs.rs/metrics) facade when the `metrics` feature is enabled.
+//!
+//! Nothing is recorded until the application installs a recorder, such as a Prometheus exporter. Without the
+//! feature these functions compile to nothing.
+
+use acvm::acir::BlackBoxFunc;
+
+/// Records that a proof was generated.
+pub(crate) fn proof_generated() {
+    #[cfg(feature = "metrics")]
+    metrics::increment_counter!("barretenberg_proofs_generated_total");
+}
+
+/// Records that a proof was rejected by the verifier.
+pub(crate) fn verification_failed() {
+    #[cfg(feature = "metrics")]
+    metrics::increment_counter!("barretenberg_verification_failures_total");
+}
+
+/// Records that the solver was asked to solve a call to a black box function.
+pub(crate) fn black_box_call(name: BlackBoxFunc) {
+    #[cfg(feature = "metrics")]
+    metrics::increment_counter!("barretenberg_black_box_calls_total", "function" => name.to_string());
+    #[cfg(not(feature = "metrics"))]
+    let _ = name;
+}
+
+/// Runs `call`, recording how long it took as the latency of the barretenberg function `function`.
+pub(crate) fn time_ffi<T>(function: &'static str, call: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "metrics")]
+    let start = std::time::Instant::now();
+
+    let result = call();
+
+    #[cfg(feature = "metrics")]
+    metrics::histogram!("barretenberg_ffi_call_duration_seconds", start.elapsed(), "function" => function);
+    #[cfg(not(feature = "metrics"))]
+    let _ = function;
+
+    result
+}
@@ -0,0 +1,204 @@
+//! Generators for circuits of configurable size which exercise the different parts of the backend.
+//!
+//! These are used by the benchmarks but are also useful for downstream crates which want to test their own
+//! integrations against representative workloads.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::acir::BlackBoxFunc;
+use acvm::FieldElement;
+
+/// A circuit along with the initial witness required to solve it.
+#[derive(Debug, Clone)]
+pub struct TestCircuit {
+    pub circuit: Circuit,
+    pub initial_witness: BTreeMap<Witness, FieldElement>,
+}
+
+/// Hands out witness indices sequentially, starting from 1.
+#[derive(Default)]
+struct WitnessAllocator {
+    current_witness_index: u32,
+}
+
+impl WitnessAllocator {
+    fn next(&mut self) -> Witness {
+        self.current_witness_index += 1;
+        Witness(self.current_witness_index)
+    }
+}
+
+fn function_input(witness: Witness, num_bits: u32) -> FunctionInput {
+    FunctionInput { witness, num_bits }
+}
+
+fn build(
+    witnesses: WitnessAllocator,
+    opcodes: Vec<Opcode>,
+    return_values: BTreeSet<Witness>,
+    initial_witness: BTreeMap<Witness, FieldElement>,
+) -> TestCircuit {
+    TestCircuit {
+        circuit: Circuit {
+            current_witness_index: witnesses.current_witness_index,
+            opcodes,
+            public_parameters: PublicInputs::default(),
+            return_values: PublicInputs(return_values),
+        },
+        initial_witness,
+    }
+}
+
+/// Returns a circuit which computes `num_hashes` independent pedersen hashes of two field elements.
+pub fn pedersen_hashes(num_hashes: usize) -> TestCircuit {
+    let mut witnesses = WitnessAllocator::default();
+    let mut opcodes = Vec::with_capacity(num_hashes);
+    let mut initial_witness = BTreeMap::new();
+
+    for i in 0..num_hashes {
+        let inputs = [witnesses.next(), witnesses.next()];
+        initial_witness.insert(inputs[0], FieldElement::from(i as u128));
+        initial_witness.insert(inputs[1], FieldElement::from(i as u128 + 1));
+
+        opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::Pedersen,
+            inputs: inputs
+                .iter()
+                .map(|witness| function_input(*witness, FieldElement::max_num_bits()))
+                .collect(),
+            outputs: vec![witnesses.next(), witnesses.next()],
+        }));
+    }
+
+    build(witnesses, opcodes, BTreeSet::new(), initial_witness)
+}
+
+/// Returns a circuit which applies SHA256 `chain_length` times to a 32 byte message,
+/// with each hash taking the previous digest as its input.
+pub fn sha256_chain(chain_length: usize) -> TestCircuit {
+    const DIGEST_BYTES: usize = 32;
+
+    let mut witnesses = WitnessAllocator::default();
+    let mut opcodes = Vec::with_capacity(chain_length);
+
+    let mut message: Vec<Witness> = (0..DIGEST_BYTES).map(|_| witnesses.next()).collect();
+    let initial_witness = message
+        .iter()
+        .enumerate()
+        .map(|(i, witness)| (*witness, FieldElement::from(i as u128)))
+        .collect();
+
+    for _ in 0..chain_length {
+        let digest: Vec<Witness> = (0..DIGEST_BYTES).map(|_| witnesses.next()).collect();
+        opcodes.push(Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+            name: BlackBoxFunc::SHA256,
+            inputs: message
+                .iter()
+                .map(|witness| function_input(*witness, 8))
+                .collect(),
+            outputs: digest.clone(),
+        }));
+        message = digest;
+    }
+
+    build(
+        witnesses,
+        opcodes,
+        message.into_iter().collect(),
+        initial_witness,
+    )
+}
+
+/// Returns a circuit which computes the root of a merkle tree of the given `depth` from a leaf and its hash path.
+///
+/// The computed root is exposed as a return value.
+pub fn merkle_membership(depth: usize) -> TestCircuit {
+    let mut witnesses = WitnessAllocator::default();
+    let mut initial_witness = BTreeMap::new();
+
+    let leaf = witnesses.next();
+    initial_witness.insert(leaf, FieldElement::from(2_u128));
+    let index = witnesses.next();
+    initial_witness.insert(index, FieldElement::from(1_u128));
+
+    let mut inputs = vec![
+        function_input(leaf, FieldElement::max_num_bits()),
+        function_input(index, FieldElement::max_num_bits()),
+    ];
+    for level in 0..depth {
+        let sibling = witnesses.next();
+        initial_witness.insert(sibling, FieldElement::from(level as u128));
+        inputs.push(function_input(sibling, FieldElement::max_num_bits()));
+    }
+
+    let root = witnesses.next();
+    let opcodes = vec![Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+        name: BlackBoxFunc::ComputeMerkleRoot,
+        inputs,
+        outputs: vec![root],
+    })];
+
+    build(witnesses, opcodes, BTreeSet::from([root]), initial_witness)
+}
+
+/// Returns a circuit made up of `2^log_num_gates` arithmetic gates, each squaring the output of the last.
+pub fn arithmetic_gates(log_num_gates: u32) -> TestCircuit {
+    let mut witnesses = WitnessAllocator::default();
+
+    let mut previous = witnesses.next();
+    let initial_witness = BTreeMap::from([(previous, FieldElement::from(3_u128))]);
+
+    let opcodes = (0..1_usize << log_num_gates)
+        .map(|_| {
+            let next = witnesses.next();
+            let opcode = Opcode::Arithmetic(Expression {
+                mul_terms: vec![(FieldElement::one(), previous, previous)],
+                linear_combinations: vec![(-FieldElement::one(), next)],
+                q_c: FieldElement::zero(),
+            });
+            previous = next;
+            opcode
+        })
+        .collect();
+
+    build(witnesses, opcodes, BTreeSet::new(), initial_witness)
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::pwg::block::Blocks;
+    use acvm::{PartialWitnessGenerator, PartialWitnessGeneratorStatus};
+
+    use super::*;
+    use crate::Barretenberg;
+
+    #[test]
+    fn generated_circuits_are_solvable() {
+        let bb = Barretenberg::new();
+
+        for test_circuit in [
+            pedersen_hashes(4),
+            sha256_chain(2),
+            merkle_membership(4),
+            arithmetic_gates(3),
+        ] {
+            let mut witness = test_circuit.initial_witness;
+            let status = bb
+                .solve(
+                    &mut witness,
+                    &mut Blocks::default(),
+                    test_circuit.circuit.opcodes,
+                )
+                .unwrap();
+
+            assert_eq!(status, PartialWitnessGeneratorStatus::Solved);
+            assert_eq!(
+                witness.len() as u32,
+                test_circuit.circuit.current_witness_index
+            );
+        }
+    }
+}
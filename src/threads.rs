@@ -0,0 +1,57 @@
+use std::os::raw::c_int;
+
+use crate::Barretenberg;
+
+extern "C" {
+    // `barretenberg-sys` links barretenberg against OpenMP, which it uses to parallelise proving.
+    fn omp_get_max_threads() -> c_int;
+    fn omp_set_num_threads(num_threads: c_int);
+}
+
+/// Restores the OpenMP thread limit of the current thread when dropped.
+pub(crate) struct ThreadLimit {
+    previous: Option<c_int>,
+}
+
+impl Barretenberg {
+    /// Limits the number of threads barretenberg uses for calls made from the current thread to
+    /// [`max_threads`][crate::BackendOptions::max_threads], until the returned guard is dropped.
+    pub(crate) fn limit_threads(&self) -> ThreadLimit {
+        let previous = self.options.max_threads.map(|max_threads| unsafe {
+            let previous = omp_get_max_threads();
+            omp_set_num_threads(max_threads.clamp(1, c_int::MAX as usize) as c_int);
+            previous
+        });
+        ThreadLimit { previous }
+    }
+}
+
+impl Drop for ThreadLimit {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous {
+            unsafe { omp_set_num_threads(previous) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BackendOptions;
+
+    #[test]
+    fn restores_thread_limit() {
+        let bb = Barretenberg::with_options(BackendOptions {
+            max_threads: Some(1),
+            ..BackendOptions::default()
+        })
+        .unwrap();
+        let previous = unsafe { omp_get_max_threads() };
+
+        let limit = bb.limit_threads();
+        assert_eq!(unsafe { omp_get_max_threads() }, 1);
+        drop(limit);
+
+        assert_eq!(unsafe { omp_get_max_threads() }, previous);
+    }
+}
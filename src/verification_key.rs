@@ -0,0 +1,602 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+
+use crate::{BackendError, Barretenberg, Error, FIELD_BYTES};
+
+/// Barretenberg's identifier for the UltraPlonk composer.
+pub(crate) const ULTRA_COMPOSER_TYPE: u32 = 2;
+
+/// The number of public inputs used to represent an aggregation object when a key contains a recursive proof.
+const RECURSIVE_PROOF_PUBLIC_INPUT_INDICES: usize = 16;
+
+/// The labels of the commitments held in an UltraPlonk verification key, in the order of Barretenberg's
+/// polynomial manifest. This is the order in which they're written when the key is encoded as fields.
+const ULTRA_COMMITMENT_LABELS: [&str; 23] = [
+    "Q_1",
+    "Q_2",
+    "Q_3",
+    "Q_4",
+    "Q_M",
+    "Q_C",
+    "Q_ARITHMETIC",
+    "Q_SORT",
+    "Q_ELLIPTIC",
+    "Q_AUX",
+    "SIGMA_1",
+    "SIGMA_2",
+    "SIGMA_3",
+    "SIGMA_4",
+    "TABLE_1",
+    "TABLE_2",
+    "TABLE_3",
+    "TABLE_4",
+    "TABLE_TYPE",
+    "ID_1",
+    "ID_2",
+    "ID_3",
+    "ID_4",
+];
+
+/// A primitive 2^28th root of unity in BN254's scalar field, from which Barretenberg derives the root of unity of
+/// each evaluation domain.
+const TWO_ADIC_ROOT_OF_UNITY: &str =
+    "0x2a3c09f0a58a7e8500e0a7eb8ef62abc402d111e41112ed49bd61b6e725b19f0";
+
+/// The largest power of two for which BN254's scalar field has a root of unity.
+const TWO_ADICITY: u32 = 28;
+
+/// The generator of the coset used by Barretenberg's evaluation domains.
+const COSET_GENERATOR: u16 = 5;
+
+/// Commitment coordinates are split into limbs of this many bytes so that each limb fits inside of a `FieldElement`.
+const LIMB_BYTES: usize = 17;
+
+/// A point on the BN254 curve, with each coordinate encoded as 32 big-endian bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commitment {
+    pub x: [u8; 32],
+    pub y: [u8; 32],
+}
+
+/// A structured view over the verification keys produced by Barretenberg.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationKey {
+    pub composer_type: u32,
+    pub circuit_size: u32,
+    pub num_public_inputs: u32,
+    pub commitments: BTreeMap<String, Commitment>,
+    pub contains_recursive_proof: bool,
+    pub recursive_proof_public_input_indices: Vec<u32>,
+}
+
+impl VerificationKey {
+    /// Parses a verification key from the binary format returned by `preprocess`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<VerificationKey, BackendError> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        let composer_type = reader.read_u32()?;
+        let circuit_size = reader.read_u32()?;
+        let num_public_inputs = reader.read_u32()?;
+
+        let num_commitments = reader.read_u32()?;
+        let mut commitments = BTreeMap::new();
+        for _ in 0..num_commitments {
+            let label_len = reader.read_u32()? as usize;
+            let label = String::from_utf8(reader.read_bytes(label_len)?.to_vec())
+                .map_err(|_| malformed("commitment label is not valid utf8"))?;
+            let x = reader.read_array()?;
+            let y = reader.read_array()?;
+            commitments.insert(label, Commitment { x, y });
+        }
+
+        let contains_recursive_proof = reader.read_bytes(1)?[0] == 1;
+
+        let num_indices = reader.read_u32()?;
+        let mut recursive_proof_public_input_indices = Vec::new();
+        for _ in 0..num_indices {
+            recursive_proof_public_input_indices.push(reader.read_u32()?);
+        }
+
+        if reader.offset != bytes.len() {
+            return Err(malformed(&format!(
+                "found {} unexpected trailing bytes",
+                bytes.len() - reader.offset
+            ))
+            .into());
+        }
+
+        Ok(VerificationKey {
+            composer_type,
+            circuit_size,
+            num_public_inputs,
+            commitments,
+            contains_recursive_proof,
+            recursive_proof_public_input_indices,
+        })
+    }
+
+    /// Serializes the verification key into the binary format accepted by `verify_with_vk`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        buffer.extend_from_slice(&self.composer_type.to_be_bytes());
+        buffer.extend_from_slice(&self.circuit_size.to_be_bytes());
+        buffer.extend_from_slice(&self.num_public_inputs.to_be_bytes());
+
+        buffer.extend_from_slice(&(self.commitments.len() as u32).to_be_bytes());
+        for (label, commitment) in self.commitments.iter() {
+            buffer.extend_from_slice(&(label.len() as u32).to_be_bytes());
+            buffer.extend_from_slice(label.as_bytes());
+            buffer.extend_from_slice(&commitment.x);
+            buffer.extend_from_slice(&commitment.y);
+        }
+
+        buffer.push(self.contains_recursive_proof as u8);
+
+        let num_indices = self.recursive_proof_public_input_indices.len() as u32;
+        buffer.extend_from_slice(&num_indices.to_be_bytes());
+        for index in self.recursive_proof_public_input_indices.iter() {
+            buffer.extend_from_slice(&index.to_be_bytes());
+        }
+
+        buffer
+    }
+
+    /// Encodes the verification key as a JSON array of hex encoded field elements, in the same format as
+    /// Barretenberg's `vk_as_fields`.
+    ///
+    /// The fields are laid out as:
+    /// - the key's [hash][VerificationKey::hash]
+    /// - the evaluation domain's root of unity, size and coset generator
+    /// - `circuit_size`, `num_public_inputs` and `contains_recursive_proof`
+    /// - the 16 recursive proof public input indices, which are all zero if `contains_recursive_proof` isn't set
+    /// - each commitment as `[x_lo, x_hi, y_lo, y_hi]` with 136 bit low limbs, in polynomial manifest order
+    pub fn to_fields_json(&self, barretenberg: &Barretenberg) -> Result<String, BackendError> {
+        let fields = self.to_fields()?;
+        let key_hash = self.hash(barretenberg)?;
+
+        let hex_fields: Vec<String> = std::iter::once(&key_hash)
+            .chain(&fields)
+            .map(|field| format!("0x{}", field.to_hex()))
+            .collect();
+        Ok(serde_json::to_string(&hex_fields).expect("a list of strings is always valid json"))
+    }
+
+    /// Decodes a verification key from the format produced by [`VerificationKey::to_fields_json`] and
+    /// Barretenberg's `vk_as_fields`, checking that the included hash matches the key.
+    pub fn from_fields_json(
+        json: &str,
+        barretenberg: &Barretenberg,
+    ) -> Result<VerificationKey, BackendError> {
+        let hex_fields: Vec<String> = serde_json::from_str(json)
+            .map_err(|err| malformed(&format!("expected a JSON array of strings ({err})")))?;
+        let fields = hex_fields
+            .iter()
+            .map(|hex| {
+                FieldElement::from_hex(hex)
+                    .ok_or_else(|| malformed(&format!("{hex} is not a valid field element")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (expected_hash, fields) = fields
+            .split_first()
+            .ok_or_else(|| malformed("missing verification key hash"))?;
+        let verification_key = VerificationKey::from_fields(fields)?;
+        if verification_key.hash(barretenberg)? != *expected_hash {
+            return Err(malformed("verification key hash does not match its fields").into());
+        }
+
+        Ok(verification_key)
+    }
+
+    /// Computes the hash by which a circuit which verifies proofs recursively commits to this key, as
+    /// Barretenberg's `verification_key_data::compress_native`.
+    ///
+    /// The hash covers the composer type, the evaluation domain, the number of public inputs and each commitment
+    /// (`y` then `x`, in label order), packed into bytes and compressed with
+    /// [`pedersen_compress_bytes`][Barretenberg::pedersen_compress_bytes].
+    pub fn hash(&self, barretenberg: &Barretenberg) -> Result<FieldElement, BackendError> {
+        let root = domain_root(self.circuit_size)?;
+        let composer_type = u8::try_from(self.composer_type)
+            .map_err(|_| malformed(&format!("unknown composer type {}", self.composer_type)))?;
+
+        let mut preimage = vec![composer_type];
+        preimage.extend_from_slice(&COSET_GENERATOR.to_be_bytes());
+        preimage.extend_from_slice(&self.circuit_size.to_be_bytes());
+        preimage.extend_from_slice(&self.num_public_inputs.to_be_bytes());
+        for commitment in self.commitments.values() {
+            preimage.extend_from_slice(&commitment.y);
+            preimage.extend_from_slice(&commitment.x);
+        }
+        preimage.extend_from_slice(&root.to_be_bytes());
+
+        barretenberg.pedersen_compress_bytes(&preimage)
+    }
+
+    /// Assigns this key to the witnesses through which an outer circuit receives the key of a proof it verifies
+    /// recursively, so that the outer circuit can be preprocessed once and used with different inner circuits.
+    ///
+    /// `key_witnesses` receive the fields laid out as by [`VerificationKey::to_fields_json`], without the hash,
+    /// and `key_hash_witness` receives the [hash][VerificationKey::hash]. Any existing values for these witnesses are replaced.
+    pub fn assign_to_witnesses(
+        &self,
+        barretenberg: &Barretenberg,
+        witness_map: &mut BTreeMap<Witness, FieldElement>,
+        key_witnesses: &[Witness],
+        key_hash_witness: Witness,
+    ) -> Result<(), BackendError> {
+        let fields = self.to_fields()?;
+        if fields.len() != key_witnesses.len() {
+            return Err(Error::KeyCircuitMismatch {
+                key: "verification key",
+                reason: format!(
+                    "the key is made up of {} fields but the circuit takes {}",
+                    fields.len(),
+                    key_witnesses.len()
+                ),
+            }
+            .into());
+        }
+
+        let key_hash = self.hash(barretenberg)?;
+        witness_map.extend(key_witnesses.iter().copied().zip(fields));
+        witness_map.insert(key_hash_witness, key_hash);
+        Ok(())
+    }
+
+    fn to_fields(&self) -> Result<Vec<FieldElement>, Error> {
+        if self.composer_type != ULTRA_COMPOSER_TYPE {
+            return Err(malformed(&format!(
+                "only UltraPlonk keys can be encoded as fields, found composer type {}",
+                self.composer_type
+            )));
+        }
+        if self.commitments.len() != ULTRA_COMMITMENT_LABELS.len()
+            || !ULTRA_COMMITMENT_LABELS
+                .iter()
+                .all(|label| self.commitments.contains_key(*label))
+        {
+            return Err(malformed("key does not contain the UltraPlonk commitments"));
+        }
+
+        let expected_indices = if self.contains_recursive_proof {
+            RECURSIVE_PROOF_PUBLIC_INPUT_INDICES
+        } else {
+            0
+        };
+        if self.recursive_proof_public_input_indices.len() != expected_indices {
+            return Err(malformed(&format!(
+                "expected {expected_indices} recursive proof public input indices but found {}",
+                self.recursive_proof_public_input_indices.len()
+            )));
+        }
+
+        let mut fields = vec![
+            domain_root(self.circuit_size)?,
+            FieldElement::from(self.circuit_size as u128),
+            FieldElement::from(COSET_GENERATOR as u128),
+            FieldElement::from(self.circuit_size as u128),
+            FieldElement::from(self.num_public_inputs as u128),
+            FieldElement::from(self.contains_recursive_proof as u128),
+        ];
+        // Barretenberg always writes every index, padding with zeros when there's no recursive proof.
+        fields.extend(
+            self.recursive_proof_public_input_indices
+                .iter()
+                .map(|index| FieldElement::from(*index as u128))
+                .chain(std::iter::repeat(FieldElement::zero()))
+                .take(RECURSIVE_PROOF_PUBLIC_INPUT_INDICES),
+        );
+        for label in ULTRA_COMMITMENT_LABELS {
+            let commitment = &self.commitments[label];
+            fields.extend(split_coordinate(&commitment.x));
+            fields.extend(split_coordinate(&commitment.y));
+        }
+
+        Ok(fields)
+    }
+
+    fn from_fields(fields: &[FieldElement]) -> Result<VerificationKey, Error> {
+        let (root, fields_after_root) = fields
+            .split_first()
+            .ok_or_else(|| malformed("missing domain root"))?;
+        let mut fields_iter = fields_after_root.iter();
+        let mut next_u32 = |name: &str| -> Result<u32, Error> {
+            let field = fields_iter
+                .next()
+                .ok_or_else(|| malformed(&format!("missing {name}")))?;
+            field_to_u32(field).ok_or_else(|| malformed(&format!("{name} does not fit in a u32")))
+        };
+
+        let domain_size = next_u32("domain size")?;
+        let generator = next_u32("domain generator")?;
+        let circuit_size = next_u32("circuit size")?;
+        let num_public_inputs = next_u32("number of public inputs")?;
+        let contains_recursive_proof = match next_u32("recursive proof flag")? {
+            0 => false,
+            1 => true,
+            _ => return Err(malformed("recursive proof flag must be 0 or 1")),
+        };
+        if domain_size != circuit_size
+            || generator != COSET_GENERATOR as u32
+            || *root != domain_root(circuit_size)?
+        {
+            return Err(malformed(&format!(
+                "evaluation domain does not match a circuit size of {circuit_size}"
+            )));
+        }
+
+        let mut recursive_proof_public_input_indices = Vec::new();
+        for _ in 0..RECURSIVE_PROOF_PUBLIC_INPUT_INDICES {
+            recursive_proof_public_input_indices
+                .push(next_u32("recursive proof public input index")?);
+        }
+        if !contains_recursive_proof {
+            if recursive_proof_public_input_indices
+                .iter()
+                .any(|index| *index != 0)
+            {
+                return Err(malformed(
+                    "recursive proof public input indices must be zero without a recursive proof",
+                ));
+            }
+            recursive_proof_public_input_indices.clear();
+        }
+
+        let header_len = fields.len() - fields_iter.len();
+        let commitment_fields = &fields[header_len..];
+        if commitment_fields.len() != ULTRA_COMMITMENT_LABELS.len() * 4 {
+            return Err(malformed(&format!(
+                "expected {} commitment limbs but found {}",
+                ULTRA_COMMITMENT_LABELS.len() * 4,
+                commitment_fields.len()
+            )));
+        }
+
+        let mut commitments = BTreeMap::new();
+        for (label, limbs) in ULTRA_COMMITMENT_LABELS
+            .iter()
+            .zip(commitment_fields.chunks_exact(4))
+        {
+            let commitment = Commitment {
+                x: join_coordinate(&limbs[0], &limbs[1])?,
+                y: join_coordinate(&limbs[2], &limbs[3])?,
+            };
+            commitments.insert(label.to_string(), commitment);
+        }
+
+        Ok(VerificationKey {
+            composer_type: ULTRA_COMPOSER_TYPE,
+            circuit_size,
+            num_public_inputs,
+            commitments,
+            contains_recursive_proof,
+            recursive_proof_public_input_indices,
+        })
+    }
+}
+
+/// Returns the root of unity which generates the evaluation domain of a circuit of `circuit_size` gates.
+fn domain_root(circuit_size: u32) -> Result<FieldElement, Error> {
+    let log_size = circuit_size.trailing_zeros();
+    if !circuit_size.is_power_of_two() || log_size > TWO_ADICITY {
+        return Err(malformed(&format!(
+            "circuit size {circuit_size} is not a supported power of two"
+        )));
+    }
+
+    let mut root =
+        FieldElement::from_hex(TWO_ADIC_ROOT_OF_UNITY).expect("root of unity should be valid hex");
+    for _ in log_size..TWO_ADICITY {
+        root = root * root;
+    }
+    Ok(root)
+}
+
+/// Splits a coordinate into its low 136 bits and the remaining high bits.
+fn split_coordinate(coordinate: &[u8; 32]) -> [FieldElement; 2] {
+    let (hi, lo) = coordinate.split_at(FIELD_BYTES - LIMB_BYTES);
+    [
+        FieldElement::from_be_bytes_reduce(lo),
+        FieldElement::from_be_bytes_reduce(hi),
+    ]
+}
+
+/// Recombines the limbs produced by `split_coordinate`.
+fn join_coordinate(lo: &FieldElement, hi: &FieldElement) -> Result<[u8; 32], Error> {
+    let lo_bytes = lo.to_be_bytes();
+    let hi_bytes = hi.to_be_bytes();
+
+    let (lo_padding, lo_limb) = lo_bytes.split_at(FIELD_BYTES - LIMB_BYTES);
+    let (hi_padding, hi_limb) = hi_bytes.split_at(LIMB_BYTES);
+    if lo_padding.iter().chain(hi_padding).any(|byte| *byte != 0) {
+        return Err(malformed("commitment limb is out of range"));
+    }
+
+    let mut coordinate = [0u8; 32];
+    coordinate[..FIELD_BYTES - LIMB_BYTES].copy_from_slice(hi_limb);
+    coordinate[FIELD_BYTES - LIMB_BYTES..].copy_from_slice(lo_limb);
+    Ok(coordinate)
+}
+
+fn field_to_u32(field: &FieldElement) -> Option<u32> {
+    if field.num_bits() > u32::BITS {
+        return None;
+    }
+    Some(field.to_u128() as u32)
+}
+
+fn malformed(reason: &str) -> Error {
+    Error::MalformedVerificationKey(reason.to_string())
+}
+
+/// A cursor over the big-endian encoding used by Barretenberg's serialization.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.offset..end))
+            .ok_or_else(|| malformed("unexpected end of verification key"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn read_array<const SIZE: usize>(&mut self) -> Result<[u8; SIZE], Error> {
+        Ok(self
+            .read_bytes(SIZE)?
+            .try_into()
+            .expect("slice should be of the requested size"))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ultra_verification_key() -> VerificationKey {
+        let commitments = ULTRA_COMMITMENT_LABELS
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let mut x = [0u8; 32];
+                let mut y = [0xffu8; 32];
+                x[0] = i as u8;
+                x[31] = 1;
+                y[0] = 0x30;
+                (label.to_string(), Commitment { x, y })
+            })
+            .collect();
+
+        VerificationKey {
+            composer_type: ULTRA_COMPOSER_TYPE,
+            circuit_size: 1 << 12,
+            num_public_inputs: 2,
+            commitments,
+            contains_recursive_proof: false,
+            recursive_proof_public_input_indices: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() -> Result<(), BackendError> {
+        let verification_key = ultra_verification_key();
+        let bytes = verification_key.to_bytes();
+
+        assert_eq!(VerificationKey::from_bytes(&bytes)?, verification_key);
+        assert!(VerificationKey::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn fields_json_round_trip() -> Result<(), BackendError> {
+        let barretenberg = Barretenberg::new();
+        let verification_key = ultra_verification_key();
+
+        let json = verification_key.to_fields_json(&barretenberg)?;
+        assert_eq!(
+            VerificationKey::from_fields_json(&json, &barretenberg)?,
+            verification_key
+        );
+
+        // Tampering with any field should invalidate the hash
+        let circuit_size = format!("0x{}", FieldElement::from(1_u128 << 12).to_hex());
+        let tampered_circuit_size = format!("0x{}", FieldElement::from(1_u128 << 13).to_hex());
+        let tampered = json.replacen(&circuit_size, &tampered_circuit_size, 1);
+        assert_ne!(tampered, json);
+        assert!(VerificationKey::from_fields_json(&tampered, &barretenberg).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn fields_follow_barretenberg_layout() -> Result<(), BackendError> {
+        let mut verification_key = ultra_verification_key();
+        verification_key.circuit_size = 1 << 16;
+        let fields = verification_key.to_fields()?;
+
+        // The work root written by Barretenberg into the Solidity verification key of a circuit with 2^16 gates
+        let root = FieldElement::from_hex(
+            "0x00eeb2cb5981ed45649abebde081dcff16c8601de4347e7dd1628ba2daac43b7",
+        )
+        .unwrap();
+        assert_eq!(fields.len(), 114);
+        assert_eq!(fields[0], root);
+        assert_eq!(fields[1], FieldElement::from(1_u128 << 16));
+        assert_eq!(fields[2], FieldElement::from(5_u128));
+        assert_eq!(fields[3], FieldElement::from(1_u128 << 16));
+        assert_eq!(fields[4], FieldElement::from(2_u128));
+        assert!(fields[5..22].iter().all(FieldElement::is_zero));
+        assert_eq!(
+            fields[22..24],
+            split_coordinate(&verification_key.commitments["Q_1"].x)
+        );
+        assert_eq!(
+            fields[110..114],
+            [
+                split_coordinate(&verification_key.commitments["ID_4"].x),
+                split_coordinate(&verification_key.commitments["ID_4"].y)
+            ]
+            .concat()
+        );
+
+        // The work root for 2^15 gates, likewise taken from a Barretenberg generated verification key
+        assert_eq!(
+            domain_root(1 << 15)?,
+            FieldElement::from_hex(
+                "0x2d1ba66f5941dc91017171fa69ec2bd0022a2a2d4115a009a93458fd4e26ecfb"
+            )
+            .unwrap()
+        );
+
+        // The domain is derived from the circuit size so must agree with it
+        let mut inconsistent_fields = fields.clone();
+        inconsistent_fields[1] = FieldElement::from(1_u128 << 15);
+        assert!(VerificationKey::from_fields(&inconsistent_fields).is_err());
+        assert_eq!(VerificationKey::from_fields(&fields)?, verification_key);
+        Ok(())
+    }
+
+    #[test]
+    fn assigns_key_to_recursion_witnesses() -> Result<(), BackendError> {
+        let barretenberg = Barretenberg::new();
+        let verification_key = ultra_verification_key();
+        let fields = verification_key.to_fields()?;
+        let key_witnesses: Vec<_> = (10..10 + fields.len() as u32).map(Witness).collect();
+        let key_hash_witness = Witness(5);
+
+        let mut witness_map = BTreeMap::from([(Witness(1), FieldElement::one())]);
+        verification_key.assign_to_witnesses(
+            &barretenberg,
+            &mut witness_map,
+            &key_witnesses,
+            key_hash_witness,
+        )?;
+
+        assert_eq!(witness_map.len(), 2 + fields.len());
+        assert_eq!(witness_map[&key_witnesses[1]], fields[1]);
+        assert_eq!(
+            witness_map[&key_hash_witness],
+            verification_key.hash(&barretenberg)?
+        );
+        assert!(verification_key
+            .assign_to_witnesses(
+                &barretenberg,
+                &mut witness_map,
+                &key_witnesses[1..],
+                key_hash_witness
+            )
+            .is_err());
+        Ok(())
+    }
+}
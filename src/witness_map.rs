@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+
+use crate::field::{field_from_be_bytes, hex_to_bytes};
+use crate::{BackendError, Error, FIELD_BYTES};
+
+/// CBOR major types used in the binary witness map encoding.
+const CBOR_UNSIGNED: u8 = 0;
+const CBOR_BYTES: u8 = 2;
+const CBOR_MAP: u8 = 5;
+
+/// Serializes a witness map as a JSON object mapping each witness index (in decimal) to its value
+/// as a `0x`-prefixed, 64 character hex string, e.g. `{"1":"0x00…05","2":"0x00…0a"}`.
+///
+/// Entries are written in ascending order of witness index.
+pub fn witness_map_to_json(witness_map: &BTreeMap<Witness, FieldElement>) -> String {
+    serde_json::to_string(&JsonWitnessMap(witness_map))
+        .expect("a map of strings is always valid json")
+}
+
+/// Parses a witness map from the JSON format produced by [`witness_map_to_json`].
+///
+/// Values must be canonical field elements, i.e. less than the field modulus.
+pub fn witness_map_from_json(json: &str) -> Result<BTreeMap<Witness, FieldElement>, BackendError> {
+    let entries: BTreeMap<String, String> = serde_json::from_str(json).map_err(|err| {
+        Error::MalformedWitnessMap(format!("expected a JSON object of strings ({err})"))
+    })?;
+
+    let witness_map = entries
+        .iter()
+        .map(|(index, value)| {
+            let witness_index: u32 = index.parse().map_err(|_| {
+                Error::MalformedWitnessMap(format!("{index} is not a valid witness index"))
+            })?;
+            let bytes = value
+                .strip_prefix("0x")
+                .and_then(hex_to_bytes)
+                .ok_or_else(|| {
+                    Error::MalformedWitnessMap(format!(
+                        "value of witness {index} is not a 0x-prefixed field element: {value}"
+                    ))
+                })?;
+            let field = field_from_be_bytes(&bytes).map_err(|_| {
+                Error::MalformedWitnessMap(format!(
+                    "value of witness {index} is not less than the field modulus"
+                ))
+            })?;
+            Ok((Witness(witness_index), field))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(witness_map)
+}
+
+/// Serializes a witness map as a CBOR map from each witness index (an unsigned integer) to its value
+/// as a 32 byte big-endian byte string.
+///
+/// This is considerably cheaper to produce and parse than [`witness_map_to_json`] for large witnesses.
+/// Entries are written in ascending order of witness index, using the shortest encoding of each length.
+pub fn witness_map_to_cbor(witness_map: &BTreeMap<Witness, FieldElement>) -> Vec<u8> {
+    let mut cbor = Vec::with_capacity(9 + witness_map.len() * (5 + 2 + FIELD_BYTES));
+    write_cbor_header(&mut cbor, CBOR_MAP, witness_map.len() as u64);
+    for (witness, value) in witness_map {
+        write_cbor_header(&mut cbor, CBOR_UNSIGNED, witness.witness_index() as u64);
+        write_cbor_header(&mut cbor, CBOR_BYTES, FIELD_BYTES as u64);
+        cbor.extend_from_slice(&value.to_be_bytes());
+    }
+    cbor
+}
+
+/// Parses a witness map from the CBOR format produced by [`witness_map_to_cbor`].
+///
+/// Values must be canonical field elements, i.e. less than the field modulus.
+pub fn witness_map_from_cbor(
+    mut cbor: &[u8],
+) -> Result<BTreeMap<Witness, FieldElement>, BackendError> {
+    let num_entries = read_cbor_header(&mut cbor, CBOR_MAP)?;
+
+    let mut witness_map = BTreeMap::new();
+    for _ in 0..num_entries {
+        let index = read_cbor_header(&mut cbor, CBOR_UNSIGNED)?;
+        let witness_index = u32::try_from(index).map_err(|_| {
+            Error::MalformedWitnessMap(format!("{index} is not a valid witness index"))
+        })?;
+
+        let len = read_cbor_header(&mut cbor, CBOR_BYTES)?;
+        if len != FIELD_BYTES as u64 || cbor.len() < FIELD_BYTES {
+            return Err(Error::MalformedWitnessMap(format!(
+                "value of witness {index} is not a {FIELD_BYTES} byte string"
+            ))
+            .into());
+        }
+        let (bytes, rest) = cbor.split_at(FIELD_BYTES);
+        cbor = rest;
+
+        let field = field_from_be_bytes(bytes).map_err(|_| {
+            Error::MalformedWitnessMap(format!(
+                "value of witness {index} is not less than the field modulus"
+            ))
+        })?;
+        witness_map.insert(Witness(witness_index), field);
+    }
+
+    if !cbor.is_empty() {
+        return Err(
+            Error::MalformedWitnessMap("unexpected data after witness map".to_owned()).into(),
+        );
+    }
+    Ok(witness_map)
+}
+
+fn write_cbor_header(cbor: &mut Vec<u8>, major_type: u8, value: u64) {
+    let major_type = major_type << 5;
+    match value {
+        0..=23 => cbor.push(major_type | value as u8),
+        24..=0xff => cbor.extend_from_slice(&[major_type | 24, value as u8]),
+        0x100..=0xffff => {
+            cbor.push(major_type | 25);
+            cbor.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            cbor.push(major_type | 26);
+            cbor.extend_from_slice(&(value as u32).to_be_bytes());
+        }
+        _ => {
+            cbor.push(major_type | 27);
+            cbor.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+}
+
+/// Reads the header of a CBOR data item of the `expected` major type, returning its argument.
+///
+/// Indefinite length items aren't supported as [`witness_map_to_cbor`] never produces them.
+fn read_cbor_header(cbor: &mut &[u8], expected: u8) -> Result<u64, Error> {
+    let truncated = || Error::MalformedWitnessMap("unexpected end of CBOR data".to_owned());
+
+    let (&initial_byte, rest) = cbor.split_first().ok_or_else(truncated)?;
+    let major_type = initial_byte >> 5;
+    if major_type != expected {
+        return Err(Error::MalformedWitnessMap(format!(
+            "expected CBOR major type {expected} but found {major_type}"
+        )));
+    }
+
+    let argument_len = match initial_byte & 0x1f {
+        value @ 0..=23 => {
+            *cbor = rest;
+            return Ok(value as u64);
+        }
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        additional_info => {
+            return Err(Error::MalformedWitnessMap(format!(
+                "unsupported CBOR additional information {additional_info}"
+            )))
+        }
+    };
+    if rest.len() < argument_len {
+        return Err(truncated());
+    }
+    let (argument, rest) = rest.split_at(argument_len);
+    *cbor = rest;
+
+    let mut bytes = [0u8; 8];
+    bytes[8 - argument_len..].copy_from_slice(argument);
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// How [`merge_witness_maps`] handles a witness which has different values in the two maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Fail the merge, leaving the target map unchanged.
+    Error,
+    /// Keep the value already in the target map.
+    KeepExisting,
+    /// Replace the value in the target map with the incoming one.
+    Overwrite,
+}
+
+/// The differences between two witness maps, as returned by [`diff_witness_maps`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitnessMapDiff {
+    /// Witnesses which only have a value in the left map.
+    pub only_in_left: BTreeMap<Witness, FieldElement>,
+    /// Witnesses which only have a value in the right map.
+    pub only_in_right: BTreeMap<Witness, FieldElement>,
+    /// Witnesses which have a value in both maps, with the left and right values respectively.
+    pub conflicting: BTreeMap<Witness, (FieldElement, FieldElement)>,
+}
+
+impl WitnessMapDiff {
+    /// Returns `true` if the two maps were identical.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_left.is_empty() && self.only_in_right.is_empty() && self.conflicting.is_empty()
+    }
+}
+
+/// Adds the values in `other` to `witness_map`, e.g. to combine partially solved witnesses from different
+/// solver runs or parties.
+///
+/// Witnesses with equal values in both maps are never treated as conflicting. With [`ConflictPolicy::Error`]
+/// the first conflicting witness is reported and `witness_map` is left unmodified.
+pub fn merge_witness_maps(
+    witness_map: &mut BTreeMap<Witness, FieldElement>,
+    other: &BTreeMap<Witness, FieldElement>,
+    policy: ConflictPolicy,
+) -> Result<(), BackendError> {
+    if policy == ConflictPolicy::Error {
+        let conflict = other.iter().find(|(witness, value)| {
+            matches!(witness_map.get(witness), Some(existing) if existing != *value)
+        });
+        if let Some((witness, _)) = conflict {
+            return Err(Error::ConflictingWitnessValue(witness.witness_index()).into());
+        }
+    }
+
+    for (witness, value) in other {
+        match policy {
+            ConflictPolicy::KeepExisting => {
+                witness_map.entry(*witness).or_insert(*value);
+            }
+            ConflictPolicy::Error | ConflictPolicy::Overwrite => {
+                witness_map.insert(*witness, *value);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Compares two witness maps, returning the witnesses which are only assigned in one of them or which are
+/// assigned different values.
+pub fn diff_witness_maps(
+    left: &BTreeMap<Witness, FieldElement>,
+    right: &BTreeMap<Witness, FieldElement>,
+) -> WitnessMapDiff {
+    let mut diff = WitnessMapDiff::default();
+    for (witness, left_value) in left {
+        match right.get(witness) {
+            None => {
+                diff.only_in_left.insert(*witness, *left_value);
+            }
+            Some(right_value) if right_value != left_value => {
+                diff.conflicting
+                    .insert(*witness, (*left_value, *right_value));
+            }
+            Some(_) => {}
+        }
+    }
+    for (witness, right_value) in right {
+        if !left.contains_key(witness) {
+            diff.only_in_right.insert(*witness, *right_value);
+        }
+    }
+    diff
+}
+
+/// Returns the values of `circuit`'s public inputs (including its return values) from a solved witness map.
+///
+/// The result is ordered by witness index, which is the order in which barretenberg expects the public inputs,
+/// and can be passed directly to [`ProofSystemCompiler::verify_with_vk`][acvm::ProofSystemCompiler::verify_with_vk].
+pub fn extract_public_inputs(
+    circuit: &Circuit,
+    witness_map: &BTreeMap<Witness, FieldElement>,
+) -> Result<BTreeMap<Witness, FieldElement>, BackendError> {
+    let public_inputs = circuit
+        .public_inputs()
+        .0
+        .into_iter()
+        .map(|witness| {
+            let value = witness_map
+                .get(&witness)
+                .ok_or(Error::MissingPublicInput(witness.witness_index()))?;
+            Ok((witness, *value))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok(public_inputs)
+}
+
+/// Serializes a witness map in witness index order.
+///
+/// Deriving `Serialize` on a `BTreeMap<String, String>` would order the entries lexicographically
+/// (e.g. `"10"` before `"2"`).
+struct JsonWitnessMap<'a>(&'a BTreeMap<Witness, FieldElement>);
+
+impl Serialize for JsonWitnessMap<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (witness, value) in self.0 {
+            map.serialize_entry(
+                &witness.witness_index().to_string(),
+                &format!("0x{}", value.to_hex()),
+            )?;
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acvm::acir::circuit::PublicInputs;
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    #[test]
+    fn witness_map_json_round_trip() -> Result<(), BackendError> {
+        let witness_map = BTreeMap::from([
+            (Witness(2), FieldElement::from(5_u128)),
+            (Witness(10), -FieldElement::one()),
+        ]);
+
+        let json = witness_map_to_json(&witness_map);
+
+        assert!(json.starts_with(&format!(r#"{{"2":"0x{}""#, "0".repeat(63) + "5")));
+        assert_eq!(witness_map_from_json(&json)?, witness_map);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_witness_maps() {
+        for json in [
+            r#"[]"#,
+            r#"{"one": "0x01"}"#,
+            r#"{"1": "01"}"#,
+            r#"{"1": "0xzz"}"#,
+            r#"{"1": "0x"}"#,
+            r#"{"1": 1}"#,
+            // The field modulus, which would otherwise be reduced to zero.
+            r#"{"1": "0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"}"#,
+        ] {
+            assert!(
+                witness_map_from_json(json).is_err(),
+                "{json} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn witness_map_cbor_round_trip() -> Result<(), BackendError> {
+        let witness_map = BTreeMap::from([
+            (Witness(2), FieldElement::from(5_u128)),
+            (Witness(300), -FieldElement::one()),
+            (Witness(u32::MAX), FieldElement::zero()),
+        ]);
+
+        let cbor = witness_map_to_cbor(&witness_map);
+
+        // A map of 3 entries, whose first key is 2 and first value is a 32 byte string.
+        assert_eq!(cbor[..4], [0xa3, 0x02, 0x58, 0x20]);
+        assert_eq!(witness_map_from_cbor(&cbor)?, witness_map);
+        assert_eq!(witness_map_from_cbor(&[0xa0])?, BTreeMap::new());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_cbor_witness_maps() {
+        let cbor = witness_map_to_cbor(&BTreeMap::from([(Witness(1), FieldElement::one())]));
+        let mut non_canonical = cbor.clone();
+        non_canonical[4..].fill(0xff);
+        let mut trailing_data = cbor.clone();
+        trailing_data.push(0);
+
+        let malformed: [&[u8]; 6] = [
+            &cbor[..cbor.len() - 1],
+            &cbor[1..],
+            &non_canonical[..],
+            &trailing_data[..],
+            // An indefinite length map.
+            &[0xbf, 0xff],
+            // A witness index which doesn't fit in a u32.
+            &[0xa1, 0x1b, 1, 0, 0, 0, 0, 0, 0, 0],
+        ];
+        for cbor in malformed {
+            assert!(
+                witness_map_from_cbor(cbor).is_err(),
+                "{cbor:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn merges_witness_maps() -> Result<(), BackendError> {
+        let existing = BTreeMap::from([
+            (Witness(1), FieldElement::one()),
+            (Witness(2), FieldElement::from(2_u128)),
+        ]);
+        let other = BTreeMap::from([
+            (Witness(1), FieldElement::one()),
+            (Witness(2), FieldElement::from(5_u128)),
+            (Witness(3), FieldElement::from(3_u128)),
+        ]);
+
+        let mut merged = existing.clone();
+        assert!(merge_witness_maps(&mut merged, &other, ConflictPolicy::Error).is_err());
+        assert_eq!(merged, existing);
+
+        merge_witness_maps(&mut merged, &other, ConflictPolicy::KeepExisting)?;
+        assert_eq!(merged[&Witness(2)], FieldElement::from(2_u128));
+        assert_eq!(merged[&Witness(3)], FieldElement::from(3_u128));
+
+        merge_witness_maps(&mut merged, &other, ConflictPolicy::Overwrite)?;
+        assert_eq!(merged, other);
+
+        // Values which agree aren't conflicts.
+        let mut agreeing = existing.clone();
+        merge_witness_maps(
+            &mut agreeing,
+            &BTreeMap::from([(Witness(1), FieldElement::one())]),
+            ConflictPolicy::Error,
+        )?;
+        assert_eq!(agreeing, existing);
+        Ok(())
+    }
+
+    #[test]
+    fn diffs_witness_maps() {
+        let left = BTreeMap::from([
+            (Witness(1), FieldElement::one()),
+            (Witness(2), FieldElement::from(2_u128)),
+        ]);
+        let right = BTreeMap::from([
+            (Witness(2), FieldElement::from(5_u128)),
+            (Witness(3), FieldElement::from(3_u128)),
+        ]);
+
+        let diff = diff_witness_maps(&left, &right);
+
+        assert_eq!(
+            diff,
+            WitnessMapDiff {
+                only_in_left: BTreeMap::from([(Witness(1), FieldElement::one())]),
+                only_in_right: BTreeMap::from([(Witness(3), FieldElement::from(3_u128))]),
+                conflicting: BTreeMap::from([(
+                    Witness(2),
+                    (FieldElement::from(2_u128), FieldElement::from(5_u128))
+                )]),
+            }
+        );
+        assert!(diff_witness_maps(&left, &left).is_empty());
+    }
+
+    #[test]
+    fn extracts_public_inputs() -> Result<(), BackendError> {
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes: vec![],
+            public_parameters: PublicInputs(BTreeSet::from([Witness(3), Witness(1)])),
+            return_values: PublicInputs(BTreeSet::from([Witness(4)])),
+        };
+        let witness_map: BTreeMap<_, _> = (1..=4)
+            .map(|index| (Witness(index), FieldElement::from(index as u128)))
+            .collect();
+
+        let public_inputs = extract_public_inputs(&circuit, &witness_map)?;
+
+        assert_eq!(
+            public_inputs.into_iter().collect::<Vec<_>>(),
+            vec![
+                (Witness(1), FieldElement::from(1_u128)),
+                (Witness(3), FieldElement::from(3_u128)),
+                (Witness(4), FieldElement::from(4_u128)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_missing_public_inputs() {
+        let circuit = Circuit {
+            current_witness_index: 2,
+            opcodes: vec![],
+            public_parameters: PublicInputs(BTreeSet::from([Witness(2)])),
+            return_values: PublicInputs::default(),
+        };
+        let witness_map = BTreeMap::from([(Witness(1), FieldElement::one())]);
+
+        assert!(extract_public_inputs(&circuit, &witness_map).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! Encrypts witness maps for a remote prover so that private inputs aren't exposed in transit.
+//!
+//! A witness map is sealed to the prover's X25519 public key: a fresh ephemeral key is agreed with the
+//! recipient's key, and the shared secret is hashed into a ChaCha20-Poly1305 key which encrypts the witness map's
+//! [CBOR encoding][crate::witness_map_to_cbor]. Only the holder of the recipient's secret key can unseal it.
+
+use std::collections::BTreeMap;
+
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+use blake2::digest::FixedOutput;
+use blake2::{Blake2s, Digest};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::framing;
+use crate::{witness_map_from_cbor, witness_map_to_cbor, BackendError, Error, SecretWitness};
+
+const PUBLIC_KEY_BYTES: usize = 32;
+
+const NONCE_BYTES: usize = 12;
+
+const HEADER_BYTES: usize = framing::HEADER_BYTES + PUBLIC_KEY_BYTES + NONCE_BYTES;
+
+/// Returns the X25519 public key to which witness maps are sealed for the holder of `secret_key`.
+pub fn sealing_public_key(secret_key: &[u8; 32]) -> [u8; 32] {
+    PublicKey::from(&StaticSecret::from(*secret_key)).to_bytes()
+}
+
+/// Encrypts `witness_map` so that it can only be read with the secret key corresponding to `recipient_public_key`.
+///
+/// The output is made up of the magic bytes `BBSW`, a big-endian `u32` format version, the sender's ephemeral
+/// public key, a random 96 bit nonce and the ciphertext. The header is authenticated along with the witness map.
+pub fn seal_witness_map(
+    witness_map: &BTreeMap<Witness, FieldElement>,
+    recipient_public_key: &[u8; 32],
+) -> Result<Vec<u8>, BackendError> {
+    let recipient_public_key = PublicKey::from(*recipient_public_key);
+    let ephemeral_secret = EphemeralSecret::random_from_rng(OsRng);
+    let ephemeral_public_key = PublicKey::from(&ephemeral_secret);
+    let shared_secret = ephemeral_secret.diffie_hellman(&recipient_public_key);
+    if !shared_secret.was_contributory() {
+        return Err(Error::SealedWitness("recipient public key is invalid".to_owned()).into());
+    }
+    let cipher = cipher(
+        shared_secret.as_bytes(),
+        &ephemeral_public_key,
+        &recipient_public_key,
+    );
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut sealed = Vec::with_capacity(HEADER_BYTES);
+    sealed.extend_from_slice(&framing::SEALED_WITNESS.header());
+    sealed.extend_from_slice(ephemeral_public_key.as_bytes());
+    sealed.extend_from_slice(&nonce);
+
+    let plaintext = Zeroizing::new(witness_map_to_cbor(witness_map));
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &sealed,
+            },
+        )
+        .map_err(|_| Error::SealedWitness("could not encrypt witness map".to_owned()))?;
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Decrypts a witness map produced by [`seal_witness_map`].
+///
+/// `secret_key` is called once to obtain the recipient's secret key, which is wiped from memory after use.
+pub fn unseal_witness_map(
+    sealed: &[u8],
+    secret_key: impl FnOnce() -> [u8; 32],
+) -> Result<SecretWitness, BackendError> {
+    if sealed.len() < HEADER_BYTES {
+        return Err(Error::SealedWitness("sealed witness map is truncated".to_owned()).into());
+    }
+    let (header, ciphertext) = sealed.split_at(HEADER_BYTES);
+    framing::SEALED_WITNESS
+        .check_header(header)
+        .map_err(Error::SealedWitness)?;
+    let (ephemeral_public_key, nonce) = header[framing::HEADER_BYTES..].split_at(PUBLIC_KEY_BYTES);
+    let ephemeral_public_key: [u8; PUBLIC_KEY_BYTES] =
+        ephemeral_public_key.try_into().expect("slice is 32 bytes");
+    let ephemeral_public_key = PublicKey::from(ephemeral_public_key);
+    let nonce = Nonce::from_slice(nonce);
+
+    let mut secret_key_bytes = secret_key();
+    let secret_key = StaticSecret::from(secret_key_bytes);
+    secret_key_bytes.zeroize();
+    let shared_secret = secret_key.diffie_hellman(&ephemeral_public_key);
+
+    let plaintext = cipher(
+        shared_secret.as_bytes(),
+        &ephemeral_public_key,
+        &PublicKey::from(&secret_key),
+    )
+    .decrypt(
+        nonce,
+        Payload {
+            msg: ciphertext,
+            aad: header,
+        },
+    )
+    .map_err(|_| {
+        Error::SealedWitness(
+            "could not unseal witness map, either the key is wrong or the data is corrupted"
+                .to_owned(),
+        )
+    })?;
+    let plaintext = Zeroizing::new(plaintext);
+
+    Ok(SecretWitness::new(witness_map_from_cbor(&plaintext)?))
+}
+
+/// Derives the cipher key from the shared secret, binding it to both parties' public keys.
+fn cipher(
+    shared_secret: &[u8; 32],
+    ephemeral_public_key: &PublicKey,
+    recipient_public_key: &PublicKey,
+) -> ChaCha20Poly1305 {
+    let mut hasher = <Blake2s as Digest>::new();
+    hasher.update(shared_secret);
+    hasher.update(ephemeral_public_key.as_bytes());
+    hasher.update(recipient_public_key.as_bytes());
+    // The key is written straight into wiped memory and borrowed by the cipher, which wipes its own copy when
+    // dropped, so no temporary copies of it are left behind.
+    let mut key = Zeroizing::new([0; 32]);
+    FixedOutput::finalize_into(hasher, Key::from_mut_slice(key.as_mut_slice()));
+    ChaCha20Poly1305::new(Key::from_slice(key.as_slice()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET_KEY: [u8; 32] = [7; 32];
+
+    fn witness_map() -> BTreeMap<Witness, FieldElement> {
+        BTreeMap::from([
+            (Witness(1), FieldElement::from(42_u128)),
+            (Witness(2), -FieldElement::one()),
+        ])
+    }
+
+    #[test]
+    fn sealed_witness_round_trip() -> Result<(), BackendError> {
+        let sealed = seal_witness_map(&witness_map(), &sealing_public_key(&SECRET_KEY))?;
+
+        assert_eq!(*unseal_witness_map(&sealed, || SECRET_KEY)?, witness_map());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_wrong_key_and_tampering() -> Result<(), BackendError> {
+        let sealed = seal_witness_map(&witness_map(), &sealing_public_key(&SECRET_KEY))?;
+
+        assert!(unseal_witness_map(&sealed, || [8; 32]).is_err());
+        for index in [0, 5, 8, HEADER_BYTES - 1, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[index] ^= 1;
+            assert!(unseal_witness_map(&tampered, || SECRET_KEY).is_err());
+        }
+        assert!(unseal_witness_map(&sealed[..HEADER_BYTES - 1], || SECRET_KEY).is_err());
+        Ok(())
+    }
+}
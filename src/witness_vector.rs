@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+use acvm::acir::circuit::Circuit;
+use acvm::acir::native_types::Witness;
+use acvm::FieldElement;
+
+use crate::barretenberg_structures::Assignments;
+use crate::secret::zeroize_field_elements;
+
+/// A witness assignment stored densely by witness index, for circuits with millions of witnesses where a
+/// `BTreeMap<Witness, FieldElement>` spends more memory on its nodes than on the values themselves.
+///
+/// Witnesses beyond the dense range, e.g. those added by a solver after the vector was sized, are kept in a sparse
+/// overlay so that any witness can be assigned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitnessVector {
+    dense: Vec<Option<FieldElement>>,
+    overlay: BTreeMap<Witness, FieldElement>,
+    len: usize,
+}
+
+impl WitnessVector {
+    /// Creates an empty vector with dense storage for the witnesses `_0` to `_{num_witnesses - 1}`.
+    pub fn with_num_witnesses(num_witnesses: u32) -> WitnessVector {
+        WitnessVector {
+            dense: vec![None; num_witnesses as usize],
+            ..WitnessVector::default()
+        }
+    }
+
+    /// Creates an empty vector with dense storage for every witness in `circuit`.
+    pub fn for_circuit(circuit: &Circuit) -> WitnessVector {
+        WitnessVector::with_num_witnesses(circuit.num_vars())
+    }
+
+    /// Copies `witness_map` into a vector with dense storage for every witness in `circuit`.
+    ///
+    /// Witness maps may come from untrusted sources, so the dense range is never sized from their keys;
+    /// any witness outside of the circuit is kept in the overlay.
+    pub fn from_witness_map(
+        circuit: &Circuit,
+        witness_map: &BTreeMap<Witness, FieldElement>,
+    ) -> WitnessVector {
+        WitnessVector::for_circuit(circuit).extended_with(witness_map)
+    }
+
+    fn extended_with(mut self, witness_map: &BTreeMap<Witness, FieldElement>) -> WitnessVector {
+        for (witness, value) in witness_map {
+            self.insert(*witness, *value);
+        }
+        self
+    }
+
+    /// Returns the value assigned to `witness`, if any.
+    pub fn get(&self, witness: Witness) -> Option<FieldElement> {
+        match self.dense.get(witness.witness_index() as usize) {
+            Some(value) => *value,
+            None => self.overlay.get(&witness).copied(),
+        }
+    }
+
+    /// Assigns `value` to `witness`, returning the value it was previously assigned.
+    pub fn insert(&mut self, witness: Witness, value: FieldElement) -> Option<FieldElement> {
+        let previous = match self.dense.get_mut(witness.witness_index() as usize) {
+            Some(slot) => slot.replace(value),
+            None => self.overlay.insert(witness, value),
+        };
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// The number of assigned witnesses.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates over the assigned witnesses in ascending order of witness index.
+    pub fn iter(&self) -> impl Iterator<Item = (Witness, FieldElement)> + '_ {
+        let dense = self
+            .dense
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| value.map(|value| (Witness(index as u32), value)));
+        // The overlay only holds witnesses beyond the dense range so this preserves the ordering.
+        dense.chain(
+            self.overlay
+                .iter()
+                .map(|(witness, value)| (*witness, *value)),
+        )
+    }
+
+    /// Overwrites every assigned value with zero so that secret witness values don't linger in memory.
+    pub fn zeroize(&mut self) {
+        zeroize_field_elements(self.dense.iter_mut().flatten());
+        zeroize_field_elements(self.overlay.values_mut());
+    }
+
+    /// Returns the values of the witnesses `_1` to `_{num_witnesses - 1}` in the order barretenberg expects,
+    /// filling in zero for any witness which hasn't been assigned.
+    pub(crate) fn to_assignments(&self, num_witnesses: u32) -> Assignments {
+        let mut assignments = Assignments::with_capacity(num_witnesses.saturating_sub(1) as usize);
+        assignments.extend_from_witness_range(1..num_witnesses, |witness| self.get(witness));
+        assignments
+    }
+}
+
+impl From<&BTreeMap<Witness, FieldElement>> for WitnessVector {
+    /// Copies `witness_map` into a vector whose dense range is sized by the number of assignments, so that a
+    /// single large witness index can't force a huge allocation. Witnesses beyond that range are kept in the overlay.
+    ///
+    /// Prefer [`WitnessVector::from_witness_map`] where the circuit is known.
+    fn from(witness_map: &BTreeMap<Witness, FieldElement>) -> WitnessVector {
+        let num_witnesses = u32::try_from(witness_map.len())
+            .unwrap_or(u32::MAX)
+            .saturating_add(1);
+        WitnessVector::with_num_witnesses(num_witnesses).extended_with(witness_map)
+    }
+}
+
+impl From<WitnessVector> for BTreeMap<Witness, FieldElement> {
+    fn from(witness_vector: WitnessVector) -> BTreeMap<Witness, FieldElement> {
+        witness_vector.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlay_holds_witnesses_beyond_dense_range() {
+        let mut witness_vector = WitnessVector::with_num_witnesses(4);
+        assert_eq!(
+            witness_vector.insert(Witness(9), FieldElement::from(9_u128)),
+            None
+        );
+        assert_eq!(
+            witness_vector.insert(Witness(2), FieldElement::from(2_u128)),
+            None
+        );
+        assert_eq!(
+            witness_vector.insert(Witness(2), FieldElement::from(3_u128)),
+            Some(FieldElement::from(2_u128))
+        );
+
+        assert_eq!(witness_vector.len(), 2);
+        assert_eq!(witness_vector.get(Witness(1)), None);
+        assert_eq!(
+            witness_vector.iter().collect::<Vec<_>>(),
+            vec![
+                (Witness(2), FieldElement::from(3_u128)),
+                (Witness(9), FieldElement::from(9_u128)),
+            ]
+        );
+        assert_eq!(
+            witness_vector
+                .to_assignments(4)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![
+                FieldElement::zero(),
+                FieldElement::from(3_u128),
+                FieldElement::zero()
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_witness_maps() {
+        let witness_map = BTreeMap::from([
+            (Witness(1), FieldElement::one()),
+            (Witness(5), FieldElement::from(5_u128)),
+        ]);
+
+        let witness_vector = WitnessVector::from(&witness_map);
+
+        assert_eq!(BTreeMap::from(witness_vector), witness_map);
+    }
+
+    #[test]
+    fn large_witness_indices_are_kept_in_the_overlay() {
+        let witness_map = BTreeMap::from([
+            (Witness(1), FieldElement::one()),
+            (Witness(u32::MAX - 1), FieldElement::from(2_u128)),
+            (Witness(u32::MAX), FieldElement::from(3_u128)),
+        ]);
+
+        let witness_vector = WitnessVector::from(&witness_map);
+
+        assert_eq!(witness_vector.dense.len(), 4);
+        assert_eq!(witness_vector.overlay.len(), 2);
+        assert_eq!(BTreeMap::from(witness_vector), witness_map);
+    }
+}
@@ -0,0 +1,136 @@
+//! Checks that this crate's hashes and keys agree byte-for-byte with reference vectors taken from barretenberg.
+//!
+//! Run with `cargo test --features interop-vectors`.
+
+use acvm::FieldElement;
+use acvm_backend_barretenberg::{Barretenberg, InMemoryMerkleTree, SecretScalar};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct PedersenVectors {
+    compress: Vec<PedersenCompressVector>,
+}
+
+#[derive(Deserialize)]
+struct PedersenCompressVector {
+    inputs: Vec<String>,
+    expected: String,
+}
+
+#[derive(Deserialize)]
+struct Blake2sVectors {
+    hash_to_field: Vec<Blake2sVector>,
+}
+
+#[derive(Deserialize)]
+struct Blake2sVector {
+    /// The hex encoded message.
+    input: String,
+    expected: String,
+}
+
+#[derive(Deserialize)]
+struct SchnorrVectors {
+    public_keys: Vec<SchnorrPublicKeyVector>,
+}
+
+#[derive(Deserialize)]
+struct SchnorrPublicKeyVector {
+    private_key: String,
+    expected_x: String,
+    expected_y: String,
+}
+
+#[derive(Deserialize)]
+struct MerkleVectors {
+    empty_leaf: String,
+    roots: Vec<MerkleRootVector>,
+}
+
+#[derive(Deserialize)]
+struct MerkleRootVector {
+    depth: u32,
+    leaves: Vec<String>,
+    expected: String,
+}
+
+fn load<T: serde::de::DeserializeOwned>(json: &str) -> T {
+    serde_json::from_str(json).expect("test vectors should be valid JSON")
+}
+
+fn field(hex: &str) -> FieldElement {
+    FieldElement::from_hex(hex).unwrap_or_else(|| panic!("{hex} is not a field element"))
+}
+
+fn bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("input should be hex"))
+        .collect()
+}
+
+#[test]
+fn pedersen_matches_barretenberg() {
+    let vectors: PedersenVectors = load(include_str!("vectors/pedersen.json"));
+    let bb = Barretenberg::default();
+
+    for vector in vectors.compress {
+        let inputs: Vec<FieldElement> = vector.inputs.iter().map(|input| field(input)).collect();
+        let hash = bb.pedersen_compress(&inputs).unwrap();
+        assert_eq!(hash, field(&vector.expected), "inputs {:?}", vector.inputs);
+    }
+}
+
+#[test]
+fn blake2s_matches_barretenberg() {
+    let vectors: Blake2sVectors = load(include_str!("vectors/blake2s.json"));
+    let bb = Barretenberg::default();
+
+    for vector in vectors.hash_to_field {
+        let hash = bb.blake2s_to_field(&bytes(&vector.input));
+        assert_eq!(hash, field(&vector.expected), "input {:?}", vector.input);
+    }
+}
+
+#[test]
+fn schnorr_public_keys_match_barretenberg() {
+    let vectors: SchnorrVectors = load(include_str!("vectors/schnorr.json"));
+    let bb = Barretenberg::default();
+
+    for vector in vectors.public_keys {
+        let private_key = SecretScalar::from_field(&field(&vector.private_key));
+        let public_key = bb.schnorr_compute_public_key(&private_key).unwrap();
+
+        let (x, y) = public_key.split_at(32);
+        assert_eq!(
+            (
+                FieldElement::from_be_bytes_reduce(x),
+                FieldElement::from_be_bytes_reduce(y)
+            ),
+            (field(&vector.expected_x), field(&vector.expected_y)),
+            "private key {}",
+            vector.private_key
+        );
+    }
+}
+
+#[test]
+fn merkle_roots_match_barretenberg() {
+    let vectors: MerkleVectors = load(include_str!("vectors/merkle.json"));
+
+    let empty_tree = InMemoryMerkleTree::new(1).unwrap();
+    assert_eq!(empty_tree.leaf(0).unwrap(), field(&vectors.empty_leaf));
+
+    for vector in vectors.roots {
+        let mut tree = InMemoryMerkleTree::new(vector.depth).unwrap();
+        for (index, leaf) in vector.leaves.iter().enumerate() {
+            tree.update_leaf(index as u64, field(leaf)).unwrap();
+        }
+        assert_eq!(
+            tree.root(),
+            field(&vector.expected),
+            "leaves {:?}",
+            vector.leaves
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Checks that preprocessing and proving in a worker process use the caller's options.
+//!
+//! The worker re-runs this executable, so this can't use the default test harness, which never calls
+//! `run_subprocess_worker`. Run with `cargo test --features prove-in-subprocess --test subprocess`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, FunctionInput};
+use acvm::acir::circuit::{Circuit, Opcode, PublicInputs};
+use acvm::acir::native_types::{Expression, Witness};
+use acvm::acir::BlackBoxFunc;
+use acvm::{FieldElement, OpcodeResolution};
+use acvm_backend_barretenberg::{run_subprocess_worker, BackendOptions, Barretenberg};
+
+fn range(witness: Witness, num_bits: u32) -> Opcode {
+    Opcode::BlackBoxFuncCall(BlackBoxFuncCall {
+        name: BlackBoxFunc::RANGE,
+        inputs: vec![FunctionInput { witness, num_bits }],
+        outputs: vec![],
+    })
+}
+
+/// Constrains `_2 = _1` with two range constraints on `_1`, which are merged when range constraints are aggregated.
+fn circuit() -> Circuit {
+    Circuit {
+        current_witness_index: 2,
+        opcodes: vec![
+            range(Witness(1), 16),
+            range(Witness(1), 8),
+            Opcode::Arithmetic(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![
+                    (FieldElement::one(), Witness(1)),
+                    (-FieldElement::one(), Witness(2)),
+                ],
+                q_c: FieldElement::zero(),
+            }),
+        ],
+        public_parameters: PublicInputs(BTreeSet::from([Witness(2)])),
+        return_values: PublicInputs::default(),
+    }
+}
+
+fn main() {
+    run_subprocess_worker();
+
+    let circuit = circuit();
+    let witness_values = BTreeMap::from([
+        (Witness(1), FieldElement::from(5_u128)),
+        (Witness(2), FieldElement::from(5_u128)),
+    ]);
+    let public_inputs = BTreeMap::from([(Witness(2), FieldElement::from(5_u128))]);
+
+    let bb = Barretenberg::with_options(BackendOptions {
+        aggregate_range_constraints: true,
+        ..BackendOptions::default()
+    })
+    .unwrap();
+
+    let (proving_key, verification_key) = bb.preprocess_in_subprocess(&circuit).unwrap();
+    let (expected_proving_key, expected_verification_key) = bb.preprocess_keys(&circuit).unwrap();
+    assert!(proving_key == expected_proving_key);
+    assert_eq!(verification_key, expected_verification_key);
+
+    let proof = bb
+        .prove_in_subprocess(&circuit, &witness_values, &proving_key)
+        .unwrap();
+    assert!(bb
+        .verify(&proof, public_inputs, &circuit, &verification_key)
+        .unwrap());
+
+    let bb = Barretenberg::with_options(
+        BackendOptions::default()
+            .with_black_box_override(BlackBoxFunc::AND, |_, _| Ok(OpcodeResolution::Solved)),
+    )
+    .unwrap();
+    assert!(bb.preprocess_in_subprocess(&circuit).is_err());
+
+    println!("subprocess: ok");
+}